@@ -0,0 +1,12 @@
+#version 300 es
+precision highp float;
+
+uniform sampler2D uScene;
+
+in vec2 vTexCoord;
+
+out vec4 color;
+
+void main() {
+    color = texture(uScene, vTexCoord);
+}
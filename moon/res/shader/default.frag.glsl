@@ -9,13 +9,19 @@ uniform sampler2D uTex0;
 uniform sampler2D uTex1;
 uniform vec3 uCamPos;
 uniform vec4 uColor;
+uniform float uLogDepthFC;
 
 in vec2 vPosition;
 in vec2 vTexCoord;
 in vec4 vColor;
+in float vFragDepth;
 
 out vec4 color;
 
 void main() {
+    // Writing the depth disables early depth testing, so it is only done in the logarithmic depth variant
+#ifdef LOG_DEPTH
+    gl_FragDepth = log2(vFragDepth) * uLogDepthFC * 0.5;
+#endif
     color = texture(uTex0, vTexCoord) * vColor * uColor;
 }
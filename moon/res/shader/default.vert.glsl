@@ -9,13 +9,20 @@ uniform mat4 uModel;
 uniform mat4 uView;
 uniform mat4 uProj;
 uniform vec4 uColor;
+uniform float uLogDepthFC;
 
 out vec2 vTexCoord;
 out vec2 vPosition;
 out vec4 vColor;
+out float vFragDepth;
 
 void main() {
     gl_Position = uProj * uView * vec4(aPosition, 0.0, 1.0);
+    // Logarithmic depth, enabled when the coefficient is positive
+    vFragDepth = 1.0 + gl_Position.w;
+    if (uLogDepthFC > 0.0) {
+        gl_Position.z = (log2(max(1e-6, vFragDepth)) * uLogDepthFC - 1.0) * gl_Position.w;
+    }
     vTexCoord = aTexCoord;
     vColor = aColor;
 }
@@ -0,0 +1,41 @@
+#version 300 es
+
+layout (location = 0) in vec2 aPosition;
+layout (location = 1) in vec2 aTexCoord;
+
+// Per-instance attributes
+layout (location = 3) in vec2 aInstancePosition;
+layout (location = 4) in vec2 aInstanceSize;
+layout (location = 5) in float aInstanceRotation;
+layout (location = 6) in vec4 aInstanceUV;
+layout (location = 7) in vec4 aInstanceColor;
+
+uniform float uTime;
+uniform mat4 uView;
+uniform mat4 uProj;
+uniform vec4 uColor;
+uniform float uLogDepthFC;
+// UV co-ordinates of the sprite, as (minU, minV, maxU, maxV)
+uniform vec4 uSpriteRect;
+
+out vec2 vTexCoord;
+out vec2 vPosition;
+out vec4 vColor;
+out float vFragDepth;
+
+void main() {
+    float s = sin(aInstanceRotation);
+    float c = cos(aInstanceRotation);
+    vec2 local = aPosition * aInstanceSize;
+    vec2 world = aInstancePosition + vec2(local.x * c - local.y * s, local.x * s + local.y * c);
+    gl_Position = uProj * uView * vec4(world, 0.0, 1.0);
+    // Logarithmic depth, enabled when the coefficient is positive
+    vFragDepth = 1.0 + gl_Position.w;
+    if (uLogDepthFC > 0.0) {
+        gl_Position.z = (log2(max(1e-6, vFragDepth)) * uLogDepthFC - 1.0) * gl_Position.w;
+    }
+    // The instance UV region is (minU, maxU, minV, maxV), relative to the sprite
+    vec2 region = mix(aInstanceUV.xz, aInstanceUV.yw, aTexCoord);
+    vTexCoord = mix(uSpriteRect.xy, uSpriteRect.zw, region);
+    vColor = aInstanceColor;
+}
@@ -0,0 +1,14 @@
+#version 300 es
+precision highp float;
+
+in vec2 vTexCoord;
+in vec4 vColor;
+
+out vec4 color;
+
+void main() {
+    // Quadratic falloff from the center of the quad to its inscribed circle
+    float falloff = clamp(1.0 - length(vTexCoord * 2.0 - 1.0), 0.0, 1.0);
+    falloff *= falloff;
+    color = vec4(vColor.rgb * vColor.a * falloff, 1.0);
+}
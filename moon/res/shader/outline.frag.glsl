@@ -0,0 +1,32 @@
+#version 300 es
+precision highp float;
+
+uniform sampler2D uTex0;
+uniform vec4 uOutlineColor;
+uniform float uOutlineThickness;
+uniform vec2 uTexelSize;
+// The UV rectangle of the sprite, as the minimum and maximum corners
+uniform vec4 uUVRect;
+
+in vec2 vTexCoord;
+
+out vec4 color;
+
+// Sample the alpha of the sprite, treating texels outside of its UV rectangle as transparent
+float spriteAlpha(vec2 uv) {
+    if (any(lessThan(uv, uUVRect.xy)) || any(greaterThan(uv, uUVRect.zw))) {
+        return 0.0;
+    }
+    return texture(uTex0, uv).a;
+}
+
+void main() {
+    // Dilate the alpha of the sprite by sampling around each texel
+    float alpha = spriteAlpha(vTexCoord);
+    for (int i = 0; i < 8; i++) {
+        float angle = float(i) * 0.7853982;
+        vec2 offset = vec2(cos(angle), sin(angle)) * uTexelSize * uOutlineThickness;
+        alpha = max(alpha, spriteAlpha(vTexCoord + offset));
+    }
+    color = vec4(uOutlineColor.rgb, uOutlineColor.a * alpha);
+}
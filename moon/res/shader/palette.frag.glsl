@@ -0,0 +1,31 @@
+#version 300 es
+precision highp float;
+
+uniform float uTime;
+uniform mat4 uModel;
+uniform mat4 uView;
+uniform mat4 uProj;
+uniform sampler2D uTex0;
+uniform sampler2D uPalette;
+uniform vec3 uCamPos;
+uniform vec4 uColor;
+uniform float uLogDepthFC;
+
+in vec2 vPosition;
+in vec2 vTexCoord;
+in vec4 vColor;
+in float vFragDepth;
+
+out vec4 color;
+
+void main() {
+    // Writing the depth disables early depth testing, so it is only done in the logarithmic depth variant
+#ifdef LOG_DEPTH
+    gl_FragDepth = log2(vFragDepth) * uLogDepthFC * 0.5;
+#endif
+    // The red channel of the sprite is used as an index into the palette
+    vec4 indexed = texture(uTex0, vTexCoord);
+    int index = int(indexed.r * 255.0 + 0.5);
+    vec4 paletteColor = texelFetch(uPalette, ivec2(index, 0), 0);
+    color = vec4(paletteColor.rgb, paletteColor.a * indexed.a) * vColor * uColor;
+}
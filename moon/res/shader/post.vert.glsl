@@ -0,0 +1,10 @@
+#version 300 es
+
+out vec2 vTexCoord;
+
+void main() {
+    // A single triangle covering the whole screen, generated from the vertex ID
+    vec2 position = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+    vTexCoord = position;
+    gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+}
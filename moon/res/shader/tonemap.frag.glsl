@@ -0,0 +1,19 @@
+#version 300 es
+precision highp float;
+
+uniform sampler2D uScene;
+uniform float uExposure;
+
+in vec2 vTexCoord;
+
+out vec4 color;
+
+// Narkowicz's approximation of the ACES filmic curve
+vec3 aces(vec3 x) {
+    return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), 0.0, 1.0);
+}
+
+void main() {
+    vec4 scene = texture(uScene, vTexCoord);
+    color = vec4(aces(scene.rgb * uExposure), 1.0);
+}
@@ -0,0 +1,157 @@
+//! The [`Bindings`] struct and [`Action`] enum, used to remap keys and gamepad buttons to logical actions.
+
+use std::collections::BTreeMap;
+
+use crate::input::{InputManager, Key};
+use crate::Vec2;
+
+/// Index of the horizontal axis of the left stick, in the standard gamepad mapping.
+const LEFT_STICK_X: u32 = 0;
+/// Index of the vertical axis of the left stick, in the standard gamepad mapping. It points down.
+const LEFT_STICK_Y: u32 = 1;
+
+/// A logical action that a [`Key`] or gamepad button can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    /// Move to the left.
+    MoveLeft,
+    /// Move to the right.
+    MoveRight,
+    /// Move up.
+    MoveUp,
+    /// Move down.
+    MoveDown,
+    /// Jump.
+    Jump,
+}
+
+/// A mapping of [`Actions`](Action) to [`Keys`](Key) and gamepad buttons.
+///
+/// Gamepad buttons use the indices of the [standard mapping](https://w3c.github.io/gamepad/#remapping), and the left stick also moves.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::bindings::{Action, Bindings};
+/// use moon_engine::input::{InputManager, Key};
+///
+/// let mut bindings = Bindings::default();
+/// bindings.bind(Action::Jump, Key::W);
+///
+/// let mut input = InputManager::new();
+/// input.key_down(Key::W);
+///
+/// assert!(bindings.is_action_active(&input, Action::Jump));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    keys: BTreeMap<Action, Key>,
+    buttons: BTreeMap<Action, u32>,
+}
+
+impl Default for Bindings {
+    /// The default [`Bindings`], using WASD to move and Space to jump.
+    ///
+    /// On a gamepad, the D-pad moves and the bottom face button (`0`) jumps.
+    fn default() -> Self {
+        let keys = BTreeMap::from([
+            (Action::MoveLeft, Key::A),
+            (Action::MoveRight, Key::D),
+            (Action::MoveUp, Key::W),
+            (Action::MoveDown, Key::S),
+            (Action::Jump, Key::Space),
+        ]);
+        let buttons = BTreeMap::from([
+            (Action::MoveLeft, 14),
+            (Action::MoveRight, 15),
+            (Action::MoveUp, 12),
+            (Action::MoveDown, 13),
+            (Action::Jump, 0),
+        ]);
+        Self { keys, buttons }
+    }
+}
+
+impl Bindings {
+    /// Create new [`Bindings`] with no [`Actions`](Action) bound.
+    pub fn new() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+            buttons: BTreeMap::new(),
+        }
+    }
+
+    /// Bind an [`Action`] to a [`Key`], replacing any previous binding.
+    pub fn bind(&mut self, action: Action, key: Key) {
+        self.keys.insert(action, key);
+    }
+
+    /// Remove the [`Key`] binding of an [`Action`].
+    pub fn unbind(&mut self, action: Action) {
+        self.keys.remove(&action);
+    }
+
+    /// Get the [`Key`] bound to an [`Action`], if any.
+    pub fn get_key(&self, action: Action) -> Option<Key> {
+        self.keys.get(&action).copied()
+    }
+
+    /// Bind an [`Action`] to a gamepad button, replacing any previous button binding.
+    pub fn bind_button(&mut self, action: Action, button: u32) {
+        self.buttons.insert(action, button);
+    }
+
+    /// Remove the gamepad button binding of an [`Action`].
+    pub fn unbind_button(&mut self, action: Action) {
+        self.buttons.remove(&action);
+    }
+
+    /// Get the gamepad button bound to an [`Action`], if any.
+    pub fn get_button(&self, action: Action) -> Option<u32> {
+        self.buttons.get(&action).copied()
+    }
+
+    /// Check if the [`Key`] or gamepad button bound to an [`Action`] is pressed.
+    ///
+    /// Returns false if the [`Action`] is not bound.
+    pub fn is_action_active(&self, input: &InputManager, action: Action) -> bool {
+        let key_pressed = self
+            .get_key(action)
+            .map(|key| input.get_key_state(key))
+            .unwrap_or(false);
+        let button_pressed = self
+            .get_button(action)
+            .zip(input.gamepad())
+            .map(|(button, gamepad)| gamepad.get_button(button))
+            .unwrap_or(false);
+        key_pressed || button_pressed
+    }
+
+    /// Get the direction held with the movement [`Actions`](Action) and the left stick of the gamepad as a [`Vec2`].
+    ///
+    /// The `X` component is `MoveRight - MoveLeft` and the `Y` component is `MoveUp - MoveDown`, plus the stick pointing right and up.
+    /// A direction longer than `1.0` is normalized, so a partly tilted stick gives a shorter direction.
+    pub fn movement_axis(&self, input: &InputManager) -> Vec2 {
+        let mut axis = Vec2::new(
+            self.axis(input, Action::MoveLeft, Action::MoveRight),
+            self.axis(input, Action::MoveDown, Action::MoveUp),
+        );
+        if let Some(gamepad) = input.gamepad() {
+            axis += Vec2::new(
+                gamepad.get_axis(LEFT_STICK_X),
+                -gamepad.get_axis(LEFT_STICK_Y),
+            );
+        }
+        if axis.norm() > 1.0 {
+            axis.normalize()
+        } else {
+            axis
+        }
+    }
+
+    /// Get the difference between the states of a positive and a negative [`Action`].
+    fn axis(&self, input: &InputManager, negative: Action, positive: Action) -> f32 {
+        self.is_action_active(input, positive) as i32 as f32
+            - self.is_action_active(input, negative) as i32 as f32
+    }
+}
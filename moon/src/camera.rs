@@ -1,23 +1,78 @@
 //! The [`Camera`] struct.
 
+use crate::collider::AABB;
+use crate::input::{InputManager, MouseButton};
 use crate::transform::Transform;
 use crate::Mat4;
 use crate::Ortho;
+use crate::Perspective;
+use crate::Random;
+use crate::Vec2;
 use crate::Vec3;
+use crate::Vec4;
 
 /// The 'X' component at the left and right edges of the screen
 pub const FIXED_WIDTH: f32 = 20.0;
 /// Calculate the height from the `FIXED_WIDTH` to maintain 16:9 Aspect ratio
 pub const FIXED_HEIGHT: f32 = FIXED_WIDTH / 1.77;
 
-/// A [`Camera`] represents a Virtual Camera, that has a view and Orthographic projection matrices
+/// The smallest zoom factor a [`Camera`] can have.
+pub const MIN_ZOOM: f32 = 0.01;
+
+/// The largest positional offset of a shaking [`Camera`], in world units.
+pub const MAX_SHAKE_OFFSET: f32 = 0.5;
+/// The largest rotational offset of a shaking [`Camera`], in radians.
+pub const MAX_SHAKE_ANGLE: f32 = 0.1;
+/// The amount of trauma a shaking [`Camera`] loses per second.
+pub const SHAKE_DECAY: f32 = 1.0;
+
+/// The kind of projection used by a [`Camera`], along with its parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectionKind {
+    /// An orthographic projection, without perspective distortion. Used for 2D scenes.
+    Orthographic(Ortho),
+    /// A perspective projection.
+    Perspective(Perspective),
+}
+
+impl ProjectionKind {
+    /// Get the projection matrix as a [`Mat4`].
+    pub fn as_matrix(&self) -> &Mat4 {
+        match self {
+            ProjectionKind::Orthographic(orthographic) => orthographic.as_matrix(),
+            ProjectionKind::Perspective(perspective) => perspective.as_matrix(),
+        }
+    }
+}
+
+/// Where the world origin is placed in the view of a 2D [`Camera`], see [`Camera::new_2d_with_origin`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CameraOrigin {
+    /// The origin is at the center of the view.
+    #[default]
+    Center,
+    /// The origin is at the bottom-left corner of the view, so the visible area has positive co-ordinates.
+    BottomLeft,
+}
+
+/// A [`Camera`] represents a Virtual Camera, that has a view and a projection matrix.
+///
+/// The projection is orthographic by default, see [`ProjectionKind`].
 #[derive(Debug)]
 pub struct Camera {
     /// [`Transform`] for the Camera
     pub transform: Transform,
-    orthographic: Ortho,
+    projection: ProjectionKind,
+    origin: CameraOrigin,
     width: f32,
     height: f32,
+    zoom: f32,
+    bounds: Option<AABB>,
+    view: Option<Mat4>,
+    trauma: f32,
+    shake_intensity: f32,
+    shake_offset: Vec2,
+    shake_angle: f32,
 }
 
 impl Default for Camera {
@@ -26,14 +81,22 @@ impl Default for Camera {
             transform: Transform::new(),
             width: FIXED_WIDTH,
             height: FIXED_HEIGHT,
-            orthographic: Ortho::new(
+            origin: CameraOrigin::Center,
+            zoom: 1.0,
+            bounds: None,
+            view: None,
+            trauma: 0.0,
+            shake_intensity: 1.0,
+            shake_offset: Vec2::zeros(),
+            shake_angle: 0.0,
+            projection: ProjectionKind::Orthographic(Ortho::new(
                 -FIXED_WIDTH / 2.0,
                 FIXED_WIDTH / 2.0,
                 FIXED_HEIGHT / 2.0,
                 -FIXED_HEIGHT / 2.0,
                 0f32,
                 1000.0f32,
-            ),
+            )),
         }
     }
 }
@@ -62,14 +125,74 @@ impl Camera {
         Self {
             width,
             height,
-            orthographic: Ortho::new(
+            projection: ProjectionKind::Orthographic(Ortho::new(
                 -width / 2.0,
                 width / 2.0,
                 height / 2.0,
                 -height / 2.0,
                 0f32,
                 1000.0f32,
-            ),
+            )),
+            ..Default::default()
+        }
+    }
+    /// Create a new 2D `Camera`, with an orthographic projection showing `width` by `height` world units around the origin.
+    ///
+    /// The `Y` axis points up the screen, unlike [`Camera::new`] and [`Camera::with_width_and_height`] where it points down like screen co-ordinates.
+    /// One world unit is the same size on both axes if the width and height match the aspect ratio of the canvas.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::camera::Camera;
+    ///
+    /// let camera = Camera::new_2d(16.0, 9.0);
+    /// let (x, y) = camera.screen_to_world_coordinates(16.0, 0.0);
+    ///
+    /// assert_eq!((x, y), (8.0, 4.5));
+    /// ```
+    pub fn new_2d(width: f32, height: f32) -> Self {
+        Self::new_2d_with_origin(width, height, CameraOrigin::Center)
+    }
+    /// Create a new 2D `Camera` like [`Camera::new_2d`], with the world origin placed at a [`CameraOrigin`].
+    ///
+    /// With [`CameraOrigin::BottomLeft`], the view spans `0.0..width` horizontally and `0.0..height` vertically.
+    pub fn new_2d_with_origin(width: f32, height: f32, origin: CameraOrigin) -> Self {
+        let (left, bottom) = match origin {
+            CameraOrigin::Center => (-width / 2.0, -height / 2.0),
+            CameraOrigin::BottomLeft => (0.0, 0.0),
+        };
+        Self {
+            origin,
+            ..Self::new_orthographic(left, left + width, bottom, bottom + height, 0f32, 1000.0f32)
+        }
+    }
+    /// Get where the world origin is placed in the view of the `Camera`.
+    pub fn origin(&self) -> CameraOrigin {
+        self.origin
+    }
+    /// Create a new `Camera` with an orthographic projection, using the given clipping planes.
+    pub fn new_orthographic(
+        left: f32,
+        right: f32,
+        bottom: f32,
+        top: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        Self {
+            width: right - left,
+            height: (top - bottom).abs(),
+            projection: ProjectionKind::Orthographic(Ortho::new(
+                left, right, bottom, top, near, far,
+            )),
+            ..Default::default()
+        }
+    }
+    /// Create a new `Camera` with a perspective projection, using an aspect ratio, vertical field of view in radians, and clipping planes.
+    pub fn new_perspective(aspect: f32, fovy: f32, near: f32, far: f32) -> Self {
+        Self {
+            projection: ProjectionKind::Perspective(Perspective::new(aspect, fovy, near, far)),
             ..Default::default()
         }
     }
@@ -80,23 +203,402 @@ impl Camera {
         self.height = height;
     }
 
+    /// Set the aspect ratio of the `Camera`, and update the Projection Matrix to match.
+    ///
+    /// An orthographic projection keeps its vertical extent, and adjusts its horizontal extent around its center.
+    /// With [`CameraOrigin::BottomLeft`], the left edge is kept instead, so the origin stays in the corner.
+    pub fn set_aspect_ratio(&mut self, aspect: f32) {
+        if !(aspect.is_finite() && aspect > 0.0) {
+            return;
+        }
+        match &mut self.projection {
+            ProjectionKind::Orthographic(orthographic) => {
+                let width = (orthographic.top() - orthographic.bottom()).abs() * aspect;
+                let left = match self.origin {
+                    CameraOrigin::Center => {
+                        (orthographic.left() + orthographic.right() - width) / 2.0
+                    }
+                    CameraOrigin::BottomLeft => orthographic.left(),
+                };
+                orthographic.set_left_and_right(left, left + width);
+            }
+            ProjectionKind::Perspective(perspective) => perspective.set_aspect(aspect),
+        }
+    }
+
+    /// Set the vertical field of view of a perspective `Camera`, in degrees.
+    ///
+    /// Values outside `0.0..180.0`, and orthographic projections, are ignored.
+    pub fn set_fov(&mut self, degrees: f32) {
+        if let ProjectionKind::Perspective(perspective) = &mut self.projection {
+            if degrees > 0.0 && degrees < 180.0 {
+                perspective.set_fovy(degrees.to_radians());
+            }
+        }
+    }
+
+    /// Set the distance to the near clipping plane of the `Camera`.
+    ///
+    /// The near plane must be closer than the far plane, and in front of a perspective `Camera`, otherwise it is ignored.
+    pub fn set_near(&mut self, near: f32) {
+        if near >= self.far() {
+            return;
+        }
+        match &mut self.projection {
+            ProjectionKind::Orthographic(orthographic) => orthographic.set_znear(near),
+            ProjectionKind::Perspective(perspective) if near > 0.0 => perspective.set_znear(near),
+            ProjectionKind::Perspective(_) => {}
+        }
+    }
+
+    /// Set the distance to the far clipping plane of the `Camera`.
+    ///
+    /// The far plane must be further than the near plane, otherwise it is ignored.
+    pub fn set_far(&mut self, far: f32) {
+        if far <= self.near() {
+            return;
+        }
+        match &mut self.projection {
+            ProjectionKind::Orthographic(orthographic) => orthographic.set_zfar(far),
+            ProjectionKind::Perspective(perspective) => perspective.set_zfar(far),
+        }
+    }
+
     /// Return the Projection Matrix of the `Camera` as a slice of `f32` so it can be used by WebGL.
     pub fn projection(&self) -> &[f32] {
-        self.orthographic.as_matrix().as_slice()
+        self.projection.as_matrix().as_slice()
+    }
+
+    /// Get the [`ProjectionKind`] of the `Camera`.
+    pub fn projection_kind(&self) -> &ProjectionKind {
+        &self.projection
+    }
+
+    /// Get the distance to the near clipping plane of the `Camera`.
+    pub fn near(&self) -> f32 {
+        match &self.projection {
+            ProjectionKind::Orthographic(orthographic) => orthographic.znear(),
+            ProjectionKind::Perspective(perspective) => perspective.znear(),
+        }
+    }
+
+    /// Get the distance to the far clipping plane of the `Camera`.
+    pub fn far(&self) -> f32 {
+        match &self.projection {
+            ProjectionKind::Orthographic(orthographic) => orthographic.zfar(),
+            ProjectionKind::Perspective(perspective) => perspective.zfar(),
+        }
+    }
+
+    /// Return the view matrix of the `Camera` as a [`Mat4`].
+    ///
+    /// This is the custom view matrix if one is set, or the inverse of the matrix of its [`Transform`].
+    /// Any shake offset is applied on top.
+    pub fn view_matrix(&self) -> Mat4 {
+        let view = self.view.unwrap_or_else(|| {
+            self.transform
+                .matrix()
+                .try_inverse()
+                .unwrap_or_else(Mat4::identity)
+        });
+        if self.shake_offset == Vec2::zeros() && self.shake_angle == 0.0 {
+            return view;
+        }
+        let shake =
+            Mat4::new_translation(&Vec3::new(self.shake_offset.x, self.shake_offset.y, 0.0))
+                * Mat4::new_rotation(Vec3::new(0.0, 0.0, self.shake_angle));
+        shake.try_inverse().unwrap_or_else(Mat4::identity) * view
+    }
+
+    /// Set a custom view matrix, bypassing the [`Transform`] of the `Camera`.
+    ///
+    /// The position of the [`Transform`] is kept in sync if the matrix is invertible. Moving the `Camera` with [`drag_pan`](Camera::drag_pan) or [`set_bounds`](Camera::set_bounds) clears the custom view matrix.
+    pub fn set_view(&mut self, view: Mat4) {
+        if let Some(world) = view.try_inverse() {
+            self.transform
+                .set_position(Vec3::new(world.m14, world.m24, world.m34));
+        }
+        self.view = Some(view);
+    }
+
+    /// Clear the custom view matrix, so that the view follows the [`Transform`] of the `Camera` again.
+    pub fn clear_view(&mut self) {
+        self.view = None;
+    }
+
+    /// Check if a custom view matrix is set.
+    pub fn has_custom_view(&self) -> bool {
+        self.view.is_some()
     }
 
     /// Return the calculated and combined view-projection matrix as a [`Mat4`].
     pub fn view_projection_matrix(&self) -> Mat4 {
-        self.transform.matrix() * self.orthographic.as_matrix()
+        self.projection.as_matrix() * self.view_matrix()
+    }
+
+    /// Get the forward direction of the `Camera` in world space.
+    ///
+    /// The `Camera` looks down its negative `Z` axis.
+    pub fn forward(&self) -> Vec3 {
+        let view_inverse = self
+            .view_matrix()
+            .try_inverse()
+            .unwrap_or_else(Mat4::identity);
+        (view_inverse * Vec4::new(0.0, 0.0, -1.0, 0.0))
+            .xyz()
+            .normalize()
+    }
+
+    /// Convert a point in normalized device co-ordinates to a world-space ray.
+    ///
+    /// Returns the origin of the ray on the near plane, and its normalized direction towards the far plane.
+    /// For an orthographic projection, the direction is always [`forward`](Camera::forward), and the origin moves with the point.
+    pub fn screen_to_world_ray(&self, ndc: Vec2) -> (Vec3, Vec3) {
+        let inverse = self
+            .view_projection_matrix()
+            .try_inverse()
+            .unwrap_or_else(Mat4::identity);
+        let unproject = |depth: f32| {
+            let point = inverse * Vec4::new(ndc.x, ndc.y, depth, 1.0);
+            point.xyz() / point.w
+        };
+        let near = unproject(-1.0);
+        let far = unproject(1.0);
+        (near, (far - near).normalize())
+    }
+
+    /// Project a point in world space to pixel co-ordinates in a viewport, such as to place HTML elements over it.
+    ///
+    /// The origin of the pixel co-ordinates is the top-left corner of the viewport, with `Y` pointing down.
+    /// Returns [`None`] if the point is behind a perspective `Camera`. Points outside the view are not clamped.
+    /// This is the inverse of [`screen_to_world_ray`](Camera::screen_to_world_ray).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::camera::Camera;
+    /// use moon_engine::{Vec2, Vec3};
+    ///
+    /// let camera = Camera::new_2d(16.0, 9.0);
+    /// let screen = camera.world_to_screen(Vec3::new(8.0, 4.5, 0.0), Vec2::new(1600.0, 900.0));
+    ///
+    /// assert_eq!(screen, Some(Vec2::new(1600.0, 0.0)));
+    /// ```
+    pub fn world_to_screen(&self, world: Vec3, viewport: Vec2) -> Option<Vec2> {
+        let clip = self.view_projection_matrix() * Vec4::new(world.x, world.y, world.z, 1.0);
+        if clip.w <= 0.0 {
+            return None;
+        }
+        let ndc = clip.xy() / clip.w;
+        Some(Vec2::new(
+            (ndc.x + 1.0) / 2.0 * viewport.x,
+            (1.0 - ndc.y) / 2.0 * viewport.y,
+        ))
+    }
+
+    /// Get the zoom factor of the `Camera`.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Set the zoom factor of the `Camera`, and scale the Projection Matrix to match.
+    ///
+    /// A zoom factor above `1.0` magnifies the view. The zoom factor is clamped to at least [`MIN_ZOOM`].
+    pub fn set_zoom(&mut self, zoom: f32) {
+        let zoom = zoom.max(MIN_ZOOM);
+        let scale = self.zoom / zoom;
+        match &mut self.projection {
+            ProjectionKind::Orthographic(orthographic) => {
+                let (left, right) = (orthographic.left(), orthographic.right());
+                let (bottom, top) = (orthographic.bottom(), orthographic.top());
+                orthographic.set_left_and_right(left * scale, right * scale);
+                orthographic.set_bottom_and_top(bottom * scale, top * scale);
+            }
+            ProjectionKind::Perspective(perspective) => {
+                let fovy = 2.0 * ((perspective.fovy() / 2.0).tan() * scale).atan();
+                perspective.set_fovy(fovy);
+            }
+        }
+        self.zoom = zoom;
+    }
+
+    /// Change the zoom factor of the `Camera` by a delta, such as from the scroll wheel.
+    ///
+    /// The zoom factor is clamped to at least [`MIN_ZOOM`].
+    pub fn zoom_by(&mut self, delta: f32) {
+        self.set_zoom(self.zoom + delta);
+    }
+
+    /// Zoom the `Camera` by a delta, keeping a world-space point, such as the one under the cursor, fixed on the screen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::camera::Camera;
+    /// use moon_engine::Vec2;
+    ///
+    /// let mut camera = Camera::new();
+    /// camera.zoom_at(1.0, Vec2::new(4.0, 0.0));
+    ///
+    /// assert_eq!(camera.zoom(), 2.0);
+    /// assert_eq!(camera.transform.position().x, 2.0);
+    /// ```
+    pub fn zoom_at(&mut self, zoom_delta: f32, cursor_world: Vec2) {
+        let old_zoom = self.zoom;
+        self.zoom_by(zoom_delta);
+
+        let position = self.transform.position();
+        let offset = Vec2::new(position.x, position.y) - cursor_world;
+        let offset = offset * (old_zoom / self.zoom);
+        self.set_position_within_bounds(Vec3::new(
+            cursor_world.x + offset.x,
+            cursor_world.y + offset.y,
+            position.z,
+        ));
+    }
+
+    /// Get the bounds of the `Camera`, if any.
+    pub fn bounds(&self) -> Option<&AABB> {
+        self.bounds.as_ref()
+    }
+
+    /// Set the bounds that the position of the `Camera` is kept within.
+    ///
+    /// Passing [`None`] allows the `Camera` to move freely.
+    pub fn set_bounds(&mut self, bounds: Option<AABB>) {
+        self.bounds = bounds;
+        self.set_position_within_bounds(self.transform.position());
+    }
+
+    /// Pan the `Camera` while the middle mouse button is held, using the state of an [`InputManager`].
+    ///
+    /// The mouse delta is converted from pixels to world units, so that the content under the cursor follows it at any zoom.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::camera::Camera;
+    /// use moon_engine::input::{InputManager, MouseButton};
+    /// use moon_engine::Vec3;
+    ///
+    /// let mut camera = Camera::new();
+    /// let mut input = InputManager::new();
+    /// input.mouse_button_down(MouseButton::Middle);
+    /// input.set_mouse_position(0.0, 0.0);
+    /// input.set_mouse_position(10.0, 0.0);
+    /// camera.drag_pan(&input);
+    ///
+    /// assert_eq!(camera.transform.position(), Vec3::new(-10.0, 0.0, 0.0));
+    /// ```
+    pub fn drag_pan(&mut self, input: &InputManager) {
+        if !input.get_mouse_button_state(MouseButton::Middle) {
+            return;
+        }
+        let delta = self.screen_to_world_delta(input.get_mouse_delta());
+        let position = self.transform.position() - Vec3::new(delta.x, delta.y, 0.0);
+        self.set_position_within_bounds(position);
+    }
+
+    /// Convert a movement in screen pixels, such as the mouse delta, to a movement in world units.
+    ///
+    /// For a perspective projection, the movement is measured on the near plane.
+    pub fn screen_to_world_delta(&self, delta: Vec2) -> Vec2 {
+        match &self.projection {
+            ProjectionKind::Orthographic(orthographic) => Vec2::new(
+                delta.x * (orthographic.right() - orthographic.left()) / self.width,
+                delta.y * (orthographic.bottom() - orthographic.top()) / self.height,
+            ),
+            ProjectionKind::Perspective(_) => {
+                let (start_x, start_y) = self.screen_to_world_coordinates(0.0, 0.0);
+                let (end_x, end_y) = self.screen_to_world_coordinates(delta.x, delta.y);
+                Vec2::new(end_x - start_x, end_y - start_y)
+            }
+        }
+    }
+
+    /// Add trauma to the `Camera`, making it shake. The trauma is clamped to `0.0..=1.0`.
+    ///
+    /// The shake is stronger with more trauma, and stops once it decays to zero in [`update_shake`](Camera::update_shake).
+    pub fn add_shake(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).clamp(0.0, 1.0);
+    }
+
+    /// Set the factor the shake offsets are scaled by. Defaults to `1.0`.
+    pub fn set_shake_intensity(&mut self, intensity: f32) {
+        self.shake_intensity = intensity.max(0.0);
+    }
+
+    /// Get the current trauma of the `Camera`.
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Get the current positional offset of the shake.
+    pub fn shake_offset(&self) -> Vec2 {
+        self.shake_offset
+    }
+
+    /// Decay the trauma of the `Camera` linearly, and pick a new random shake offset.
+    ///
+    /// The offset is only applied to the view matrix, so the position of the `Camera` does not drift. Should be called once per frame.
+    pub fn update_shake(&mut self, delta_time: f32) {
+        self.trauma = (self.trauma - SHAKE_DECAY * delta_time).max(0.0);
+        if self.trauma <= 0.0 {
+            self.shake_offset = Vec2::zeros();
+            self.shake_angle = 0.0;
+            return;
+        }
+        // Squaring the trauma makes small amounts of it more subtle
+        let shake = self.trauma * self.trauma * self.shake_intensity;
+        self.shake_offset = Vec2::random_range(Vec2::new(-1.0, -1.0), Vec2::new(1.0, 1.0))
+            * MAX_SHAKE_OFFSET
+            * shake;
+        self.shake_angle = f32::random_range(-1.0, 1.0) * MAX_SHAKE_ANGLE * shake;
+    }
+
+    /// Move the `Camera` towards a target position with exponential smoothing, such as to follow a player.
+    ///
+    /// The smoothing is a time constant in seconds: a smoothing of `0.0` snaps to the target instantly, and higher values lag further behind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::camera::Camera;
+    /// use moon_engine::Vec3;
+    ///
+    /// let mut camera = Camera::new();
+    /// camera.follow(Vec3::new(5.0, 2.0, 0.0), 0.0, 0.016);
+    ///
+    /// assert_eq!(camera.transform.position(), Vec3::new(5.0, 2.0, 0.0));
+    /// ```
+    pub fn follow(&mut self, target: Vec3, smoothing: f32, delta_time: f32) {
+        let position = if smoothing <= 0.0 {
+            target
+        } else {
+            let t = 1.0 - (-delta_time / smoothing).exp();
+            self.transform.position().lerp(&target, t)
+        };
+        self.set_position_within_bounds(position);
+    }
+
+    /// Set the position of the `Camera`, clamped to its bounds if set.
+    fn set_position_within_bounds(&mut self, mut position: Vec3) {
+        if let Some(bounds) = &self.bounds {
+            position.x = position.x.clamp(bounds.min.x, bounds.max.x);
+            position.y = position.y.clamp(bounds.min.y, bounds.max.y);
+        }
+        self.transform.set_position(position);
+        self.view = None;
     }
 
     /// Get a position in screen co-ordinates to a range within the world.
     ///
-    /// This works by first converting it into a `-1.0 to 1.0` range, and then multiplying its components by the [`FIXED_WIDTH`] and [`FIXED_HEIGHT`].
+    /// This works by first converting it into a `-1.0 to 1.0` range, and then unprojecting it onto the near plane with [`screen_to_world_ray`](Camera::screen_to_world_ray).
     pub fn screen_to_world_coordinates(&self, screen_x: f32, screen_y: f32) -> (f32, f32) {
-        let clipped_x = screen_x / self.width - 0.5;
-        let clipped_y = screen_y / self.height - 0.5;
+        let clipped_x = screen_x / self.width * 2.0 - 1.0;
+        let clipped_y = 1.0 - screen_y / self.height * 2.0;
 
-        (clipped_x * FIXED_WIDTH, clipped_y * FIXED_HEIGHT)
+        let (origin, _) = self.screen_to_world_ray(Vec2::new(clipped_x, clipped_y));
+        (origin.x, origin.y)
     }
 }
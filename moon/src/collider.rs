@@ -1,7 +1,10 @@
 //! Definition of the [`Collider`] and [`Collide`] traits, as well as simple Colliders.
 
+use std::collections::BTreeMap;
+
 use crate::clamp;
 use crate::Point;
+use crate::Vec3;
 
 /// Default bounding box size for a [`Point`].
 pub const POINT_BOUNDING_SIZE: f32 = 0.1;
@@ -24,8 +27,8 @@ pub trait Collide<T: Collider> {
     /// # Examples
     ///
     /// ```
-    /// # use moon::Point;
-    /// # use moon::collider::{Collide, Circle};
+    /// # use moon_engine::Point;
+    /// # use moon_engine::collider::{Collide, Circle};
     /// let a = Point::new(0.0, 0.5);
     /// let b = Circle::new_size(0.1);
     ///
@@ -35,7 +38,7 @@ pub trait Collide<T: Collider> {
 }
 
 /// An Axis-Aligned Bounding Box (AABB).
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct AABB {
     /// The lowest point on the X and Y axes of the [`AABB`].
     pub min: Point,
@@ -77,6 +80,169 @@ impl AABB {
             max: position + half,
         }
     }
+
+    /// Checks if two [`AABBs`](AABB) overlap.
+    ///
+    /// [`AABBs`](AABB) that only touch along an edge do not overlap.
+    pub fn intersects(&self, other: &AABB) -> bool {
+        self.collide_with(other)
+    }
+
+    /// Checks if a [`Point`] lies inside the [`AABB`].
+    ///
+    /// A [`Point`] on the edge of the [`AABB`] is not inside it.
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.collide_with(&point)
+    }
+
+    /// Get the minimum translation vector that moves `self` out of another [`AABB`].
+    ///
+    /// The vector is along the axis with the smallest overlap, preferring the `X` axis when both are equal. Returns [`None`] if the [`AABBs`](AABB) do not overlap.
+    pub fn resolve(&self, other: &AABB) -> Option<Point> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let overlap_x = self.max.x.min(other.max.x) - self.min.x.max(other.min.x);
+        let overlap_y = self.max.y.min(other.max.y) - self.min.y.max(other.min.y);
+        let offset = (self.min + self.max) - (other.min + other.max);
+        let sign = |delta: f32| if delta < 0.0 { -1.0 } else { 1.0 };
+
+        if overlap_x <= overlap_y {
+            Some(Point::new(overlap_x * sign(offset.x), 0.0))
+        } else {
+            Some(Point::new(0.0, overlap_y * sign(offset.y)))
+        }
+    }
+
+    /// Intersect a ray with the [`AABB`], lying on the `z = 0` plane, using the slab method.
+    ///
+    /// Returns the distance along the ray to the nearest hit, in multiples of `dir`, or [`None`] if the ray misses or points away. A ray starting inside the [`AABB`] hits at `0.0`.
+    pub fn ray_intersect(&self, origin: Vec3, dir: Vec3) -> Option<f32> {
+        let min = Vec3::new(self.min.x, self.min.y, 0.0);
+        let max = Vec3::new(self.max.x, self.max.y, 0.0);
+        let mut near = 0.0f32;
+        let mut far = f32::INFINITY;
+        for axis in 0..3 {
+            if dir[axis].abs() <= f32::EPSILON {
+                if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                    return None;
+                }
+                continue;
+            }
+            let t1 = (min[axis] - origin[axis]) / dir[axis];
+            let t2 = (max[axis] - origin[axis]) / dir[axis];
+            near = near.max(t1.min(t2));
+            far = far.min(t1.max(t2));
+            if near > far {
+                return None;
+            }
+        }
+        Some(near)
+    }
+}
+
+/// An identifier for an entry in a [`SpatialGrid`].
+pub type ColliderId = u32;
+
+/// A uniform grid that buckets [`AABBs`](AABB) by cell, for broad-phase collision checks.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: BTreeMap<(i32, i32), Vec<ColliderId>>,
+    entries: BTreeMap<ColliderId, AABB>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl SpatialGrid {
+    /// Create a new, empty [`SpatialGrid`] with a given cell size, in world units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cell_size` is not a finite, positive number.
+    pub fn new(cell_size: f32) -> Self {
+        assert!(
+            cell_size.is_finite() && cell_size > 0.0,
+            "SpatialGrid cell size must be finite and positive, got {}",
+            cell_size
+        );
+        Self {
+            cell_size,
+            cells: BTreeMap::new(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Get the range of cells covered by an [`AABB`].
+    fn cell_range(&self, area: &AABB) -> ((i32, i32), (i32, i32)) {
+        let cell = |point: Point| {
+            (
+                (point.x / self.cell_size).floor() as i32,
+                (point.y / self.cell_size).floor() as i32,
+            )
+        };
+        (cell(area.min), cell(area.max))
+    }
+
+    /// Insert an [`AABB`] with an ID into the [`SpatialGrid`], replacing any existing entry with the same ID.
+    pub fn insert(&mut self, id: ColliderId, collider: AABB) {
+        self.remove(id);
+        let ((min_x, min_y), (max_x, max_y)) = self.cell_range(&collider);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                self.cells.entry((x, y)).or_default().push(id);
+            }
+        }
+        self.entries.insert(id, collider);
+    }
+
+    /// Remove the entry with an ID from the [`SpatialGrid`], returning its [`AABB`] if it existed.
+    pub fn remove(&mut self, id: ColliderId) -> Option<AABB> {
+        let collider = self.entries.remove(&id)?;
+        let ((min_x, min_y), (max_x, max_y)) = self.cell_range(&collider);
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                if let Some(cell) = self.cells.get_mut(&(x, y)) {
+                    cell.retain(|entry| *entry != id);
+                    if cell.is_empty() {
+                        self.cells.remove(&(x, y));
+                    }
+                }
+            }
+        }
+        Some(collider)
+    }
+
+    /// Get the number of entries in the [`SpatialGrid`].
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the [`SpatialGrid`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the IDs of the entries that overlap an area, in ascending order.
+    ///
+    /// Only the cells covered by the area are searched, and each candidate is then checked with [`AABB::intersects`].
+    pub fn query_region(&self, area: AABB) -> Vec<ColliderId> {
+        let ((min_x, min_y), (max_x, max_y)) = self.cell_range(&area);
+        let mut found: Vec<ColliderId> = Vec::new();
+        for ((_, y), cell) in self.cells.range((min_x, min_y)..=(max_x, max_y)) {
+            if (min_y..=max_y).contains(y) {
+                found.extend(cell);
+            }
+        }
+        found.sort_unstable();
+        found.dedup();
+        found.retain(|id| self.entries[id].intersects(&area));
+        found
+    }
 }
 
 /// A Cicle Collider.
@@ -112,6 +278,23 @@ impl Circle {
             radius,
         }
     }
+
+    /// Checks if two [`Circles`](Circle) overlap.
+    pub fn intersects_circle(&self, other: &Circle) -> bool {
+        self.collide_with(other)
+    }
+
+    /// Checks if the [`Circle`] overlaps an [`AABB`].
+    pub fn intersects_aabb(&self, other: &AABB) -> bool {
+        self.collide_with(other)
+    }
+
+    /// Checks if a [`Point`] lies inside the [`Circle`].
+    ///
+    /// A [`Point`] on the circumference of the [`Circle`] is not inside it.
+    pub fn contains_point(&self, point: Point) -> bool {
+        self.collide_with(&point)
+    }
 }
 
 impl Collider for Point {
@@ -141,8 +324,8 @@ impl Collider for AABB {
 impl Collider for Circle {
     fn get_bounding_box(&self) -> AABB {
         AABB {
-            min: self.origin - Point::from_element(1.0),
-            max: self.origin + Point::from_element(1.0),
+            min: self.origin - Point::from_element(self.radius),
+            max: self.origin + Point::from_element(self.radius),
         }
     }
 
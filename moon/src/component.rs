@@ -18,6 +18,13 @@ pub trait Component {
         None
     }
 
+    /// Get the [`Component`]'s drawable [`Quad`], between its state before the last update and its current one.
+    ///
+    /// The `alpha` goes from `0.0` for the previous state to `1.0` for the current one. Defaults to [`get_quads`](Component::get_quads).
+    fn get_interpolated_quads(&self, _alpha: f32) -> Option<Vec<Quad>> {
+        self.get_quads()
+    }
+
     /// Borrow the [`Component`] as a [`&mut dyn Any`](Any).
     fn as_any(&self) -> &dyn Any;
 
@@ -0,0 +1,149 @@
+//! The [`DebugOverlay`] and [`Line`] structs. Used to visualize the world while debugging.
+
+use crate::collider::AABB;
+use crate::component::Component;
+use crate::renderer::Quad;
+use crate::{Color32, Vec2};
+
+/// Default width of a [`Line`] in world units.
+pub const DEFAULT_LINE_WIDTH: f32 = 0.02;
+
+/// A straight [`Line`] between two points.
+#[derive(Debug, Clone, Copy)]
+pub struct Line {
+    /// The start point of the [`Line`].
+    pub start: Vec2,
+    /// The end point of the [`Line`].
+    pub end: Vec2,
+    /// The color of the [`Line`].
+    pub color: Color32,
+}
+
+impl Line {
+    /// Create a new [`Line`] between two points with a given [`Color32`].
+    pub fn new(start: Vec2, end: Vec2, color: Color32) -> Self {
+        Self { start, end, color }
+    }
+
+    /// Get the four [`Lines`](Line) outlining an [`AABB`].
+    pub fn aabb_outline(aabb: &AABB, color: Color32) -> [Line; 4] {
+        let (min, max) = (aabb.min, aabb.max);
+        let corners = [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)];
+        [0, 1, 2, 3].map(|index| Line::new(corners[index], corners[(index + 1) % 4], color))
+    }
+
+    /// Get the [`Line`] as a thin, rotated [`Quad`] with a given width.
+    pub fn get_quad(&self, width: f32) -> Quad {
+        let center = (self.start + self.end) / 2.0;
+        let direction = self.end - self.start;
+        Quad::new_from_position_and_rotation_and_size_and_color(
+            center.x,
+            center.y,
+            direction.y.atan2(direction.x),
+            direction.norm(),
+            width,
+            self.color,
+        )
+    }
+}
+
+/// A [`DebugOverlay`] collects [`Lines`](Line) to be drawn over the scene.
+///
+/// The [`Lines`](Line) are kept until [`clear`](DebugOverlay::clear) is called.
+#[derive(Debug, Clone)]
+pub struct DebugOverlay {
+    lines: Vec<Line>,
+    /// Width of the [`Lines`](Line) in world units.
+    pub line_width: f32,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self {
+            lines: Vec::new(),
+            line_width: DEFAULT_LINE_WIDTH,
+        }
+    }
+}
+
+impl Component for DebugOverlay {
+    /// Get a thin [`Quad`] for each [`Line`].
+    fn get_quads(&self) -> Option<Vec<Quad>> {
+        Some(
+            self.lines
+                .iter()
+                .map(|line| line.get_quad(self.line_width))
+                .collect(),
+        )
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl DebugOverlay {
+    /// Create a new, empty [`DebugOverlay`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Get the [`Lines`](Line) of the [`DebugOverlay`] as a slice.
+    pub fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Remove all the [`Lines`](Line) from the [`DebugOverlay`].
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Add a [`Line`] between two points.
+    pub fn draw_line(&mut self, start: Vec2, end: Vec2, color: Color32) {
+        self.lines.push(Line::new(start, end, color));
+    }
+
+    /// Add the outline of an [`AABB`], such as a collider.
+    pub fn draw_aabb_outline(&mut self, aabb: &AABB, color: Color32) {
+        self.lines.extend(Line::aabb_outline(aabb, color));
+    }
+
+    /// Add a grid of [`Lines`](Line) inside the given bounds.
+    ///
+    /// Lines are placed at every multiple of `spacing` within the bounds, including the edges. Nothing is added if `spacing` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::collider::AABB;
+    /// use moon_engine::debug::DebugOverlay;
+    /// use moon_engine::Color32;
+    ///
+    /// let mut overlay = DebugOverlay::new();
+    /// overlay.draw_grid(1.0, Color32::WHITE, AABB::new_size(4.0, 4.0));
+    ///
+    /// assert_eq!(overlay.lines().len(), 10);
+    /// ```
+    pub fn draw_grid(&mut self, spacing: f32, color: Color32, bounds: AABB) {
+        if spacing <= 0.0 {
+            return;
+        }
+        let (min, max) = (bounds.min, bounds.max);
+
+        let (first_x, last_x) = ((min.x / spacing).ceil(), (max.x / spacing).floor());
+        for index in first_x as i32..=last_x as i32 {
+            let x = index as f32 * spacing;
+            self.draw_line(Vec2::new(x, min.y), Vec2::new(x, max.y), color);
+        }
+
+        let (first_y, last_y) = ((min.y / spacing).ceil(), (max.y / spacing).floor());
+        for index in first_y as i32..=last_y as i32 {
+            let y = index as f32 * spacing;
+            self.draw_line(Vec2::new(min.x, y), Vec2::new(max.x, y), color);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! The [`Font`] struct, for drawing text with a bitmap font.
+
+use crate::renderer::Quad;
+use crate::texture::SubTexture;
+use crate::{Color32, Vec2};
+
+/// The character drawn in place of those missing from a [`Font`].
+pub const FALLBACK_GLYPH: char = '?';
+
+/// A fixed-grid bitmap font.
+///
+/// The glyphs are cells of an atlas, in row-major order, starting with the first character of the [`Font`].
+#[derive(Debug, Clone)]
+pub struct Font {
+    atlas: SubTexture,
+    glyphs: Vec<SubTexture>,
+    first_char: u32,
+}
+
+impl Font {
+    /// Create a new [`Font`] from an atlas divided into a grid of glyphs, starting with the NUL character.
+    ///
+    /// A 16x16 grid covers all 256 extended ASCII characters.
+    pub fn new_grid(atlas: SubTexture, columns: u32, rows: u32) -> Self {
+        Self::new_grid_with_first_char(atlas, columns, rows, '\0')
+    }
+
+    /// Create a new [`Font`] from an atlas divided into a grid of glyphs, starting with a given character.
+    pub fn new_grid_with_first_char(
+        atlas: SubTexture,
+        columns: u32,
+        rows: u32,
+        first_char: char,
+    ) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let mut glyphs = Vec::with_capacity((columns * rows) as usize);
+        for row in 0..rows {
+            for column in 0..columns {
+                glyphs.push(atlas.region(Color32(
+                    column as f32 / columns as f32,
+                    (column + 1) as f32 / columns as f32,
+                    row as f32 / rows as f32,
+                    (row + 1) as f32 / rows as f32,
+                )));
+            }
+        }
+        Self {
+            atlas,
+            glyphs,
+            first_char: first_char as u32,
+        }
+    }
+
+    /// Get the atlas of the [`Font`].
+    pub fn atlas(&self) -> &SubTexture {
+        &self.atlas
+    }
+
+    /// Get the glyph of a character, or [`None`] if the [`Font`] does not contain it.
+    pub fn glyph(&self, character: char) -> Option<&SubTexture> {
+        (character as u32)
+            .checked_sub(self.first_char)
+            .and_then(|index| self.glyphs.get(index as usize))
+    }
+
+    /// Get a [`Quad`] for each visible character of some text, with its top-left corner at a given position.
+    ///
+    /// Each glyph is a square with sides of `scale` world units. Newlines move down a line, spaces leave a gap, and characters missing from the [`Font`] are drawn as [`FALLBACK_GLYPH`], if present.
+    pub fn quads(&self, text: &str, position: Vec2, scale: f32, color: Color32) -> Vec<Quad> {
+        let half = scale / 2.0;
+        let mut cursor = position;
+        let mut quads = Vec::with_capacity(text.len());
+        for character in text.chars() {
+            match character {
+                '\n' => {
+                    cursor = Vec2::new(position.x, cursor.y + scale);
+                    continue;
+                }
+                ' ' => {}
+                character => {
+                    if let Some(glyph) =
+                        self.glyph(character).or_else(|| self.glyph(FALLBACK_GLYPH))
+                    {
+                        let quad = Quad::new_from_position_and_size_and_sprite(
+                            cursor.x + half,
+                            cursor.y + half,
+                            scale,
+                            scale,
+                            glyph,
+                        );
+                        quads.push(quad.with_tint(color));
+                    }
+                }
+            }
+            cursor.x += scale;
+        }
+        quads
+    }
+}
@@ -0,0 +1,97 @@
+//! The [`Framebuffer`] struct. Used to render to a [`Texture`] instead of the screen.
+
+use web_sys::{WebGlFramebuffer, WebGlRenderbuffer};
+
+use crate::texture::Texture;
+use crate::{gl, GL};
+
+/// A [`Framebuffer`] is an off-screen render target, with a [`Texture`] as its color attachment and a depth attachment.
+#[derive(Debug)]
+pub struct Framebuffer {
+    framebuffer: Option<WebGlFramebuffer>,
+    depth: Option<WebGlRenderbuffer>,
+    /// The color attachment of the [`Framebuffer`].
+    pub texture: Texture,
+}
+
+impl gl::Bind for Framebuffer {
+    /// Bind the [`Framebuffer`] and set the viewport to cover it.
+    fn bind(&self, gl: &GL) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, self.framebuffer.as_ref());
+        gl.viewport(0, 0, self.width() as i32, self.height() as i32);
+    }
+    /// Bind the default framebuffer, and set the viewport to cover the canvas.
+    fn unbind(&self, gl: &GL) {
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+        gl.viewport(0, 0, gl.drawing_buffer_width(), gl.drawing_buffer_height());
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        let gl = gl::get_context();
+
+        gl.delete_framebuffer(self.framebuffer.as_ref());
+        gl.delete_renderbuffer(self.depth.as_ref());
+    }
+}
+
+impl Framebuffer {
+    /// Create a new [`Framebuffer`] with a given width and height.
+    pub fn new(gl: &GL, width: u32, height: u32) -> Self {
+        Self::new_with_texture(gl, Texture::new_empty(gl, width, height))
+    }
+
+    /// Create a new [`Framebuffer`] with a high dynamic range color attachment, if supported.
+    pub fn new_hdr(gl: &GL, width: u32, height: u32) -> Self {
+        Self::new_with_texture(gl, Texture::new_empty_hdr(gl, width, height))
+    }
+
+    /// Create a new [`Framebuffer`] using an existing [`Texture`] as its color attachment.
+    ///
+    /// A depth attachment of the same size is created along with it.
+    pub fn new_with_texture(gl: &GL, texture: Texture) -> Self {
+        let depth = gl.create_renderbuffer();
+        gl.bind_renderbuffer(GL::RENDERBUFFER, depth.as_ref());
+        gl.renderbuffer_storage(
+            GL::RENDERBUFFER,
+            GL::DEPTH_COMPONENT24,
+            texture.width as i32,
+            texture.height as i32,
+        );
+        gl.bind_renderbuffer(GL::RENDERBUFFER, None);
+
+        let framebuffer = gl.create_framebuffer();
+        gl.bind_framebuffer(GL::FRAMEBUFFER, framebuffer.as_ref());
+        gl.framebuffer_texture_2d(
+            GL::FRAMEBUFFER,
+            GL::COLOR_ATTACHMENT0,
+            GL::TEXTURE_2D,
+            texture.texture(),
+            0,
+        );
+        gl.framebuffer_renderbuffer(
+            GL::FRAMEBUFFER,
+            GL::DEPTH_ATTACHMENT,
+            GL::RENDERBUFFER,
+            depth.as_ref(),
+        );
+        gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+
+        Self {
+            framebuffer,
+            depth,
+            texture,
+        }
+    }
+
+    /// Get the width of the [`Framebuffer`].
+    pub fn width(&self) -> u32 {
+        self.texture.width
+    }
+
+    /// Get the height of the [`Framebuffer`].
+    pub fn height(&self) -> u32 {
+        self.texture.height
+    }
+}
@@ -0,0 +1,220 @@
+//! The [`Player`] struct, a simple platformer character.
+
+use std::rc::Rc;
+
+use crate::bindings::{Action, Bindings};
+use crate::collider::AABB;
+use crate::component::Component;
+use crate::input::InputManager;
+use crate::renderer::Quad;
+use crate::texture::SubTexture;
+use crate::tilemap::TileMap;
+use crate::Vec2;
+
+/// Default downward acceleration of a [`Player`], in world units per second squared.
+pub const DEFAULT_GRAVITY: f32 = 30.0;
+/// Default upward velocity of a [`Player`] when it jumps, in world units per second.
+pub const DEFAULT_JUMP_SPEED: f32 = 12.0;
+/// Default horizontal speed of a [`Player`], in world units per second.
+pub const DEFAULT_SPEED: f32 = 5.0;
+
+/// A platformer character, that runs, jumps and falls under gravity.
+///
+/// The `Y` axis points down the screen, so gravity is positive and jumping sets a negative vertical velocity.
+#[derive(Debug, Clone)]
+pub struct Player {
+    /// Position of the center of the [`Player`], in world units.
+    pub position: Vec2,
+    /// Position of the [`Player`] before the last update, used to draw it between simulation steps.
+    pub previous_position: Vec2,
+    /// Size of the [`Player`], in world units.
+    pub size: Vec2,
+    /// Velocity of the [`Player`], in world units per second.
+    pub velocity: Vec2,
+    /// Horizontal speed of the [`Player`] while a movement key is held.
+    pub speed: f32,
+    /// Upward velocity of the [`Player`] when it jumps.
+    pub jump_speed: f32,
+    /// Downward acceleration of the [`Player`].
+    pub gravity: f32,
+    /// An optional flat floor, as a `Y` position the [`Player`] lands on.
+    pub floor: Option<f32>,
+    /// Whether the [`Player`] is standing on the ground.
+    pub is_grounded: bool,
+    /// Whether the [`Player`] has jumped and not landed yet.
+    pub is_jumping: bool,
+    /// An optional sprite to draw the [`Player`] with, facing right.
+    pub sprite: Option<Rc<SubTexture>>,
+    /// Whether the [`Player`] is facing left, in which case its sprite is mirrored.
+    pub facing_left: bool,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            position: Vec2::zeros(),
+            previous_position: Vec2::zeros(),
+            size: Vec2::from_element(1.0),
+            velocity: Vec2::zeros(),
+            speed: DEFAULT_SPEED,
+            jump_speed: DEFAULT_JUMP_SPEED,
+            gravity: DEFAULT_GRAVITY,
+            floor: None,
+            is_grounded: false,
+            is_jumping: false,
+            sprite: None,
+            facing_left: false,
+        }
+    }
+}
+
+impl Component for Player {
+    fn update(&mut self, delta_time: f32) {
+        self.previous_position = self.position;
+        self.velocity.y += self.gravity * delta_time;
+        self.position += self.velocity * delta_time;
+        self.is_grounded = false;
+
+        if let Some(floor) = self.floor {
+            let bottom = self.position.y + self.size.y / 2.0;
+            if bottom >= floor && self.velocity.y >= 0.0 {
+                self.position.y = floor - self.size.y / 2.0;
+                self.land();
+            }
+        }
+    }
+
+    fn get_quads(&self) -> Option<Vec<Quad>> {
+        Some(vec![self.quad()])
+    }
+
+    fn get_interpolated_quads(&self, alpha: f32) -> Option<Vec<Quad>> {
+        Some(vec![self.interpolated_quad(alpha)])
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl Player {
+    /// Create a new [`Player`] at a given position.
+    pub fn new_with_position(pos_x: f32, pos_y: f32) -> Self {
+        Self {
+            position: Vec2::new(pos_x, pos_y),
+            previous_position: Vec2::new(pos_x, pos_y),
+            ..Default::default()
+        }
+    }
+
+    /// Set the horizontal velocity and facing from the movement [`Bindings`], and jump if the [`Action::Jump`] key is held.
+    pub fn handle_input(&mut self, input: &InputManager, bindings: &Bindings) {
+        let horizontal = bindings.movement_axis(input).x;
+        self.velocity.x = if horizontal == 0.0 {
+            0.0
+        } else {
+            horizontal.signum() * self.speed
+        };
+        if horizontal != 0.0 {
+            self.facing_left = horizontal < 0.0;
+        }
+        if bindings.is_action_active(input, Action::Jump) {
+            self.jump();
+        }
+    }
+
+    /// Jump, if the [`Player`] is on the ground.
+    ///
+    /// Returns whether the [`Player`] jumped.
+    pub fn jump(&mut self) -> bool {
+        if !self.is_grounded {
+            return false;
+        }
+        self.velocity.y = -self.jump_speed;
+        self.is_grounded = false;
+        self.is_jumping = true;
+        true
+    }
+
+    /// Stop falling, and mark the [`Player`] as on the ground.
+    fn land(&mut self) {
+        self.velocity.y = 0.0;
+        self.is_grounded = true;
+        self.is_jumping = false;
+    }
+
+    /// Get the bounding box of the [`Player`], in world space.
+    pub fn aabb(&self) -> AABB {
+        AABB::new_position_and_size(self.position.x, self.position.y, self.size.x, self.size.y)
+    }
+
+    /// Push the [`Player`] out of the solid [`Tiles`](crate::tilemap::Tile) of a [`TileMap`] it overlaps.
+    ///
+    /// Only the cells under the [`Player`] are checked. Being pushed up lands the [`Player`], and velocity into a [`Tile`] is cancelled.
+    pub fn resolve_tilemap_collision(&mut self, map: &TileMap, tile_size: f32) {
+        let bounds = self.aabb();
+        let (min_x, min_y) = TileMap::world_to_tile(bounds.min, tile_size);
+        let (max_x, max_y) = TileMap::world_to_tile(bounds.max, tile_size);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                let push = match map
+                    .world_collider(x, y, tile_size)
+                    .and_then(|collider| self.aabb().resolve(&collider))
+                {
+                    Some(push) => push,
+                    None => continue,
+                };
+                self.position += push;
+                if push.x != 0.0 && push.x.signum() != self.velocity.x.signum() {
+                    self.velocity.x = 0.0;
+                }
+                if push.y < 0.0 && self.velocity.y >= 0.0 {
+                    self.land();
+                } else if push.y > 0.0 && self.velocity.y < 0.0 {
+                    self.velocity.y = 0.0;
+                }
+            }
+        }
+    }
+
+    /// Get a [`Quad`] to draw the [`Player`] with, mirrored if it is facing left.
+    pub fn quad(&self) -> Quad {
+        self.quad_at(self.position)
+    }
+
+    /// Get a [`Quad`] to draw the [`Player`] with, between its previous and current position.
+    ///
+    /// The `alpha` goes from `0.0` at the previous position to `1.0` at the current one, and is clamped.
+    pub fn interpolated_quad(&self, alpha: f32) -> Quad {
+        let alpha = alpha.clamp(0.0, 1.0);
+        self.quad_at(self.previous_position.lerp(&self.position, alpha))
+    }
+
+    /// Get a [`Quad`] to draw the [`Player`] with at a given position.
+    fn quad_at(&self, position: Vec2) -> Quad {
+        match &self.sprite {
+            Some(sprite) if self.facing_left => Quad::new_from_position_and_size_and_sprite(
+                position.x,
+                position.y,
+                self.size.x,
+                self.size.y,
+                &sprite.flipped_x(),
+            ),
+            Some(sprite) => Quad::new_from_position_and_size_and_sprite(
+                position.x,
+                position.y,
+                self.size.x,
+                self.size.y,
+                sprite,
+            ),
+            None => {
+                Quad::new_from_position_and_size(position.x, position.y, self.size.x, self.size.y)
+            }
+        }
+    }
+}
@@ -3,14 +3,14 @@
 use wasm_bindgen::JsCast;
 use web_sys::WebGl2RenderingContext;
 
-use crate::Canvas;
+use crate::{console_log, Canvas};
 
 /// An alias for [`WebGl2RenderingContext`].
 ///
 /// # Examples
 ///
 /// ```no_run
-/// use moon::*;
+/// use moon_engine::*;
 /// let gl = gl::get_context();
 /// ```
 pub type GL = WebGl2RenderingContext;
@@ -25,24 +25,77 @@ pub trait Bind {
     fn unbind(&self, _gl: &GL) {}
 }
 
-/// Check for, and print any WebGL errors if found.
+/// Get the name of a WebGL error enum, as returned by `getError`.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::gl::{gl_error_name, GL};
+///
+/// assert_eq!(gl_error_name(GL::INVALID_ENUM), "INVALID_ENUM");
+/// ```
+pub fn gl_error_name(error: u32) -> &'static str {
+    match error {
+        GL::NO_ERROR => "NO_ERROR",
+        GL::INVALID_ENUM => "INVALID_ENUM",
+        GL::INVALID_VALUE => "INVALID_VALUE",
+        GL::INVALID_OPERATION => "INVALID_OPERATION",
+        GL::INVALID_FRAMEBUFFER_OPERATION => "INVALID_FRAMEBUFFER_OPERATION",
+        GL::OUT_OF_MEMORY => "OUT_OF_MEMORY",
+        GL::CONTEXT_LOST_WEBGL => "CONTEXT_LOST_WEBGL",
+        _ => "UNKNOWN_ERROR",
+    }
+}
+
+/// Check for, and log any WebGL errors to the browser console if found.
 ///
 /// Takes a reference to a [`WebGl2RenderingContext`] and returns a [`bool`], indicating whether any errors were found.
 ///
 /// # Examples
 ///
 /// ```no_run
-/// use moon::*;
+/// use moon_engine::*;
 /// # let gl = gl::get_context();
 ///
 /// let has_errors = gl::check_gl_error(&gl);
 /// ```
 ///
 pub fn check_gl_error(gl: &GL) -> bool {
+    log_gl_errors(gl, None)
+}
+
+/// Check for WebGL errors after a call, and log them along with a description of the call site.
+///
+/// Only runs in debug builds. In release builds it does nothing, and does not stall on `getError`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use moon_engine::*;
+/// # let gl = gl::get_context();
+///
+/// gl.clear(gl::GL::COLOR_BUFFER_BIT);
+/// gl::gl_check(&gl, "clear");
+/// ```
+#[inline]
+pub fn gl_check(gl: &GL, context: &str) {
+    #[cfg(debug_assertions)]
+    log_gl_errors(gl, Some(context));
+    #[cfg(not(debug_assertions))]
+    let _ = (gl, context);
+}
+
+/// Log all pending WebGL errors, returning whether any were found.
+fn log_gl_errors(gl: &GL, context: Option<&str>) -> bool {
     let mut found_error = false;
     let mut gl_error = gl.get_error();
     while gl_error != GL::NO_ERROR {
-        println!("OpenGL Error {}", gl_error);
+        match context {
+            Some(context) => {
+                console_log!("WebGL error {} after {}", gl_error_name(gl_error), context)
+            }
+            None => console_log!("WebGL error {}", gl_error_name(gl_error)),
+        }
         found_error = true;
         gl_error = gl.get_error();
     }
@@ -56,7 +109,7 @@ pub fn check_gl_error(gl: &GL) -> bool {
 /// # Examples
 ///
 /// ```no_run
-/// use moon::gl::*;
+/// use moon_engine::gl::*;
 ///
 /// let context: GL = get_context();
 /// ```
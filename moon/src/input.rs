@@ -1,33 +1,366 @@
-//! The [`InputManager`] struct.
+//! The [`InputManager`] and [`GamepadState`] structs, and the [`Key`] and [`MouseButton`] enums.
 
 use std::collections::BTreeSet;
 
+use wasm_bindgen::JsCast;
+
 use crate::Vec2;
 
+/// A keyboard key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Key {
+    /// The A key.
+    A,
+    /// The B key.
+    B,
+    /// The C key.
+    C,
+    /// The D key.
+    D,
+    /// The E key.
+    E,
+    /// The F key.
+    F,
+    /// The G key.
+    G,
+    /// The H key.
+    H,
+    /// The I key.
+    I,
+    /// The J key.
+    J,
+    /// The K key.
+    K,
+    /// The L key.
+    L,
+    /// The M key.
+    M,
+    /// The N key.
+    N,
+    /// The O key.
+    O,
+    /// The P key.
+    P,
+    /// The Q key.
+    Q,
+    /// The R key.
+    R,
+    /// The S key.
+    S,
+    /// The T key.
+    T,
+    /// The U key.
+    U,
+    /// The V key.
+    V,
+    /// The W key.
+    W,
+    /// The X key.
+    X,
+    /// The Y key.
+    Y,
+    /// The Z key.
+    Z,
+    /// The 0 key on the number row.
+    Num0,
+    /// The 1 key on the number row.
+    Num1,
+    /// The 2 key on the number row.
+    Num2,
+    /// The 3 key on the number row.
+    Num3,
+    /// The 4 key on the number row.
+    Num4,
+    /// The 5 key on the number row.
+    Num5,
+    /// The 6 key on the number row.
+    Num6,
+    /// The 7 key on the number row.
+    Num7,
+    /// The 8 key on the number row.
+    Num8,
+    /// The 9 key on the number row.
+    Num9,
+    /// The Space bar.
+    Space,
+    /// The Up arrow key.
+    ArrowUp,
+    /// The Down arrow key.
+    ArrowDown,
+    /// The Left arrow key.
+    ArrowLeft,
+    /// The Right arrow key.
+    ArrowRight,
+    /// Either Shift key.
+    Shift,
+    /// Either Control key.
+    Control,
+    /// Either Alt key.
+    Alt,
+    /// The Tab key.
+    Tab,
+    /// The Enter key.
+    Enter,
+    /// The Escape key.
+    Escape,
+    /// Any key that is not recognized.
+    Unknown,
+}
+
+const LETTER_KEYS: [Key; 26] = [
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+];
+
+const NUMBER_KEYS: [Key; 10] = [
+    Key::Num0,
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+];
+
+impl From<u8> for Key {
+    /// Convert the ASCII code of a character to a [`Key`].
+    ///
+    /// Upper and lower case letters map to the same [`Key`]. This is not the `keyCode` of a `KeyboardEvent`, where `97..=122` are numpad and function keys.
+    fn from(key_char: u8) -> Self {
+        match key_char {
+            b'A'..=b'Z' => LETTER_KEYS[(key_char - b'A') as usize],
+            b'a'..=b'z' => LETTER_KEYS[(key_char - b'a') as usize],
+            b'0'..=b'9' => NUMBER_KEYS[(key_char - b'0') as usize],
+            b' ' => Key::Space,
+            b'\t' => Key::Tab,
+            b'\r' => Key::Enter,
+            0x1B => Key::Escape,
+            _ => Key::Unknown,
+        }
+    }
+}
+
+impl From<&str> for Key {
+    /// Convert the `key` of a `KeyboardEvent` to a [`Key`].
+    fn from(key: &str) -> Self {
+        match key {
+            "ArrowUp" => Key::ArrowUp,
+            "ArrowDown" => Key::ArrowDown,
+            "ArrowLeft" => Key::ArrowLeft,
+            "ArrowRight" => Key::ArrowRight,
+            "Shift" => Key::Shift,
+            "Control" => Key::Control,
+            "Alt" => Key::Alt,
+            "Tab" => Key::Tab,
+            "Enter" => Key::Enter,
+            "Escape" => Key::Escape,
+            _ => match key.as_bytes() {
+                [key_char] => Key::from(*key_char),
+                _ => Key::Unknown,
+            },
+        }
+    }
+}
+
+impl Key {
+    /// Convert the `code` of a `KeyboardEvent` to a [`Key`].
+    ///
+    /// The `code` names the physical key, regardless of the layout and modifiers, so a digit pressed with Shift is still released as the same [`Key`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::input::Key;
+    ///
+    /// assert_eq!(Key::from_code("Digit1"), Key::Num1);
+    /// assert_eq!(Key::from_code("KeyW"), Key::W);
+    /// assert_eq!(Key::from_code("ShiftLeft"), Key::Shift);
+    /// ```
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "Space" => Key::Space,
+            "ShiftLeft" | "ShiftRight" => Key::Shift,
+            "ControlLeft" | "ControlRight" => Key::Control,
+            "AltLeft" | "AltRight" => Key::Alt,
+            "NumpadEnter" => Key::Enter,
+            _ => match code
+                .strip_prefix("Key")
+                .or_else(|| code.strip_prefix("Digit"))
+                .map(str::as_bytes)
+            {
+                Some([key_char @ (b'A'..=b'Z' | b'0'..=b'9')]) => Key::from(*key_char),
+                Some(_) => Key::Unknown,
+                None => Key::from(code),
+            },
+        }
+    }
+}
+
+/// A mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MouseButton {
+    /// The main button, usually the left button.
+    Left,
+    /// The auxiliary button, usually the middle button or wheel.
+    Middle,
+    /// The secondary button, usually the right button.
+    Right,
+    /// Any button that is not recognized.
+    Unknown,
+}
+
+impl From<i16> for MouseButton {
+    /// Convert the `button` of a `MouseEvent` to a [`MouseButton`].
+    fn from(button: i16) -> Self {
+        match button {
+            0 => MouseButton::Left,
+            1 => MouseButton::Middle,
+            2 => MouseButton::Right,
+            _ => MouseButton::Unknown,
+        }
+    }
+}
+
+/// Axis values with a magnitude below the [`GAMEPAD_DEADZONE`] are read as `0.0`.
+pub const GAMEPAD_DEADZONE: f32 = 0.15;
+
+/// A snapshot of the buttons and axes of a gamepad.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GamepadState {
+    buttons: Vec<bool>,
+    axes: Vec<f32>,
+}
+
+impl GamepadState {
+    /// Create a new [`GamepadState`] with the given button states and raw axis values.
+    pub fn new(buttons: Vec<bool>, axes: Vec<f32>) -> Self {
+        Self { buttons, axes }
+    }
+
+    /// Read the current state of a [`web_sys::Gamepad`].
+    pub fn from_gamepad(gamepad: &web_sys::Gamepad) -> Self {
+        let buttons = gamepad
+            .buttons()
+            .iter()
+            .map(|button| {
+                button
+                    .dyn_into::<web_sys::GamepadButton>()
+                    .map(|button| button.pressed())
+                    .unwrap_or(false)
+            })
+            .collect();
+        let axes = gamepad
+            .axes()
+            .iter()
+            .map(|axis| axis.as_f64().unwrap_or(0.0) as f32)
+            .collect();
+        Self { buttons, axes }
+    }
+
+    /// Get the state of a button as a [`bool`].
+    ///
+    /// Returns false if the gamepad has no button with the given index.
+    pub fn get_button(&self, index: u32) -> bool {
+        self.buttons.get(index as usize).copied().unwrap_or(false)
+    }
+
+    /// Get the value of an axis, in the range `-1.0..=1.0`.
+    ///
+    /// Values inside the [`GAMEPAD_DEADZONE`] are read as `0.0`, and the rest are rescaled to start from it. Returns `0.0` if the gamepad has no axis with the given index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::input::GamepadState;
+    ///
+    /// let gamepad = GamepadState::new(Vec::new(), vec![0.1, -1.0]);
+    ///
+    /// assert_eq!(gamepad.get_axis(0), 0.0);
+    /// assert_eq!(gamepad.get_axis(1), -1.0);
+    /// ```
+    pub fn get_axis(&self, index: u32) -> f32 {
+        let value = self.axes.get(index as usize).copied().unwrap_or(0.0);
+        if value.abs() < GAMEPAD_DEADZONE {
+            0.0
+        } else {
+            let scaled = (value.abs() - GAMEPAD_DEADZONE) / (1.0 - GAMEPAD_DEADZONE);
+            scaled.min(1.0).copysign(value)
+        }
+    }
+}
+
 /// A store for Input-related data.
 ///
 /// The [`InputManager`] stores and handles the current input states.
 ///
 /// # Examples
 /// ```
-/// use moon::input::InputManager;
+/// use moon_engine::input::{InputManager, Key};
 ///
 /// let mut input = InputManager::new();
 ///
 /// input.key_down(b'w');
 ///
 /// assert!(input.get_key_state(b'w'));
+/// assert!(input.get_key_state(Key::W));
 /// ```
 #[derive(Default)]
 pub struct InputManager {
     /// Set of Keyboard key states.
     ///
     /// If a key is present, then it is being pressed, and otherwise it is not.
-    keyboard_states: BTreeSet<u8>,
+    keyboard_states: BTreeSet<Key>,
+    /// Set of Keyboard key states in the previous frame.
+    previous_keyboard_states: BTreeSet<Key>,
+    /// Set of keys pressed since the last frame, and not consumed yet.
+    ///
+    /// A key pressed and released between two frames stays in the set, so its press is not lost.
+    pressed_keys: BTreeSet<Key>,
+    /// Set of Mouse button states.
+    ///
+    /// If a button is present, then it is being pressed, and otherwise it is not.
+    mouse_button_states: BTreeSet<MouseButton>,
+    /// State of the first connected gamepad, if any.
+    gamepad: Option<GamepadState>,
     /// Position of the Mouse.
     ///
-    /// The Screen-Space position of the Mouse as a [`Vec2`].
+    /// The Screen-Space position of the Mouse as a [`Vec2`], in pixels from the top-left of the canvas.
     pub mouse_position: Vec2,
+    /// Movement of the Mouse since the last frame.
+    ///
+    /// In pixels, like [`mouse_position`](InputManager::mouse_position).
+    mouse_delta: Vec2,
+    /// Whether a mouse position has been recorded, so that the first movement does not produce a delta.
+    has_mouse_position: bool,
+    /// Scroll wheel movement since the last frame.
+    scroll_delta: f32,
 }
 
 impl InputManager {
@@ -40,28 +373,218 @@ impl InputManager {
 
     /// Key Down State.
     ///
-    /// Sets the key in the [`BTreeSet`].
-    pub fn key_down(&mut self, key_code: u8) {
-        self.keyboard_states.insert(key_code);
+    /// Sets the key in the [`BTreeSet`]. Accepts either a [`Key`] or an ASCII byte code.
+    pub fn key_down<K: Into<Key>>(&mut self, key: K) {
+        let key = key.into();
+        // Repeated key down events while a key is held are not new presses
+        if key != Key::Unknown && self.keyboard_states.insert(key) {
+            self.pressed_keys.insert(key);
+        }
     }
 
     /// Key Up State.
     ///
-    /// Resets the key in the [`BTreeSet`].
-    pub fn key_up(&mut self, key_code: u8) {
-        self.keyboard_states.remove(&key_code);
+    /// Resets the key in the [`BTreeSet`]. Accepts either a [`Key`] or an ASCII byte code.
+    pub fn key_up<K: Into<Key>>(&mut self, key: K) {
+        let key = key.into();
+        self.keyboard_states.remove(&key);
     }
 
     /// Get the state of a key as a [`bool`].
     ///
-    /// Returns true if the key is currently pressed, or false.
-    pub fn get_key_state(&self, key_code: u8) -> bool {
-        self.keyboard_states.contains(&key_code)
+    /// Returns true if the key is currently pressed, or false. Accepts either a [`Key`] or an ASCII byte code.
+    pub fn get_key_state<K: Into<Key>>(&self, key: K) -> bool {
+        self.keyboard_states.contains(&key.into())
+    }
+
+    /// Check if a key was pressed this frame.
+    ///
+    /// Returns true only on the frame the key goes from up to down, as of the last call to [`new_frame`](InputManager::new_frame).
+    pub fn get_key_pressed<K: Into<Key>>(&self, key: K) -> bool {
+        let key = key.into();
+        self.keyboard_states.contains(&key) && !self.previous_keyboard_states.contains(&key)
+    }
+
+    /// Check if a key was released this frame.
+    ///
+    /// Returns true only on the frame the key goes from down to up, as of the last call to [`new_frame`](InputManager::new_frame).
+    pub fn get_key_released<K: Into<Key>>(&self, key: K) -> bool {
+        let key = key.into();
+        !self.keyboard_states.contains(&key) && self.previous_keyboard_states.contains(&key)
+    }
+
+    /// Poll the first connected gamepad using `navigator.getGamepads()`.
+    ///
+    /// Should be called once per frame, as the browser does not send events for gamepad input.
+    pub fn update_gamepads(&mut self) {
+        let gamepads = web_sys::window().and_then(|window| window.navigator().get_gamepads().ok());
+        self.gamepad = gamepads.and_then(|gamepads| {
+            gamepads
+                .iter()
+                .filter_map(|gamepad| gamepad.dyn_into::<web_sys::Gamepad>().ok())
+                .find(|gamepad| gamepad.connected())
+                .map(|gamepad| GamepadState::from_gamepad(&gamepad))
+        });
     }
 
-    /// Set the mouse position.
+    /// Set the state of the gamepad directly, such as from a different input source.
+    pub fn set_gamepad(&mut self, gamepad: Option<GamepadState>) {
+        self.gamepad = gamepad;
+    }
+
+    /// Get the state of the first connected gamepad, if any.
+    pub fn gamepad(&self) -> Option<&GamepadState> {
+        self.gamepad.as_ref()
+    }
+
+    /// Check if a gamepad is connected.
+    pub fn gamepad_connected(&self) -> bool {
+        self.gamepad.is_some()
+    }
+
+    /// Get the direction held with the WASD keys as a [`Vec2`].
+    ///
+    /// The `X` component is `D - A` and the `Y` component is `W - S`. A non-zero direction is normalized, and each component is clamped to `-1.0..=1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::input::{InputManager, Key};
+    /// use moon_engine::Vec2;
+    ///
+    /// let mut input = InputManager::new();
+    /// input.key_down(Key::D);
+    ///
+    /// assert_eq!(input.movement_axis(), Vec2::new(1.0, 0.0));
+    /// ```
+    pub fn movement_axis(&self) -> Vec2 {
+        let axis = Vec2::new(self.axis(Key::A, Key::D), self.axis(Key::S, Key::W));
+        if axis == Vec2::zeros() {
+            axis
+        } else {
+            axis.normalize().map(|component| component.clamp(-1.0, 1.0))
+        }
+    }
+
+    /// Get the direction held with the Q and E keys as an [`f32`].
+    ///
+    /// Returns `E - Q`, so E moves up and Q moves down.
+    pub fn vertical_axis(&self) -> f32 {
+        self.axis(Key::Q, Key::E)
+    }
+
+    /// Get the difference between the states of a positive and a negative key.
+    fn axis(&self, negative: Key, positive: Key) -> f32 {
+        self.get_key_state(positive) as i32 as f32 - self.get_key_state(negative) as i32 as f32
+    }
+
+    /// Get and consume the state of a key as a [`bool`].
+    ///
+    /// Returns true if the key is currently pressed, and removes it so that it is only read once until pressed again.
+    /// A consumed key is reported by [`get_key_released`](InputManager::get_key_released) until the next frame.
+    pub fn consume_key_state<K: Into<Key>>(&mut self, key: K) -> bool {
+        self.keyboard_states.remove(&key.into())
+    }
+
+    /// Get and consume the press edge of a key as a [`bool`].
+    ///
+    /// Returns true only if the key was pressed since the last call to [`new_frame`](InputManager::new_frame), and has not already been consumed.
+    /// This includes a key that was pressed and released again before the frame, so quick taps are not lost.
+    /// Holding a key across an [`new_frame`](InputManager::new_frame) does not trigger it again; it must be released and pressed again.
+    pub fn consume_key_pressed<K: Into<Key>>(&mut self, key: K) -> bool {
+        self.pressed_keys.remove(&key.into())
+    }
+
+    /// Advance the [`InputManager`] to the next frame.
+    ///
+    /// Stores the current key states for edge detection, and clears the key presses of the frame. The [`Application`](crate::Application) calls this at the end of each frame.
+    pub fn new_frame(&mut self) {
+        self.previous_keyboard_states = self.keyboard_states.clone();
+        self.pressed_keys.clear();
+        self.mouse_delta = Vec2::zeros();
+        self.scroll_delta = 0.0;
+    }
+
+    /// Mouse Button Down State.
+    ///
+    /// Sets the button in the [`BTreeSet`]. Accepts either a [`MouseButton`] or the `button` of a `MouseEvent`.
+    pub fn mouse_button_down<B: Into<MouseButton>>(&mut self, button: B) {
+        let button = button.into();
+        if button != MouseButton::Unknown {
+            self.mouse_button_states.insert(button);
+        }
+    }
+
+    /// Mouse Button Up State.
+    ///
+    /// Resets the button in the [`BTreeSet`]. Accepts either a [`MouseButton`] or the `button` of a `MouseEvent`.
+    pub fn mouse_button_up<B: Into<MouseButton>>(&mut self, button: B) {
+        self.mouse_button_states.remove(&button.into());
+    }
+
+    /// Get the state of a mouse button as a [`bool`].
+    ///
+    /// Returns true if the button is currently pressed, or false.
+    pub fn get_mouse_button_state<B: Into<MouseButton>>(&self, button: B) -> bool {
+        self.mouse_button_states.contains(&button.into())
+    }
+
+    /// Set the mouse position, in pixels from the top-left of the canvas.
+    ///
+    /// The movement from the previous position is added to the mouse delta. The first position only sets the position, without a delta.
     pub fn set_mouse_position(&mut self, x: f32, y: f32) {
-        self.mouse_position.x = x;
-        self.mouse_position.y = y;
+        let position = Vec2::new(x, y);
+        if self.has_mouse_position {
+            self.mouse_delta += position - self.mouse_position;
+        }
+        self.mouse_position = position;
+        self.has_mouse_position = true;
+    }
+
+    /// Get the mouse position in normalized device co-ordinates, in the range `-1.0..=1.0`.
+    ///
+    /// The [`mouse_position`](InputManager::mouse_position) is treated as pixels from the top-left of the canvas, and the `Y` axis is flipped to point up.
+    /// Returns zero if the canvas has no area.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::input::InputManager;
+    /// use moon_engine::Vec2;
+    ///
+    /// let mut input = InputManager::new();
+    /// input.set_mouse_position(0.0, 0.0);
+    ///
+    /// assert_eq!(input.mouse_ndc(800.0, 600.0), Vec2::new(-1.0, 1.0));
+    /// ```
+    pub fn mouse_ndc(&self, canvas_width: f32, canvas_height: f32) -> Vec2 {
+        if canvas_width <= 0.0 || canvas_height <= 0.0 {
+            return Vec2::zeros();
+        }
+        Vec2::new(
+            self.mouse_position.x / canvas_width * 2.0 - 1.0,
+            1.0 - self.mouse_position.y / canvas_height * 2.0,
+        )
+    }
+
+    /// Get the movement of the mouse since the last frame.
+    ///
+    /// The delta is in pixels, like [`mouse_position`](InputManager::mouse_position), and is reset to zero by [`new_frame`](InputManager::new_frame).
+    pub fn get_mouse_delta(&self) -> Vec2 {
+        self.mouse_delta
+    }
+
+    /// Add scroll wheel movement, such as the `deltaY` of a `WheelEvent`.
+    ///
+    /// Multiple events between frames are accumulated.
+    pub fn add_scroll(&mut self, delta: f32) {
+        self.scroll_delta += delta;
+    }
+
+    /// Get the scroll wheel movement since the last frame.
+    ///
+    /// The delta is reset to zero by [`new_frame`](InputManager::new_frame).
+    pub fn get_scroll_delta(&self) -> f32 {
+        self.scroll_delta
     }
 }
@@ -3,35 +3,51 @@
 
 //! Moon Engine
 
+pub mod bindings;
 pub mod camera;
 pub mod collider;
 pub mod component;
+pub mod debug;
+pub mod font;
+pub mod framebuffer;
+pub mod game;
 pub mod gl;
 pub mod input;
+pub mod light;
+pub mod material;
 pub mod math;
 pub mod mesh;
 pub mod particle;
+pub mod postprocess;
 pub mod renderer;
+pub mod scene;
 pub mod shader;
 pub mod texture;
+#[cfg(feature = "serde")]
+pub mod tiled;
+pub mod tilemap;
 pub mod transform;
 pub mod ui;
 pub mod utils;
 pub mod web;
 
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 
+use bindings::Bindings;
 use camera::Camera;
+use game::Player;
 use gl::GL;
-use input::InputManager;
+use input::{InputManager, Key, MouseButton};
 pub use math::*;
 use particle::ParticleSystem;
-use renderer::Renderer;
+use renderer::{BlendMode, Renderer};
+use scene::Scene;
 use shader::Shader;
 use texture::Texture;
 use transform::Transform;
-use utils::set_panic_hook;
-use web::Canvas;
+use utils::{set_panic_hook, DeltaSmoother, FixedTimestep, FrameTimer};
+use web::{device_pixel_ratio, handle_context_loss, now_sec, Canvas};
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -39,11 +55,24 @@ use web::Canvas;
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
+/// The default color the screen is cleared with each frame.
+pub const DEFAULT_CLEAR_COLOR: Color32 = Color32(0.5, 0.2, 0.3, 1.0);
+
 /// The [`Application`] struct acts as the communicator between the browser and the game logic. It consists of calls made from JavaScript.
 #[wasm_bindgen]
 pub struct Application {
-    renderer: Renderer,
+    renderer: Option<Renderer>,
+    scene: Scene,
     input: InputManager,
+    bindings: Bindings,
+    delta_smoother: Option<DeltaSmoother>,
+    timestep: FixedTimestep,
+    last_time: Option<f64>,
+    delta_time: f32,
+    total_time: f32,
+    frame_timer: FrameTimer,
+    paused: bool,
+    clear_color: Color32,
 }
 
 impl Default for Application {
@@ -51,8 +80,8 @@ impl Default for Application {
         // Initialize the JS panic hook
         set_panic_hook();
         Self {
-            renderer: Renderer::default(),
-            input: InputManager::new(),
+            renderer: Some(Renderer::default()),
+            ..Self::new_headless()
         }
     }
 }
@@ -69,127 +98,352 @@ impl Application {
     /// Set up data before render loop.
     #[wasm_bindgen]
     pub fn init(&mut self) {
-        let renderer = &mut self.renderer;
-
-        renderer
-            .gl
-            .blend_func(GL::SRC_ALPHA, GL::ONE_MINUS_SRC_ALPHA);
-        renderer.gl.enable(GL::BLEND);
-        // Initialize the default Shader
-        renderer.init_shader();
-
-        let u_tex0 = renderer.program.get_uniform_location(&renderer.gl, "uTex0");
-        renderer.gl.uniform1i(u_tex0.as_ref(), 0);
-
-        renderer.add_texture("TILEMAP", Texture::new_with_texture_id(&renderer.gl, 0));
-        renderer.add_texture("SHREK", Texture::new_with_texture_id(&renderer.gl, 1));
-
-        renderer.use_texture("WHITE");
+        if let Some(renderer) = self.renderer.as_ref() {
+            if let Some(canvas) = renderer
+                .gl
+                .canvas()
+                .and_then(|canvas| canvas.dyn_into::<Canvas>().ok())
+            {
+                // Without this, the browser does not restore a lost context
+                let _ = handle_context_loss(&canvas);
+            }
+        }
+        self.init_resources();
 
+        let scene = &mut self.scene;
         let simple = ParticleSystem::new_from_emission_and_position(
             particle::ParticleProps::default(),
             0.0,
             0.0,
         );
-        renderer.add_component("DEFAULT", Box::new(simple));
+        scene.add_component("DEFAULT", Box::new(simple));
 
         let fire = ParticleSystem::new_from_emission_and_position(
             particle::ParticleProps::fire(),
             -5.0,
             0.0,
         );
-        renderer.add_component("FIRE", Box::new(fire));
+        scene.add_component("FIRE", Box::new(fire));
 
         let smoke = ParticleSystem::new_from_emission_and_position(
             particle::ParticleProps::smoke(),
             5.0,
             0.0,
         );
-        renderer.add_component("SMOKE", Box::new(smoke));
+        scene.add_component("SMOKE", Box::new(smoke));
+
+        // The Player lands on a floor near the bottom of the screen
+        let player = Player {
+            floor: Some(4.0),
+            ..Player::new_with_position(0.0, 0.0)
+        };
+        scene.add_component("PLAYER", Box::new(player));
+
+        scene.init_components();
+    }
+
+    /// Check if the WebGL context has been lost, in which case [`render`](Application::render) does nothing.
+    ///
+    /// A headless [`Application`] has no context to lose, so this is always false.
+    #[wasm_bindgen]
+    pub fn is_context_lost(&self) -> bool {
+        self.renderer
+            .as_ref()
+            .is_some_and(|renderer| renderer.is_context_lost())
+    }
+
+    /// Called when the WebGL context is restored after being lost, to recreate the shaders and textures.
+    ///
+    /// The [`Components`](component::Component) are kept as they were.
+    #[wasm_bindgen]
+    pub fn on_context_restored(&mut self) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.restore_context();
+        }
+        self.init_resources();
+    }
+
+    /// Replace the default [`Shader`] with one compiled from the given sources, without rebuilding.
+    ///
+    /// Returns the compile or link log on failure, in which case the current [`Shader`] stays active.
+    #[wasm_bindgen]
+    pub fn reload_shaders(&mut self, vertex_src: &str, fragment_src: &str) -> Result<(), String> {
+        self.renderer
+            .as_mut()
+            .ok_or("The Application has no Renderer.")?
+            .reload_shader(vertex_src, fragment_src)
+    }
+
+    /// Called when window gets resized, with the new size of the canvas in CSS pixels.
+    ///
+    /// Sets the backing size of the canvas using the device pixel ratio, and updates the viewport and the [`Camera`].
+    #[wasm_bindgen]
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.resize_with_pixel_ratio(
+                width as f32,
+                height as f32,
+                device_pixel_ratio() as f32,
+            );
+        }
+    }
+
+    /// Enable or disable smoothing of the delta time measured by [`render`](Application::render).
+    ///
+    /// The last few frame times are averaged, discarding outliers, to reduce micro-stutter.
+    #[wasm_bindgen]
+    pub fn set_smooth_delta(&mut self, enabled: bool) {
+        self.delta_smoother = enabled.then(DeltaSmoother::new);
+    }
+
+    /// Set the duration of a simulation step, in seconds.
+    ///
+    /// [`render`](Application::render) runs [`step`](Application::step) as many times as needed to keep up with the frame time.
+    #[wasm_bindgen]
+    pub fn set_fixed_timestep(&mut self, step: f32) {
+        self.timestep.set_step(step);
+    }
+
+    /// Set the color the screen is cleared with, from a hex string such as `"#1e90ff"`.
+    ///
+    /// Returns an error if the string is not a valid color, in which case the clear color is unchanged.
+    #[wasm_bindgen]
+    pub fn set_clear_color_hex(&mut self, hex: &str) -> Result<(), String> {
+        self.clear_color = Color32::from_hex(hex)?;
+        Ok(())
+    }
+
+    /// Pause the simulation.
+    ///
+    /// Frames are still drawn, but [`step`](Application::step) is not run and the time passed to shaders is frozen.
+    /// Key presses made while paused are discarded, so they do not fire once the simulation resumes.
+    #[wasm_bindgen]
+    pub fn pause(&mut self) {
+        self.paused = true;
+        self.timestep.reset();
+    }
+
+    /// Resume the simulation, continuing from the time it was paused at.
+    #[wasm_bindgen]
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Check if the simulation is paused.
+    #[wasm_bindgen]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Get the frames per second, averaged over the last few frames.
+    #[wasm_bindgen(getter)]
+    pub fn fps(&self) -> f32 {
+        self.frame_timer.fps()
+    }
+
+    /// Get the frame time in milliseconds, averaged over the last few frames.
+    #[wasm_bindgen(getter)]
+    pub fn frame_ms(&self) -> f32 {
+        self.frame_timer.average_frame_ms()
+    }
+
+    /// Render at a fixed resolution, and upscale to the canvas with nearest filtering.
+    #[wasm_bindgen]
+    pub fn set_render_resolution(&mut self, width: u32, height: u32) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.set_render_resolution(width, height);
+        }
+    }
 
-        renderer.init_components();
+    /// Enable or disable depth testing, such as for 3D models. It is disabled by default for 2D sprites.
+    #[wasm_bindgen]
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.set_depth_test(enabled);
+        }
     }
 
-    /// Called when window gets resized.
+    /// Enable or disable the logarithmic depth buffer.
     #[wasm_bindgen]
-    pub fn resize(&mut self, width: f32, height: f32) {
-        self.renderer.resize(width, height);
+    pub fn set_log_depth(&mut self, enabled: bool) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.set_log_depth(enabled);
+        }
+    }
+
+    /// Enable tonemapping with a given exposure, to keep bright effects from clipping.
+    #[wasm_bindgen]
+    pub fn set_exposure(&mut self, exposure: f32) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.enable_tonemapping(exposure);
+        }
     }
 
     /// Called when a keyboard input event is generated.
+    ///
+    /// Takes the ASCII code of the character typed by the key, such as `event.key.charCodeAt(0)`, which is converted to a [`Key`].
+    /// This is not the `keyCode` of the `KeyboardEvent`, which uses different codes for some keys. Prefer [`input_code`](Application::input_code).
+    #[wasm_bindgen]
+    pub fn input(&mut self, key_char: u8, is_down: bool) {
+        self.key_input(Key::from(key_char), is_down);
+    }
+
+    /// Called when a keyboard input event is generated.
+    ///
+    /// Takes the `key` string of the `KeyboardEvent`, which is converted to a [`Key`].
+    #[wasm_bindgen]
+    pub fn input_key(&mut self, key: &str, is_down: bool) {
+        self.key_input(Key::from(key), is_down);
+    }
+
+    /// Called when a keyboard input event is generated.
+    ///
+    /// Takes the `code` string of the `KeyboardEvent`, which is converted with [`Key::from_code`].
+    /// Prefer this to [`input_key`](Application::input_key), as the `key` of a digit changes when Shift is held.
+    #[wasm_bindgen]
+    pub fn input_code(&mut self, code: &str, is_down: bool) {
+        self.key_input(Key::from_code(code), is_down);
+    }
+
+    /// Handles Mouse movement, with the position in pixels from the top-left of the canvas.
+    ///
+    /// The position is stored in pixels, see [`mouse_world_position`](Application::mouse_world_position) for the position in the world.
+    #[wasm_bindgen]
+    pub fn mouse_move(&mut self, mouse_x: i32, mouse_y: i32) {
+        self.input
+            .set_mouse_position(mouse_x as f32, mouse_y as f32);
+    }
+
+    /// Handles Mouse wheel scrolling, using the `deltaY` of a `WheelEvent`.
     #[wasm_bindgen]
-    pub fn input(&mut self, key_code: u8, is_down: bool) {
+    pub fn mouse_scroll(&mut self, delta_y: f32) {
+        self.input.add_scroll(delta_y);
+    }
+
+    /// Handles Mouse button presses, using the `button` of a `MouseEvent`.
+    #[wasm_bindgen]
+    pub fn mouse_button(&mut self, button: i16, is_down: bool) {
+        let button = MouseButton::from(button);
         if is_down {
-            self.input.key_down(key_code);
+            self.input.mouse_button_down(button);
         } else {
-            self.input.key_up(key_code);
+            self.input.mouse_button_up(button);
         }
     }
 
-    /// Handles Mouse movement.
+    /// Advance the simulation by a given delta time, in seconds.
+    ///
+    /// Handles input and updates the [`Components`](component::Component), without issuing any draw calls.
+    /// This is called by [`render`](Application::render) with the fixed timestep, but can also be called directly to step the game logic deterministically.
+    /// The [`Components`](component::Component) live in the [`Scene`] rather than the [`Renderer`], so a headless [`Application`] can be stepped without a WebGL context.
     #[wasm_bindgen]
-    pub fn mouse_move(&mut self, mouse_x: i32, mouse_y: i32) {
-        let (x, y) = self
-            .renderer
-            .camera
-            .screen_to_world_coordinates(mouse_x as f32, mouse_y as f32);
-        self.input.mouse_position = Vec2::new(x, y);
+    pub fn step(&mut self, delta_time: f32) {
+        let mouse_world_position = self.mouse_world_position();
+        let scene = &mut self.scene;
+
+        if self.input.get_key_state(Key::R) {
+            scene.init_components();
+        }
+        if self.input.get_key_state(Key::Num1) {
+            if let Ok(fire) = scene.get_mut_component::<ParticleSystem>("FIRE") {
+                fire.toggle_alive();
+            }
+        }
+        if self.input.get_key_state(Key::Num2) {
+            if let Ok(simple) = scene.get_mut_component::<ParticleSystem>("DEFAULT") {
+                simple.toggle_alive();
+            }
+        }
+        if self.input.get_key_state(Key::Num3) {
+            if let Ok(smoke) = scene.get_mut_component::<ParticleSystem>("SMOKE") {
+                smoke.toggle_alive();
+            }
+        }
+        // The Y axis points down the screen, so the movement axis is flipped vertically
+        let movement = self
+            .bindings
+            .movement_axis(&self.input)
+            .component_mul(&Vec2::new(1.0, -1.0));
+
+        if let Ok(player) = scene.get_mut_component::<Player>("PLAYER") {
+            player.handle_input(&self.input, &self.bindings);
+        }
+
+        if let (Ok(simple), Some(position)) = (
+            scene.get_mut_component::<ParticleSystem>("DEFAULT"),
+            mouse_world_position,
+        ) {
+            simple.transform.position = position;
+        }
+
+        if let Ok(smoke) = scene.get_mut_component::<ParticleSystem>("SMOKE") {
+            if smoke.alive {
+                smoke.transform.position += movement * delta_time;
+            }
+        }
+
+        scene.update_components(delta_time);
     }
 
     /// Renders a new frame.
     ///
     /// Called every frame, and draws its output onto the [Canvas](web_sys::HtmlCanvasElement).
+    ///
+    /// The time since the last frame is measured using [`Performance`](web_sys::Performance), and passed to [`render_with_delta`](Application::render_with_delta).
     #[wasm_bindgen]
-    pub fn render(&mut self, delta_time: u32) {
-        let renderer = &mut self.renderer;
-        let delta_time = delta_time as f32 / 1000.0;
-
-        renderer.clear([0.5, 0.2, 0.3, 1.0]);
+    pub fn render(&mut self) {
+        let now = now_sec();
+        let delta_time = self.last_time.map_or(0.0, |last| (now - last) as f32);
+        self.last_time = Some(now);
+        self.render_with_delta(delta_time);
+    }
 
-        if self.input.get_key_state(b'R') {
-            renderer.init_components();
-        }
-        if self.input.get_key_state(b'1') {
-            renderer
-                .get_mut_component::<ParticleSystem>("FIRE")
-                .unwrap()
-                .toggle_alive();
+    /// Renders a new frame, using a given delta time in seconds instead of measuring it.
+    ///
+    /// The frame time is accumulated, and the simulation is advanced in fixed steps, so it runs the same at any frame rate.
+    #[wasm_bindgen]
+    pub fn render_with_delta(&mut self, delta_time: f32) {
+        if self.is_context_lost() {
+            return;
         }
-        if self.input.get_key_state(b'2') {
-            renderer
-                .get_mut_component::<ParticleSystem>("DEFAULT")
-                .unwrap()
-                .toggle_alive();
+        let mut delta_time = delta_time.max(0.0);
+        if let Some(smoother) = self.delta_smoother.as_mut() {
+            delta_time = smoother.smooth(delta_time);
         }
-        if self.input.get_key_state(b'3') {
-            renderer
-                .get_mut_component::<ParticleSystem>("SMOKE")
-                .unwrap()
-                .toggle_alive();
+        self.delta_time = delta_time;
+        self.frame_timer.record(delta_time);
+        if !self.paused {
+            self.total_time += delta_time;
         }
-        let horizontal =
-            self.input.get_key_state(b'D') as i32 - self.input.get_key_state(b'A') as i32;
-        let vertical =
-            self.input.get_key_state(b'S') as i32 - self.input.get_key_state(b'W') as i32;
 
-        let simple = renderer
-            .get_mut_component::<ParticleSystem>("DEFAULT")
-            .unwrap();
+        if let Some(renderer) = self.renderer.as_mut() {
+            // Gamepads are polled from the browser, so a headless Application does not read them
+            self.input.update_gamepads();
+            renderer.begin_frame(self.clear_color.to_array());
+            renderer.set_time(self.total_time);
+        }
 
-        simple.transform.position = self.input.mouse_position;
+        let steps = if self.paused {
+            0
+        } else {
+            self.timestep.advance(delta_time)
+        };
+        for _ in 0..steps {
+            self.step(self.timestep.step());
+        }
 
-        let smoke = renderer
-            .get_mut_component::<ParticleSystem>("SMOKE")
-            .unwrap();
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.draw_components(&self.scene, self.timestep.alpha());
+            gl::gl_check(&renderer.gl, "drawing components");
 
-        if smoke.alive {
-            smoke.transform.position +=
-                Vec2::new(horizontal as f32 * delta_time, vertical as f32 * delta_time);
+            renderer.end_frame();
+            gl::gl_check(&renderer.gl, "ending the frame");
         }
 
-        renderer.update_components(delta_time);
-
-        renderer.draw_components();
+        // Keep input events of frames without a step, so they are not missed, but drop those made while paused
+        if steps > 0 || self.paused {
+            self.input.new_frame();
+        }
 
         // self.renderer.begin_layer();
         // self.renderer.add_quad(Quad::default());
@@ -198,3 +452,127 @@ impl Application {
         // self.renderer.delete_layer();
     }
 }
+
+impl Application {
+    /// Create a new [`Application`] without a [`Renderer`], that does not need a WebGL context.
+    ///
+    /// The simulation can be advanced with [`step`](Application::step) or [`render_with_delta`](Application::render_with_delta), which skips drawing. This is useful to test game logic natively.
+    pub fn new_headless() -> Self {
+        Self {
+            renderer: None,
+            scene: Scene::new(),
+            input: InputManager::new(),
+            bindings: Bindings::default(),
+            delta_smoother: None,
+            timestep: FixedTimestep::new(),
+            last_time: None,
+            delta_time: 0.0,
+            total_time: 0.0,
+            frame_timer: FrameTimer::new(),
+            paused: false,
+            clear_color: DEFAULT_CLEAR_COLOR,
+        }
+    }
+
+    /// Create the WebGL resources used by the demo, replacing any that exist.
+    fn init_resources(&mut self) {
+        let renderer = match self.renderer.as_mut() {
+            Some(renderer) => renderer,
+            None => return,
+        };
+
+        renderer.set_blend_mode(BlendMode::Alpha);
+        // Initialize the default Shader
+        renderer.init_shader();
+
+        renderer.add_texture("TILEMAP", Texture::new_with_texture_id(&renderer.gl, 0));
+        renderer.add_texture("SHREK", Texture::new_with_texture_id(&renderer.gl, 1));
+
+        renderer.use_texture("WHITE");
+        gl::gl_check(&renderer.gl, "loading textures");
+    }
+
+    /// Set the state of a [`Key`] in the [`InputManager`].
+    fn key_input(&mut self, key: Key, is_down: bool) {
+        if is_down {
+            self.input.key_down(key);
+        } else {
+            self.input.key_up(key);
+        }
+    }
+
+    /// Get the delta time of the last frame, in seconds.
+    pub fn delta_time(&self) -> f32 {
+        self.delta_time
+    }
+
+    /// Get the total time simulated so far, in seconds, not counting time spent paused.
+    pub fn total_time(&self) -> f32 {
+        self.total_time
+    }
+
+    /// Get how far the current frame is between the last simulation step and the next, from `0.0` to `1.0`.
+    ///
+    /// [`render`](Application::render) draws the [`Components`](component::Component) interpolated by this amount, see [`Component::get_interpolated_quads`](component::Component::get_interpolated_quads).
+    pub fn interpolation_alpha(&self) -> f32 {
+        self.timestep.alpha()
+    }
+
+    /// Get the color the screen is cleared with.
+    pub fn clear_color(&self) -> Color32 {
+        self.clear_color
+    }
+
+    /// Set the color the screen is cleared with.
+    pub fn set_clear_color(&mut self, color: Color32) {
+        self.clear_color = color;
+    }
+
+    /// Get the position of the mouse in world space, using the [`Camera`] of the [`Renderer`].
+    ///
+    /// Returns [`None`] if the [`Application`] is headless.
+    pub fn mouse_world_position(&self) -> Option<Vec2> {
+        let position = self.input.mouse_position;
+        self.renderer.as_ref().map(|renderer| {
+            let (x, y) = renderer
+                .camera
+                .screen_to_world_coordinates(position.x, position.y);
+            Vec2::new(x, y)
+        })
+    }
+
+    /// Get a reference to the [`InputManager`] of the [`Application`].
+    pub fn input_manager(&self) -> &InputManager {
+        &self.input
+    }
+
+    /// Get a reference to the [`Bindings`] of the [`Application`].
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    /// Get a mutable reference to the [`Bindings`] of the [`Application`], such as to remap controls.
+    pub fn bindings_mut(&mut self) -> &mut Bindings {
+        &mut self.bindings
+    }
+
+    /// Get a reference to the [`Renderer`] of the [`Application`], or [`None`] if it is headless.
+    pub fn renderer(&self) -> Option<&Renderer> {
+        self.renderer.as_ref()
+    }
+
+    /// Get a mutable reference to the [`Renderer`] of the [`Application`], or [`None`] if it is headless.
+    pub fn renderer_mut(&mut self) -> Option<&mut Renderer> {
+        self.renderer.as_mut()
+    }
+
+    /// Get a reference to the [`Scene`] of the [`Application`].
+    pub fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    /// Get a mutable reference to the [`Scene`] of the [`Application`], such as to add [`Components`](component::Component).
+    pub fn scene_mut(&mut self) -> &mut Scene {
+        &mut self.scene
+    }
+}
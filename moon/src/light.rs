@@ -0,0 +1,112 @@
+//! The [`Light`] struct, and the [`LightPass`] used to composite lights over the scene.
+
+use crate::framebuffer::Framebuffer;
+use crate::gl::Bind;
+use crate::postprocess::{draw_fullscreen, SCENE_SAMPLER};
+use crate::renderer::Quad;
+use crate::shader::Shader;
+use crate::{Color32, Mat4, Vec2, GL};
+
+/// Default ambient light, used where no [`Light`] reaches.
+pub const DEFAULT_AMBIENT_LIGHT: Color32 = Color32(0.1, 0.1, 0.1, 1.0);
+
+/// A radial [`Light`], that fades out from its position to its radius.
+#[derive(Debug, Clone, Copy)]
+pub struct Light {
+    /// World-space position of the [`Light`].
+    pub position: Vec2,
+    /// Radius of the [`Light`], at which it fades out completely.
+    pub radius: f32,
+    /// Color of the [`Light`]. The alpha scales its intensity.
+    pub color: Color32,
+}
+
+impl Light {
+    /// Create a new [`Light`] with a given position, radius and color.
+    pub fn new(position: Vec2, radius: f32, color: Color32) -> Self {
+        Self {
+            position,
+            radius,
+            color,
+        }
+    }
+
+    /// Get the [`Quad`] covering the [`Light`], which is shaded with a radial gradient.
+    pub fn get_quad(&self) -> Quad {
+        let size = self.radius * 2.0;
+        Quad::new_from_position_and_size_and_color(
+            self.position.x,
+            self.position.y,
+            size,
+            size,
+            self.color,
+        )
+    }
+}
+
+/// A [`LightPass`] accumulates [`Lights`](Light) into a light [`Framebuffer`], and multiplies the scene by it.
+#[derive(Debug)]
+pub struct LightPass {
+    /// The [`Framebuffer`] the lights are accumulated into.
+    pub framebuffer: Framebuffer,
+    shader: Shader,
+    composite: Shader,
+}
+
+impl LightPass {
+    /// Create a new [`LightPass`] with a given size.
+    pub fn new(gl: &GL, width: u32, height: u32) -> Self {
+        let shader = Shader::new_with_sources(
+            gl,
+            include_str!("../res/shader/default.vert.glsl"),
+            include_str!("../res/shader/light.frag.glsl"),
+            "Light Shader",
+        )
+        .expect("Could not create Light Shader!");
+        let composite = Shader::new_with_sources(
+            gl,
+            include_str!("../res/shader/post.vert.glsl"),
+            include_str!("../res/shader/blit.frag.glsl"),
+            "Light Composite Shader",
+        )
+        .expect("Could not create Light Composite Shader!");
+
+        Self {
+            framebuffer: Framebuffer::new_hdr(gl, width, height),
+            shader,
+            composite,
+        }
+    }
+
+    /// Resize the [`Framebuffer`] of the [`LightPass`].
+    pub fn resize(&mut self, gl: &GL, width: u32, height: u32) {
+        self.framebuffer = Framebuffer::new_hdr(gl, width, height);
+    }
+
+    /// Bind the light [`Framebuffer`] and clear it to the ambient light.
+    ///
+    /// Light [`Quads`](Quad) drawn afterwards are blended additively, using the given view and projection matrices.
+    pub fn begin(&self, gl: &GL, ambient: Color32, view: &Mat4, projection: &Mat4) {
+        self.framebuffer.bind(gl);
+        gl.clear_color(ambient.0, ambient.1, ambient.2, 1.0);
+        gl.clear(GL::COLOR_BUFFER_BIT);
+
+        self.shader.bind(gl);
+        self.shader.set_uniform_mat4(gl, "uView", view);
+        self.shader.set_uniform_mat4(gl, "uProj", projection);
+        gl.blend_func(GL::ONE, GL::ONE);
+    }
+
+    /// Multiply the currently bound render target by the light [`Framebuffer`], sampling it from the given texture unit.
+    pub fn composite(&self, gl: &GL, unit: u32) {
+        self.composite.bind(gl);
+        self.framebuffer.texture.bind_to_unit(gl, unit);
+
+        self.composite
+            .set_uniform_i32(gl, SCENE_SAMPLER, unit as i32);
+
+        gl.blend_func(GL::DST_COLOR, GL::ZERO);
+        draw_fullscreen(gl);
+        gl.blend_func(GL::SRC_ALPHA, GL::ONE_MINUS_SRC_ALPHA);
+    }
+}
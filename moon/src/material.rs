@@ -0,0 +1,148 @@
+//! The [`Material`] struct, and the [`UniformValue`] enum.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::gl::Bind;
+use crate::shader::Shader;
+use crate::{Mat4, Vec2, Vec3, Vec4, GL};
+
+/// The value of a single uniform of a [`Material`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UniformValue {
+    /// An [`f32`] uniform.
+    F32(f32),
+    /// An [`i32`] uniform, such as a sampler.
+    I32(i32),
+    /// A [`Vec2`] uniform.
+    Vec2(Vec2),
+    /// A [`Vec3`] uniform.
+    Vec3(Vec3),
+    /// A [`Vec4`] uniform, such as a color.
+    Vec4(Vec4),
+    /// A [`Mat4`] uniform.
+    Mat4(Mat4),
+}
+
+impl From<f32> for UniformValue {
+    fn from(value: f32) -> Self {
+        UniformValue::F32(value)
+    }
+}
+
+impl From<i32> for UniformValue {
+    fn from(value: i32) -> Self {
+        UniformValue::I32(value)
+    }
+}
+
+impl From<Vec2> for UniformValue {
+    fn from(value: Vec2) -> Self {
+        UniformValue::Vec2(value)
+    }
+}
+
+impl From<Vec3> for UniformValue {
+    fn from(value: Vec3) -> Self {
+        UniformValue::Vec3(value)
+    }
+}
+
+impl From<Vec4> for UniformValue {
+    fn from(value: Vec4) -> Self {
+        UniformValue::Vec4(value)
+    }
+}
+
+impl From<Mat4> for UniformValue {
+    fn from(value: Mat4) -> Self {
+        UniformValue::Mat4(value)
+    }
+}
+
+impl UniformValue {
+    /// Set the [`UniformValue`] on a bound [`Shader`].
+    pub fn apply(&self, gl: &GL, shader: &Shader, name: &str) {
+        match self {
+            UniformValue::F32(value) => shader.set_uniform_f32(gl, name, *value),
+            UniformValue::I32(value) => shader.set_uniform_i32(gl, name, *value),
+            UniformValue::Vec2(value) => shader.set_uniform_vec2(gl, name, value),
+            UniformValue::Vec3(value) => shader.set_uniform_vec3(gl, name, value),
+            UniformValue::Vec4(value) => shader.set_uniform_vec4(gl, name, value),
+            UniformValue::Mat4(value) => shader.set_uniform_mat4(gl, name, value),
+        }
+    }
+}
+
+/// A [`Shader`] along with the values of its uniforms, used to draw a kind of object.
+///
+/// A [`Material`] is bound using [`Renderer::bind_material`](crate::renderer::Renderer::bind_material).
+/// Several [`Materials`](Material) can share the same [`Shader`], with different uniform values.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::rc::Rc;
+/// use moon_engine::material::Material;
+/// use moon_engine::shader::Shader;
+/// use moon_engine::*;
+/// # let gl = gl::get_context();
+///
+/// let material = Material::new(Rc::new(Shader::new(&gl))).with_uniform("uColor", Vec4::new(1.0, 0.0, 0.0, 1.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Material {
+    shader: Rc<Shader>,
+    uniforms: BTreeMap<String, UniformValue>,
+}
+
+impl Material {
+    /// Create a new [`Material`] using a [`Shader`], with no uniform values.
+    pub fn new(shader: Rc<Shader>) -> Self {
+        Self {
+            shader,
+            uniforms: BTreeMap::new(),
+        }
+    }
+
+    /// Get the [`Shader`] of the [`Material`].
+    pub fn shader(&self) -> &Rc<Shader> {
+        &self.shader
+    }
+
+    /// Set the value of a uniform, replacing any previous value.
+    pub fn set_uniform(&mut self, name: &str, value: impl Into<UniformValue>) {
+        self.uniforms.insert(name.to_string(), value.into());
+    }
+
+    /// Set the value of a uniform, and return the [`Material`].
+    pub fn with_uniform(mut self, name: &str, value: impl Into<UniformValue>) -> Self {
+        self.set_uniform(name, value);
+        self
+    }
+
+    /// Get the value of a uniform, if it has been set.
+    pub fn uniform(&self, name: &str) -> Option<UniformValue> {
+        self.uniforms.get(name).copied()
+    }
+
+    /// Remove the value of a uniform, returning it if it was set.
+    pub fn remove_uniform(&mut self, name: &str) -> Option<UniformValue> {
+        self.uniforms.remove(name)
+    }
+
+    /// Iterate over the uniforms of the [`Material`], in order of their names.
+    pub fn uniforms(&self) -> impl Iterator<Item = (&str, &UniformValue)> + '_ {
+        self.uniforms
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// Bind the [`Shader`] of the [`Material`], and set all of its uniform values.
+    pub fn apply(&self, gl: &GL) {
+        self.shader.bind(gl);
+        for (name, value) in self.uniforms.iter() {
+            value.apply(gl, &self.shader, name);
+        }
+    }
+}
@@ -14,9 +14,15 @@ pub type Vec4 = nalgebra::Vector4<f32>;
 /// An alias to [`nalgebra::Matrix4<f32>`].
 pub type Mat4 = nalgebra::Matrix4<f32>;
 
+/// An alias to [`nalgebra::UnitQuaternion<f32>`].
+pub type Quat = nalgebra::UnitQuaternion<f32>;
+
 /// An alias to [`nalgebra::Orthographic3<f32>`].
 pub type Ortho = nalgebra::Orthographic3<f32>;
 
+/// An alias to [`nalgebra::Perspective3<f32>`].
+pub type Perspective = nalgebra::Perspective3<f32>;
+
 /// An RGBA color represented with four [`f32`]s.
 ///
 /// [`Color32`] is definied as a tuple-styled struct, with public members.
@@ -24,7 +30,7 @@ pub type Ortho = nalgebra::Orthographic3<f32>;
 /// # Examples
 ///
 /// ```
-/// use moon::math::Color32;
+/// use moon_engine::math::Color32;
 /// let color = Color32::default();
 /// ```
 #[derive(Clone, Copy, Debug)]
@@ -107,12 +113,136 @@ impl Color32 {
         self.3
     }
 
+    /// Create a new [`Color32`] from four [`u8`] components.
+    pub fn from_rgba_u8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::from(Color8(r, g, b, a))
+    }
+
+    /// Parse a [`Color32`] from a hex string in the format `#RRGGBB` or `#RRGGBBAA`.
+    ///
+    /// The `#` is optional. Colors without an alpha component are opaque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::math::Color32;
+    ///
+    /// let color = Color32::from_hex("#FF00FF").unwrap();
+    /// assert_eq!(color.to_array(), Color32::MAGENTA.to_array());
+    /// assert!(Color32::from_hex("#F0F").is_err());
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, String> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if !(digits.len() == 6 || digits.len() == 8) || !digits.is_ascii() {
+            return Err(format!("Invalid hex color \"{}\".", hex));
+        }
+        let component = |index: usize| {
+            u8::from_str_radix(&digits[index..index + 2], 16)
+                .map_err(|_| format!("Invalid hex color \"{}\".", hex))
+        };
+        let alpha = if digits.len() == 8 {
+            component(6)?
+        } else {
+            255
+        };
+        Ok(Self::from_rgba_u8(
+            component(0)?,
+            component(2)?,
+            component(4)?,
+            alpha,
+        ))
+    }
+
+    /// Get the [`Color32`] as an array of four [`f32`], for uploading to the GPU.
+    pub const fn to_array(&self) -> [f32; 4] {
+        [self.0, self.1, self.2, self.3]
+    }
+
+    /// Create a new, opaque [`Color32`] from a hue in degrees, and a saturation and value between 0 and 1.
+    ///
+    /// The hue wraps around at 360 degrees, and the saturation and value are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::math::Color32;
+    ///
+    /// assert_eq!(Color32::from_hsv(0.0, 1.0, 1.0).to_array(), [1.0, 0.0, 0.0, 1.0]);
+    /// ```
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let hue = hue.rem_euclid(360.0) / 60.0;
+        let saturation = clamp(saturation, 0.0, 1.0);
+        let value = clamp(value, 0.0, 1.0);
+
+        let chroma = value * saturation;
+        let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let (r, g, b) = match hue as u32 {
+            0 => (chroma, second, 0.0),
+            1 => (second, chroma, 0.0),
+            2 => (0.0, chroma, second),
+            3 => (0.0, second, chroma),
+            4 => (second, 0.0, chroma),
+            _ => (chroma, 0.0, second),
+        };
+        let min = value - chroma;
+        Self(r + min, g + min, b + min, 1.0)
+    }
+
+    /// Get the hue in degrees, and the saturation and value of the [`Color32`]. The alpha is ignored.
+    ///
+    /// Grays have a hue of 0.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (r, g, b) = (self.0, self.1, self.2);
+        let max = r.max(g).max(b);
+        let chroma = max - r.min(g).min(b);
+
+        let hue = if chroma == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / chroma).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / chroma + 2.0)
+        } else {
+            60.0 * ((r - g) / chroma + 4.0)
+        };
+        let saturation = if max == 0.0 { 0.0 } else { chroma / max };
+        (hue, saturation, max)
+    }
+
+    /// Linearly interpolate between the [`Color32`] and another, including alpha.
+    ///
+    /// The factor is clamped between 0 and 1.
+    pub fn lerp(&self, other: &Color32, factor: f32) -> Self {
+        let factor = clamp(factor, 0.0, 1.0);
+        let mix = |from: f32, to: f32| from + (to - from) * factor;
+        Self(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+            mix(self.3, other.3),
+        )
+    }
+
     /// Pure White Color.
     pub const WHITE: Color32 = Color32(1.0, 1.0, 1.0, 1.0);
     /// Pure Black Color.
     pub const BLACK: Color32 = Color32(0.0, 0.0, 0.0, 1.0);
+    /// Pure Red Color.
+    pub const RED: Color32 = Color32(1.0, 0.0, 0.0, 1.0);
+    /// Pure Green Color.
+    pub const GREEN: Color32 = Color32(0.0, 1.0, 0.0, 1.0);
+    /// Pure Blue Color.
+    pub const BLUE: Color32 = Color32(0.0, 0.0, 1.0, 1.0);
+    /// Yellow Color.
+    pub const YELLOW: Color32 = Color32(1.0, 1.0, 0.0, 1.0);
+    /// Cyan Color.
+    pub const CYAN: Color32 = Color32(0.0, 1.0, 1.0, 1.0);
     /// Magenta Color.
     pub const MAGENTA: Color32 = Color32(1.0, 0.0, 1.0, 1.0);
+    /// Middle Gray Color.
+    pub const GRAY: Color32 = Color32(0.5, 0.5, 0.5, 1.0);
+    /// Fully transparent Black.
+    pub const TRANSPARENT: Color32 = Color32(0.0, 0.0, 0.0, 0.0);
     /// All fields zeroed out.
     pub const ZEROES: Color32 = Color32(0.0, 0.0, 0.0, 0.0);
 }
@@ -167,7 +297,7 @@ impl From<Color8> for Color32 {
 /// # Examples
 ///
 /// ```
-/// use moon::math::Color8;
+/// use moon_engine::math::Color8;
 /// let color = Color8::default();
 /// ```
 #[derive(Clone, Copy)]
@@ -325,6 +455,98 @@ impl Random for Color32 {
     }
 }
 
+/// A small, seedable pseudo-random number generator.
+///
+/// Unlike [`Random`], a [`Rng`] does not use JavaScript, and gives the same sequence for the same seed.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::math::Rng;
+///
+/// let mut a = Rng::new(42);
+/// let mut b = Rng::new(42);
+/// assert_eq!(a.next_u32(), b.next_u32());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl Rng {
+    /// Seed used in place of `0`, which would only ever generate zeroes.
+    const FALLBACK_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    /// Create a new [`Rng`] with a given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: if seed == 0 { Self::FALLBACK_SEED } else { seed },
+        }
+    }
+
+    /// Create a new [`Rng`] with a random seed.
+    ///
+    /// The seed comes from `Math.random()` on the web, and from the randomly keyed hasher of the standard library elsewhere.
+    pub fn new_random() -> Self {
+        Self::new(random_seed())
+    }
+
+    /// Get the next random [`u32`].
+    pub fn next_u32(&mut self) -> u32 {
+        // xorshift64*
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        (self.state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 32) as u32
+    }
+
+    /// Get a random [`f32`] between `0.0` (inclusive) and `1.0` (exclusive).
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Get a random [`f32`] between `min` and `max`.
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        self.next_f32() * (max - min) + min
+    }
+
+    /// Get a random [`Vec2`], with each component between those of `min` and `max`.
+    pub fn range_vec2(&mut self, min: Vec2, max: Vec2) -> Vec2 {
+        Vec2::new(self.range(min.x, max.x), self.range(min.y, max.y))
+    }
+
+    /// Get a random [`Color32`], with each component between those of `min` and `max`.
+    pub fn range_color(&mut self, min: Color32, max: Color32) -> Color32 {
+        Color32(
+            self.range(min.0, max.0),
+            self.range(min.1, max.1),
+            self.range(min.2, max.2),
+            self.range(min.3, max.3),
+        )
+    }
+}
+
+/// Get a random seed for a [`Rng`].
+#[cfg(target_arch = "wasm32")]
+fn random_seed() -> u64 {
+    (js_sys::Math::random() * u64::MAX as f64) as u64
+}
+
+/// Get a random seed for a [`Rng`].
+#[cfg(not(target_arch = "wasm32"))]
+fn random_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    RandomState::new().build_hasher().finish()
+}
+
 /// Linearly interpolate between two values.
 pub trait Lerp: Sized + Mul<f32, Output = Self> + Add<Self, Output = Self> {
     /// Linearly interpolate between two values.
@@ -1,4 +1,7 @@
-//! The definitions of [`Vertex`], [`Mesh`] and their implementations.
+//! The definitions of [`Vertex`], [`Mesh`], [`DynamicMesh`], [`BufferLayout`] and their implementations.
+
+use std::cell::RefCell;
+use std::ops::Range;
 
 use web_sys::{WebGlBuffer, WebGlVertexArrayObject};
 
@@ -27,6 +30,145 @@ impl Default for Vertex {
     }
 }
 
+impl Vertex {
+    /// Get the [`BufferLayout`] of a [`Vertex`].
+    ///
+    /// Position, UV co-ordinates and color are bound to attribute locations 0, 1 and 2 respectively.
+    pub fn layout() -> BufferLayout {
+        BufferLayout::new(std::mem::size_of::<Vertex>() as i32, 0)
+            .with_attribute(0, 2, 0)
+            .with_attribute(1, 2, 8)
+            .with_attribute(2, 4, 16)
+    }
+}
+
+/// A single [`f32`] vertex attribute in a [`BufferLayout`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VertexAttribute {
+    /// The attribute location in the shader.
+    pub location: u32,
+    /// The number of [`f32`] components of the attribute.
+    pub size: i32,
+    /// The offset of the attribute in bytes, from the start of each element.
+    pub offset: i32,
+}
+
+/// A [`BufferLayout`] describes how the data in a buffer maps to vertex attributes.
+///
+/// A divisor of 0 advances the attributes per vertex, while a divisor of N advances them once every N instances.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BufferLayout {
+    /// The size of each element in bytes.
+    pub stride: i32,
+    /// The attribute divisor used for instancing.
+    pub divisor: u32,
+    /// The [`VertexAttributes`](VertexAttribute) in the buffer.
+    pub attributes: Vec<VertexAttribute>,
+}
+
+impl BufferLayout {
+    /// Create a new [`BufferLayout`] with a given stride and divisor, and no attributes.
+    pub fn new(stride: i32, divisor: u32) -> Self {
+        Self {
+            stride,
+            divisor,
+            attributes: Vec::new(),
+        }
+    }
+
+    /// Add a [`VertexAttribute`] to the [`BufferLayout`].
+    pub fn with_attribute(mut self, location: u32, size: i32, offset: i32) -> Self {
+        self.attributes.push(VertexAttribute {
+            location,
+            size,
+            offset,
+        });
+        self
+    }
+
+    /// Set up the attribute pointers and divisors for the currently bound `GL::ARRAY_BUFFER`.
+    pub fn setup(&self, gl: &GL) {
+        for attribute in self.attributes.iter() {
+            gl.vertex_attrib_pointer_with_i32(
+                attribute.location,
+                attribute.size,
+                GL::FLOAT,
+                false,
+                self.stride,
+                attribute.offset,
+            );
+            gl.enable_vertex_attrib_array(attribute.location);
+            gl.vertex_attrib_divisor(attribute.location, self.divisor);
+        }
+    }
+}
+
+/// The type of the indices of a [`Mesh`], when sent to the GPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexType {
+    /// 16-bit indices, for up to 65535 vertices.
+    ///
+    /// Index 65535 is reserved, as WebGL2 always uses it to restart primitives.
+    U16,
+    /// 32-bit indices.
+    U32,
+}
+
+impl IndexType {
+    /// Get the smallest [`IndexType`] that can index a given number of vertices.
+    ///
+    /// [`IndexType::U16`] is only used up to 65535 vertices, so that no vertex has the primitive restart index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::mesh::IndexType;
+    ///
+    /// assert_eq!(IndexType::for_vertex_count(4), IndexType::U16);
+    /// assert_eq!(IndexType::for_vertex_count(70000), IndexType::U32);
+    /// ```
+    pub fn for_vertex_count(count: usize) -> Self {
+        if count <= u16::MAX as usize {
+            IndexType::U16
+        } else {
+            IndexType::U32
+        }
+    }
+
+    /// Get the matching WebGL type, for use in draw calls.
+    pub fn gl_type(&self) -> u32 {
+        match self {
+            IndexType::U16 => GL::UNSIGNED_SHORT,
+            IndexType::U32 => GL::UNSIGNED_INT,
+        }
+    }
+
+    /// Get the size of a single index in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            IndexType::U16 => std::mem::size_of::<u16>(),
+            IndexType::U32 => std::mem::size_of::<u32>(),
+        }
+    }
+}
+
+/// An additional vertex buffer of a [`Mesh`], with its own [`BufferLayout`].
+#[derive(Debug)]
+pub struct VertexBuffer {
+    /// The [`BufferLayout`] of the [`VertexBuffer`].
+    pub layout: BufferLayout,
+    /// The raw data of the [`VertexBuffer`].
+    pub data: Vec<u8>,
+    buffer: WebGlBuffer,
+}
+
+impl VertexBuffer {
+    /// Get the [`WebGlBuffer`] of the [`VertexBuffer`], such as to update it without copying into [`data`](VertexBuffer::data).
+    pub fn buffer(&self) -> &WebGlBuffer {
+        &self.buffer
+    }
+}
+
 /// An indiced [`Mesh`], stored along with it's vertex array, index array and vertex buffer.
 #[derive(Debug)]
 pub struct Mesh {
@@ -36,11 +178,15 @@ pub struct Mesh {
     pub vertices: Vec<Vertex>,
     /// Indices of the Mesh.
     ///
-    /// Stored as a [`Vec`] of [`u32`].
+    /// Stored as a [`Vec`] of [`u32`], and sent to the GPU using the smallest [`IndexType`] that fits.
     pub indices: Vec<u32>,
+    /// Additional [`VertexBuffers`](VertexBuffer), such as per-instance data.
+    pub buffers: Vec<VertexBuffer>,
     vao: WebGlVertexArrayObject,
     vbo: WebGlBuffer,
     ibo: WebGlBuffer,
+    /// Reused storage for converting the indices to [`IndexType::U16`] on [`setup`](Mesh::setup).
+    index_scratch: RefCell<Vec<u16>>,
 }
 
 impl Drop for Mesh {
@@ -51,6 +197,9 @@ impl Drop for Mesh {
 
         gl.delete_buffer(Some(&self.vbo));
         gl.delete_buffer(Some(&self.ibo));
+        for buffer in self.buffers.iter() {
+            gl.delete_buffer(Some(&buffer.buffer));
+        }
         self.vertices.clear();
         self.indices.clear();
         gl.delete_vertex_array(Some(&self.vao));
@@ -73,6 +222,7 @@ impl Mesh {
         Self {
             vertices,
             indices,
+            buffers: Vec::new(),
             vao: {
                 let vao = gl
                     .create_vertex_array()
@@ -82,6 +232,7 @@ impl Mesh {
             },
             vbo: gl.create_buffer().expect("Could not create Buffer."),
             ibo: gl.create_buffer().expect("Could not create Buffer."),
+            index_scratch: RefCell::default(),
         }
     }
     /// Create a new Quad mesh with a side length of 1m
@@ -131,22 +282,214 @@ impl Mesh {
                 self.vertices.len() * std::mem::size_of::<Vertex>(),
             )
         };
-        let index_slice = unsafe {
+
+        gl.buffer_data_with_u8_array(GL::ARRAY_BUFFER, vertex_slice, GL::DYNAMIC_DRAW);
+        self.upload_indices(gl);
+
+        Vertex::layout().setup(gl);
+
+        for buffer in self.buffers.iter() {
+            gl.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer.buffer));
+            gl.buffer_data_with_u8_array(GL::ARRAY_BUFFER, &buffer.data, GL::DYNAMIC_DRAW);
+            buffer.layout.setup(gl);
+        }
+    }
+
+    /// Draw the [`Mesh`] as triangles, after it has been [`set up`](Mesh::setup).
+    pub fn draw(&self, gl: &GL) {
+        self.draw_with_mode(gl, GL::TRIANGLES);
+    }
+
+    /// Draw the [`Mesh`] using its uploaded indices, with a given primitive mode such as [`GL::LINES`].
+    pub fn draw_with_mode(&self, gl: &GL, mode: u32) {
+        use gl::Bind;
+        self.bind(gl);
+        gl.draw_elements_with_i32(
+            mode,
+            self.indices.len() as i32,
+            self.index_type().gl_type(),
+            0,
+        );
+    }
+
+    /// Draw a number of instances of the [`Mesh`] as triangles, after it has been [`set up`](Mesh::setup).
+    ///
+    /// Per-instance data is read from [`VertexBuffers`](VertexBuffer) with a non-zero divisor.
+    pub fn draw_instanced(&self, gl: &GL, count: i32) {
+        use gl::Bind;
+        self.bind(gl);
+        gl.draw_elements_instanced_with_i32(
+            GL::TRIANGLES,
+            self.indices.len() as i32,
+            self.index_type().gl_type(),
+            0,
+            count,
+        );
+    }
+
+    /// Get the [`IndexType`] used to send the indices of the [`Mesh`] to the GPU.
+    pub fn index_type(&self) -> IndexType {
+        IndexType::for_vertex_count(self.vertices.len())
+    }
+
+    /// Upload the indices of the [`Mesh`] to the bound `GL::ELEMENT_ARRAY_BUFFER`, using its [`IndexType`].
+    ///
+    /// 32-bit indices are uploaded directly, and 16-bit indices are converted into storage reused between calls.
+    fn upload_indices(&self, gl: &GL) {
+        let mut scratch = self.index_scratch.borrow_mut();
+        let index_slice = match self.index_type() {
+            IndexType::U16 => {
+                scratch.clear();
+                scratch.extend(self.indices.iter().map(|&index| index as u16));
+                unsafe {
+                    std::slice::from_raw_parts(
+                        scratch.as_ptr() as *const u8,
+                        std::mem::size_of_val(scratch.as_slice()),
+                    )
+                }
+            }
+            IndexType::U32 => unsafe {
+                std::slice::from_raw_parts(
+                    self.indices.as_ptr() as *const u8,
+                    std::mem::size_of_val(self.indices.as_slice()),
+                )
+            },
+        };
+        gl.buffer_data_with_u8_array(GL::ELEMENT_ARRAY_BUFFER, index_slice, GL::DYNAMIC_DRAW);
+    }
+
+    /// Get the indices of the [`Mesh`] as bytes, using its [`IndexType`].
+    ///
+    /// This allocates a new [`Vec`], and is meant for inspection. [`setup`](Mesh::setup) uploads the indices without it.
+    pub fn index_bytes(&self) -> Vec<u8> {
+        match self.index_type() {
+            IndexType::U16 => self
+                .indices
+                .iter()
+                .flat_map(|&index| (index as u16).to_ne_bytes())
+                .collect(),
+            IndexType::U32 => self
+                .indices
+                .iter()
+                .flat_map(|&index| index.to_ne_bytes())
+                .collect(),
+        }
+    }
+
+    /// Add a new [`VertexBuffer`] with a given [`BufferLayout`] to the [`Mesh`].
+    ///
+    /// Returns the index of the [`VertexBuffer`] in [`buffers`](Mesh::buffers). The data is sent to the GPU on [`setup`](Mesh::setup).
+    pub fn add_buffer(&mut self, gl: &GL, layout: BufferLayout, data: Vec<u8>) -> usize {
+        self.buffers.push(VertexBuffer {
+            layout,
+            data,
+            buffer: gl.create_buffer().expect("Could not create Buffer."),
+        });
+        self.buffers.len() - 1
+    }
+}
+
+/// A [`Mesh`] whose vertices are updated frequently, such as a trail or a deformable shape.
+///
+/// Changes made through [`vertex_mut`](DynamicMesh::vertex_mut) and [`vertices_mut`](DynamicMesh::vertices_mut) are tracked,
+/// and only the changed range is streamed to the GPU on [`draw`](DynamicMesh::draw).
+#[derive(Debug)]
+pub struct DynamicMesh {
+    mesh: Mesh,
+    uploaded: bool,
+    dirty: Option<Range<usize>>,
+    last_upload: Option<Range<usize>>,
+}
+
+impl DynamicMesh {
+    /// Create a new [`DynamicMesh`] with the given [`vertices`](Vertex) and indices.
+    pub fn new(gl: &GL, vertices: Vec<Vertex>, indices: Vec<u32>) -> Self {
+        Self {
+            mesh: Mesh::new(gl, vertices, indices),
+            uploaded: false,
+            dirty: None,
+            last_upload: None,
+        }
+    }
+
+    /// Get the [`vertices`](Vertex) of the [`DynamicMesh`].
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.mesh.vertices
+    }
+
+    /// Get a mutable reference to a single [`Vertex`], marking it as changed.
+    pub fn vertex_mut(&mut self, index: usize) -> Option<&mut Vertex> {
+        if index < self.mesh.vertices.len() {
+            self.mark_dirty(index..index + 1);
+        }
+        self.mesh.vertices.get_mut(index)
+    }
+
+    /// Get a mutable slice of a range of [`vertices`](Vertex), marking them as changed.
+    ///
+    /// The range is clamped to the number of vertices.
+    pub fn vertices_mut(&mut self, range: Range<usize>) -> &mut [Vertex] {
+        let len = self.mesh.vertices.len();
+        let range = range.start.min(len)..range.end.min(len);
+        self.mark_dirty(range.clone());
+        &mut self.mesh.vertices[range]
+    }
+
+    /// Get the byte range of the vertex buffer that will be streamed on the next [`draw`](DynamicMesh::draw).
+    pub fn dirty_range(&self) -> Option<Range<usize>> {
+        self.dirty.clone().map(Self::byte_range)
+    }
+
+    /// Get the byte range of the vertex buffer that was streamed on the last [`draw`](DynamicMesh::draw).
+    pub fn last_upload(&self) -> Option<Range<usize>> {
+        self.last_upload.clone()
+    }
+
+    /// Stream any changed [`vertices`](Vertex) to the GPU, and draw the [`DynamicMesh`].
+    ///
+    /// The first call uploads the whole [`Mesh`], and later calls only upload the changed range using `bufferSubData`.
+    pub fn draw(&mut self, gl: &GL) {
+        use gl::Bind;
+        let vertex_bytes = unsafe {
             std::slice::from_raw_parts(
-                self.indices.as_ptr() as *const u8,
-                self.indices.len() * std::mem::size_of::<u32>(),
+                self.mesh.vertices.as_ptr() as *const u8,
+                self.mesh.vertices.len() * std::mem::size_of::<Vertex>(),
             )
         };
 
-        gl.buffer_data_with_u8_array(GL::ARRAY_BUFFER, vertex_slice, GL::DYNAMIC_DRAW);
-        gl.buffer_data_with_u8_array(GL::ELEMENT_ARRAY_BUFFER, index_slice, GL::DYNAMIC_DRAW);
+        if !self.uploaded {
+            self.mesh.setup(gl);
+            self.uploaded = true;
+            self.last_upload = Some(0..vertex_bytes.len());
+        } else if let Some(range) = self.dirty_range() {
+            self.mesh.bind(gl);
+            gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.mesh.vbo));
+            gl.buffer_sub_data_with_i32_and_u8_array(
+                GL::ARRAY_BUFFER,
+                range.start as i32,
+                &vertex_bytes[range.clone()],
+            );
+            self.last_upload = Some(range);
+        } else {
+            self.last_upload = None;
+        }
+        self.dirty = None;
+
+        self.mesh.draw(gl);
+    }
 
-        gl.vertex_attrib_pointer_with_i32(0, 2, GL::FLOAT, false, 8 * 4, 0);
-        gl.vertex_attrib_pointer_with_i32(1, 2, GL::FLOAT, false, 8 * 4, 8);
-        gl.vertex_attrib_pointer_with_i32(2, 4, GL::FLOAT, false, 8 * 4, 16);
+    fn mark_dirty(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.start.min(range.start)..dirty.end.max(range.end),
+            None => range,
+        });
+    }
 
-        gl.enable_vertex_attrib_array(0);
-        gl.enable_vertex_attrib_array(1);
-        gl.enable_vertex_attrib_array(2);
+    fn byte_range(range: Range<usize>) -> Range<usize> {
+        let size = std::mem::size_of::<Vertex>();
+        range.start * size..range.end * size
     }
 }
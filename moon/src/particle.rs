@@ -4,12 +4,84 @@ use std::f32::consts::PI;
 
 use crate::component::Component;
 use crate::math::*;
-use crate::renderer::Quad;
+use crate::renderer::{Quad, Renderer};
 use crate::transform::Transform2D;
 
-/// Maximum [`Particles`](Particle) in a [`ParticleSystem`].
+/// Default maximum [`Particles`](Particle) in a [`ParticleSystem`].
 const MAX_PARTICLES: usize = 100000;
 
+/// The area in which a [`ParticleSystem`] spawns its [`Particles`](Particle).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EmitterShape {
+    /// Spawn at the position of the [`ParticleSystem`].
+    #[default]
+    Point,
+    /// Spawn anywhere inside a circle with the given radius.
+    Circle {
+        /// Radius of the circle.
+        radius: f32,
+    },
+    /// Spawn anywhere inside a rectangle of the given size, centered on the [`ParticleSystem`].
+    Rect {
+        /// Size of the rectangle.
+        size: Vec2,
+    },
+    /// Spawn at the position of the [`ParticleSystem`], with the velocity turned by a random angle.
+    ///
+    /// The angle of the cone is in radians, centered on the base velocity.
+    Cone {
+        /// Full angle of the cone, in radians.
+        angle: f32,
+    },
+}
+
+impl EmitterShape {
+    /// Get a random spawn offset and a rotation for the velocity.
+    fn sample(&self, rng: &mut Rng) -> (Vec2, f32) {
+        match *self {
+            EmitterShape::Point => (Vec2::zeros(), 0.0),
+            EmitterShape::Circle { radius } => {
+                // The square root keeps the points evenly spread over the area
+                let distance = radius * rng.next_f32().sqrt();
+                let angle = rng.range(0.0, 2.0 * PI);
+                (Vec2::new(angle.cos(), angle.sin()) * distance, 0.0)
+            }
+            EmitterShape::Rect { size } => (rng.range_vec2(-size / 2.0, size / 2.0), 0.0),
+            EmitterShape::Cone { angle } => (Vec2::zeros(), rng.range(-angle / 2.0, angle / 2.0)),
+        }
+    }
+}
+
+/// An easing function, used to interpolate the color and size of a [`Particle`] over its lifetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ease {
+    /// Change at a constant rate.
+    #[default]
+    Linear,
+    /// Change quickly at first, and slow down towards the end.
+    EaseOut,
+}
+
+impl Ease {
+    /// Apply the [`Ease`] to a factor between `0.0` and `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::particle::Ease;
+    ///
+    /// assert_eq!(Ease::Linear.apply(0.5), 0.5);
+    /// assert_eq!(Ease::EaseOut.apply(0.5), 0.75);
+    /// ```
+    pub fn apply(&self, factor: f32) -> f32 {
+        let factor = factor.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => factor,
+            Ease::EaseOut => 1.0 - (1.0 - factor) * (1.0 - factor),
+        }
+    }
+}
+
 /// A [`ParticleProps`] defines how [`Particles`](Particle) are created.
 ///
 /// Reusing a [`ParticleProps`] allows for similar [`Particles`](Particle) to be emitted.
@@ -29,8 +101,20 @@ pub struct ParticleProps {
     pub color_modifier: Color32,
     /// How many [`Particles`](Particle) to emit on each update.
     pub burst_count: u32,
-    /// The size of the [`Particle`].
+    /// The start size of the [`Particle`].
     pub size: Vec2,
+    /// The end size of the [`Particle`].
+    pub size_end: Vec2,
+    /// A constant acceleration applied to the [`Particle`], such as gravity.
+    pub gravity: Vec2,
+    /// A modifier field for the lifetime of the [`Particle`].
+    pub lifetime_modifier: f32,
+    /// A modifier field for the size of the [`Particle`].
+    pub size_modifier: Vec2,
+    /// The [`EmitterShape`] the [`Particles`](Particle) spawn in.
+    pub shape: EmitterShape,
+    /// The [`Ease`] used to interpolate the color and size of the [`Particle`].
+    pub ease: Ease,
 }
 
 impl Default for ParticleProps {
@@ -44,6 +128,12 @@ impl Default for ParticleProps {
             color_modifier: Color32(0.2, 0.4, 0.1, 0.0),
             burst_count: 15,
             size: Vec2::new(0.05, 0.05),
+            size_end: Vec2::new(0.05, 0.05),
+            gravity: Vec2::new(0.0, 0.0),
+            lifetime_modifier: 0.0,
+            size_modifier: Vec2::new(0.0, 0.0),
+            shape: EmitterShape::Point,
+            ease: Ease::Linear,
         }
     }
 }
@@ -55,11 +145,17 @@ impl ParticleProps {
             lifetime: 10.0,
             velocity: Vec2::new(0.0, -0.2),
             velocity_modifier: Vec2::new(0.15, 0.1),
-            color_start: Color32(1.0, 1.0, 0.0, 1.0),
-            color_end: Color32(1.0, 0.0, 0.0, 1.0),
+            color_start: Color32::YELLOW,
+            color_end: Color32::RED,
             color_modifier: Color32(0.2, 0.2, 0.3, 0.0),
             burst_count: 5,
             size: Vec2::new(0.05, 0.05),
+            size_end: Vec2::new(0.05, 0.05),
+            gravity: Vec2::new(0.0, 0.0),
+            lifetime_modifier: 0.0,
+            size_modifier: Vec2::new(0.0, 0.0),
+            shape: EmitterShape::Point,
+            ease: Ease::Linear,
         }
     }
 
@@ -70,10 +166,16 @@ impl ParticleProps {
             velocity: Vec2::new(0.0, -0.4),
             velocity_modifier: Vec2::new(0.3, 0.2),
             color_start: Color32(0.7, 0.7, 0.7, 1.0),
-            color_end: Color32::BLACK,
+            color_end: Color32(0.3, 0.3, 0.3, 0.0),
             color_modifier: Color32(0.4, 0.4, 0.4, 0.0),
             burst_count: 20,
             size: Vec2::new(0.1, 0.15),
+            size_end: Vec2::new(0.04, 0.06),
+            gravity: Vec2::new(0.0, 0.0),
+            lifetime_modifier: 3.0,
+            size_modifier: Vec2::new(0.03, 0.03),
+            shape: EmitterShape::Circle { radius: 0.2 },
+            ease: Ease::EaseOut,
         }
     }
 }
@@ -82,11 +184,17 @@ impl ParticleProps {
 #[derive(Debug, Clone)]
 pub struct Particle {
     transform: Transform2D,
+    previous_transform: Transform2D,
     lifetime: f32,
     velocity: Vec2,
+    gravity: Vec2,
     color: Color32,
     color_start: Color32,
     color_end: Color32,
+    size_start: Vec2,
+    size_end: Vec2,
+    ease: Ease,
+    angular_velocity: f32,
     age: f32,
     alive: bool,
 }
@@ -95,11 +203,17 @@ impl Default for Particle {
     fn default() -> Self {
         Self {
             transform: Transform2D::new_with_scale(0.1, 0.1),
+            previous_transform: Transform2D::new_with_scale(0.1, 0.1),
             lifetime: 10.0,
             velocity: Vec2::new(0.0, 0.0),
+            gravity: Vec2::new(0.0, 0.0),
             color: Color32::ZEROES,
             color_start: Color32::WHITE,
             color_end: Color32::WHITE,
+            size_start: Vec2::new(0.1, 0.1),
+            size_end: Vec2::new(0.1, 0.1),
+            ease: Ease::Linear,
+            angular_velocity: 0.0,
             age: 0.0,
             alive: false,
         }
@@ -108,20 +222,25 @@ impl Default for Particle {
 
 impl Component for Particle {
     fn init(&mut self) {
-        self.transform.rotation = f32::random_range_max(PI);
         self.color = self.color_start;
+        self.transform.scale = self.size_start;
+        self.previous_transform = self.transform;
         self.alive = true;
         self.age = 0.0;
     }
 
     fn update(&mut self, delta_time: f32) {
+        self.previous_transform = self.transform;
         self.age += delta_time;
         if self.age > self.lifetime {
             self.alive = false;
         } else {
+            self.velocity += self.gravity * delta_time;
             self.transform.position += self.velocity * delta_time;
-            self.transform.rotation += f32::random_range(-1.0, 1.0) * delta_time;
-            self.color = Color32::lerp(self.color_start, self.color_end, self.age / self.lifetime);
+            self.transform.rotation += self.angular_velocity * delta_time;
+            let factor = self.ease.apply(self.age / self.lifetime);
+            self.color = self.color_start.lerp(&self.color_end, factor);
+            self.transform.scale = self.size_start.lerp(&self.size_end, factor);
         }
     }
 
@@ -136,20 +255,63 @@ impl Component for Particle {
 
 impl From<&ParticleProps> for Particle {
     fn from(properties: &ParticleProps) -> Self {
+        let mut rng = Rng::new(f32::random_range_max(u32::MAX as f32) as u64);
+        Self::new_from_props(properties, &mut rng)
+    }
+}
+
+impl Particle {
+    /// Get a [`Quad`] to draw the [`Particle`] with.
+    fn quad(&self) -> Quad {
+        self.quad_with_transform(&self.transform)
+    }
+
+    /// Get a [`Quad`] to draw the [`Particle`] with, between its previous and current [`Transform2D`].
+    fn interpolated_quad(&self, alpha: f32) -> Quad {
+        self.quad_with_transform(&self.previous_transform.lerp(&self.transform, alpha))
+    }
+
+    /// Get a [`Quad`] to draw the [`Particle`] with a given [`Transform2D`].
+    fn quad_with_transform(&self, transform: &Transform2D) -> Quad {
+        Quad::new_from_position_and_rotation_and_size_and_color(
+            transform.position.x,
+            transform.position.y,
+            transform.rotation,
+            transform.scale.x,
+            transform.scale.y,
+            self.color,
+        )
+    }
+
+    /// Create a new [`Particle`] from a [`ParticleProps`], using a [`Rng`] for the random modifiers and the [`EmitterShape`].
+    pub fn new_from_props(properties: &ParticleProps, rng: &mut Rng) -> Self {
+        let (offset, turn) = properties.shape.sample(rng);
+        let velocity = properties.velocity
+            + rng.range_vec2(-properties.velocity_modifier, properties.velocity_modifier);
+        let size_offset = rng.range_vec2(-properties.size_modifier, properties.size_modifier);
+        let size = properties.size + size_offset;
+        let mut transform = Transform2D::new_with_scale(size.x, size.y);
+        transform.position = offset;
+        transform.rotation = rng.range(0.0, PI);
+
         Self {
-            transform: Transform2D::new_with_scale(properties.size.x, properties.size.y),
-            lifetime: properties.lifetime,
-            velocity: {
-                properties.velocity
-                    + Vec2::random_range(
-                        -properties.velocity_modifier,
-                        properties.velocity_modifier,
-                    )
-            },
+            transform,
+            previous_transform: transform,
+            lifetime: properties.lifetime
+                + rng.range(-properties.lifetime_modifier, properties.lifetime_modifier),
+            velocity: Vec2::new(
+                velocity.x * turn.cos() - velocity.y * turn.sin(),
+                velocity.x * turn.sin() + velocity.y * turn.cos(),
+            ),
+            gravity: properties.gravity,
             color_start: properties.color_start
-                + Color32::random_range(properties.color_modifier, properties.color_modifier),
+                + rng.range_color(properties.color_modifier, properties.color_modifier),
             color_end: properties.color_end
-                + Color32::random_range(properties.color_modifier, properties.color_modifier),
+                + rng.range_color(properties.color_modifier, properties.color_modifier),
+            size_start: size,
+            size_end: properties.size_end + size_offset,
+            ease: properties.ease,
+            angular_velocity: rng.range(-1.0, 1.0),
             ..Default::default()
         }
     }
@@ -163,6 +325,8 @@ pub struct ParticleSystem {
     emission: ParticleProps,
     particles: Vec<Particle>,
     index: usize,
+    capacity: usize,
+    rng: Rng,
     /// A [`ParticleSystem`] needs to be alive to emit and update [`Particles`](Particle).
     pub alive: bool,
 }
@@ -173,6 +337,8 @@ impl Default for ParticleSystem {
             emission: ParticleProps::default(),
             particles: Vec::with_capacity(MAX_PARTICLES),
             index: 0,
+            capacity: MAX_PARTICLES,
+            rng: Rng::new_random(),
             transform: Transform2D::default(),
             alive: false,
         }
@@ -204,16 +370,18 @@ impl Component for ParticleSystem {
             self.particles
                 .iter()
                 .filter(|particle| particle.alive)
-                .map(|particle| {
-                    Quad::new_from_position_and_rotation_and_size_and_color(
-                        particle.transform.position.x,
-                        particle.transform.position.y,
-                        particle.transform.rotation,
-                        particle.transform.scale.x,
-                        particle.transform.scale.y,
-                        particle.color,
-                    )
-                })
+                .map(Particle::quad)
+                .collect(),
+        )
+    }
+
+    /// Get a [`Vec`] of [`Quad`] from all the [`Particles`](Particle), between their previous and current [`Transforms`](Transform2D).
+    fn get_interpolated_quads(&self, alpha: f32) -> Option<Vec<Quad>> {
+        Some(
+            self.particles
+                .iter()
+                .filter(|particle| particle.alive)
+                .map(|particle| particle.interpolated_quad(alpha))
                 .collect(),
         )
     }
@@ -245,18 +413,48 @@ impl ParticleSystem {
         }
     }
 
+    /// Create a new [`ParticleSystem`] using a [`ParticleProps`] for the emission, that holds at most `capacity` [`Particles`](Particle).
+    ///
+    /// The pool of [`Particles`](Particle) is allocated up front, so emitting never allocates.
+    pub fn new_from_emission_with_capacity(emission: ParticleProps, capacity: usize) -> Self {
+        // Not using `Default`, which would allocate a pool of the default capacity
+        Self {
+            emission,
+            particles: Vec::with_capacity(capacity),
+            index: 0,
+            capacity: capacity.max(1),
+            rng: Rng::new_random(),
+            transform: Transform2D::default(),
+            alive: false,
+        }
+    }
+
+    /// Get the maximum number of [`Particles`](Particle) in the [`ParticleSystem`].
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Seed the [`Rng`] of the [`ParticleSystem`], so that the same [`Particles`](Particle) are emitted each time.
+    ///
+    /// Without a seed, each [`ParticleSystem`] starts from a random one.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
     /// Toggle the `alive` field of the [`ParticleSystem`].
     pub fn toggle_alive(&mut self) {
         self.alive = !self.alive;
     }
 
     /// Emit a single [`Particle`], according to the defined [`ParticleProps`] for emission.
+    ///
+    /// Once the pool is full, slots are reused in order, replacing the oldest [`Particle`].
     pub fn emit(&mut self) {
-        if self.index >= MAX_PARTICLES {
+        if self.index >= self.capacity {
             self.index = 0;
         }
 
-        let mut new_particle = Particle::from(&self.emission);
+        let mut new_particle = Particle::new_from_props(&self.emission, &mut self.rng);
         new_particle.transform = self.transform + new_particle.transform;
 
         let particle = self.particles.get_mut(self.index);
@@ -265,8 +463,13 @@ impl ParticleSystem {
             particle.transform = new_particle.transform;
             particle.lifetime = new_particle.lifetime;
             particle.velocity = new_particle.velocity;
+            particle.gravity = new_particle.gravity;
+            particle.angular_velocity = new_particle.angular_velocity;
             particle.color_start = new_particle.color_start;
             particle.color_end = new_particle.color_end;
+            particle.size_start = new_particle.size_start;
+            particle.size_end = new_particle.size_end;
+            particle.ease = new_particle.ease;
 
             particle.init();
         } else {
@@ -282,4 +485,21 @@ impl ParticleSystem {
             self.emit()
         }
     }
+
+    /// Get the number of [`Particles`](Particle) that are still alive.
+    pub fn alive_count(&self) -> usize {
+        self.particles
+            .iter()
+            .filter(|particle| particle.alive)
+            .count()
+    }
+
+    /// Add the [`Quads`](Quad) of the alive [`Particles`](Particle) to the [`Renderer`]'s batch.
+    ///
+    /// [`begin_draw`](Renderer::begin_draw) should have been called before.
+    pub fn render(&self, renderer: &mut Renderer) {
+        for particle in self.particles.iter().filter(|particle| particle.alive) {
+            renderer.add_quad(&particle.quad());
+        }
+    }
 }
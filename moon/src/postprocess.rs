@@ -0,0 +1,117 @@
+//! The [`Tonemap`] and [`Blit`] post-processing passes.
+
+use crate::framebuffer::Framebuffer;
+use crate::gl::Bind;
+use crate::shader::Shader;
+use crate::GL;
+
+/// Name of the sampler uniform used for the scene in post-processing passes.
+pub const SCENE_SAMPLER: &str = "uScene";
+
+/// Default exposure used by the [`Tonemap`] pass.
+pub const DEFAULT_EXPOSURE: f32 = 1.0;
+
+/// Draw a single triangle that covers the whole screen.
+///
+/// The vertices are generated in the vertex shader, so no buffers are needed.
+pub fn draw_fullscreen(gl: &GL) {
+    gl.bind_vertex_array(None);
+    gl.draw_arrays(GL::TRIANGLES, 0, 3);
+}
+
+/// A [`Tonemap`] pass maps a high dynamic range scene to the displayable range.
+///
+/// The scene is rendered into a [`Framebuffer`], which is then drawn to the screen using an ACES filmic approximation, scaled by the exposure.
+#[derive(Debug)]
+pub struct Tonemap {
+    /// The exposure the scene is scaled by before tonemapping.
+    pub exposure: f32,
+    /// The [`Framebuffer`] the scene is rendered into.
+    pub framebuffer: Framebuffer,
+    shader: Shader,
+}
+
+impl Tonemap {
+    /// Create a new [`Tonemap`] pass with a given size and exposure.
+    pub fn new(gl: &GL, width: u32, height: u32, exposure: f32) -> Self {
+        let shader = Shader::new_with_sources(
+            gl,
+            include_str!("../res/shader/post.vert.glsl"),
+            include_str!("../res/shader/tonemap.frag.glsl"),
+            "Tonemap Shader",
+        )
+        .expect("Could not create Tonemap Shader!");
+
+        Self {
+            exposure,
+            framebuffer: Framebuffer::new_hdr(gl, width, height),
+            shader,
+        }
+    }
+
+    /// Resize the [`Framebuffer`] of the [`Tonemap`] pass.
+    pub fn resize(&mut self, gl: &GL, width: u32, height: u32) {
+        self.framebuffer = Framebuffer::new_hdr(gl, width, height);
+    }
+
+    /// Get the [`Shader`] used by the [`Tonemap`] pass.
+    pub fn shader(&self) -> &Shader {
+        &self.shader
+    }
+
+    /// Draw the contents of the [`Framebuffer`] to the screen, sampling it from the given texture unit.
+    pub fn run(&self, gl: &GL, unit: u32) {
+        self.framebuffer.unbind(gl);
+        self.shader.bind(gl);
+        self.framebuffer.texture.bind_to_unit(gl, unit);
+
+        self.shader.set_uniform_i32(gl, SCENE_SAMPLER, unit as i32);
+        self.shader.set_uniform_f32(gl, "uExposure", self.exposure);
+
+        draw_fullscreen(gl);
+    }
+}
+
+/// A [`Blit`] pass copies an off-screen render target onto the screen.
+///
+/// The [`Framebuffer`] is sampled with nearest filtering, so a low resolution render target is upscaled with crisp pixels.
+#[derive(Debug)]
+pub struct Blit {
+    /// The [`Framebuffer`] the scene is rendered into.
+    pub framebuffer: Framebuffer,
+    shader: Shader,
+}
+
+impl Blit {
+    /// Create a new [`Blit`] pass with a given size.
+    pub fn new(gl: &GL, width: u32, height: u32) -> Self {
+        let shader = Shader::new_with_sources(
+            gl,
+            include_str!("../res/shader/post.vert.glsl"),
+            include_str!("../res/shader/blit.frag.glsl"),
+            "Blit Shader",
+        )
+        .expect("Could not create Blit Shader!");
+
+        Self {
+            framebuffer: Framebuffer::new(gl, width, height),
+            shader,
+        }
+    }
+
+    /// Resize the [`Framebuffer`] of the [`Blit`] pass.
+    pub fn resize(&mut self, gl: &GL, width: u32, height: u32) {
+        self.framebuffer = Framebuffer::new(gl, width, height);
+    }
+
+    /// Draw the contents of the [`Framebuffer`] to the screen, sampling it from the given texture unit.
+    pub fn run(&self, gl: &GL, unit: u32) {
+        self.framebuffer.unbind(gl);
+        self.shader.bind(gl);
+        self.framebuffer.texture.bind_to_unit(gl, unit);
+
+        self.shader.set_uniform_i32(gl, SCENE_SAMPLER, unit as i32);
+
+        draw_fullscreen(gl);
+    }
+}
@@ -3,23 +3,36 @@
 use std::collections::BTreeMap;
 use std::fmt;
 use std::rc::Rc;
-use web_sys::WebGlUniformLocation;
 
-use crate::component::Component;
+use crate::collider::AABB;
+use crate::debug::Line;
+use crate::font::Font;
+use crate::framebuffer::Framebuffer;
+use crate::light::{Light, LightPass, DEFAULT_AMBIENT_LIGHT};
+use crate::material::Material;
+use crate::postprocess::{Blit, Tonemap, SCENE_SAMPLER};
+use crate::scene::Scene;
+use crate::shader::{define, LOG_DEPTH_DEFINE};
 use crate::{gl, mesh, texture, Color32};
-use crate::{Camera, Shader, Transform, GL};
+use crate::{Camera, Canvas, Shader, Transform, Vec2, Vec4, GL};
 
 use gl::Bind;
-use mesh::{Mesh, Vertex};
-use texture::{SubTexture, Texture};
+use mesh::{BufferLayout, Mesh, Vertex};
+use texture::{SubTexture, Texture, TextureUnits};
+use wasm_bindgen::JsCast;
 
 /// Maximum [`Quad`]s in a single batch.
 pub const MAX_BATCH_QUADS: i32 = 1000;
 const MAX_BATCH_VERTICES: i32 = MAX_BATCH_QUADS * 4;
 const MAX_BATCH_INDICES: i32 = MAX_BATCH_QUADS * 6;
 
+/// Name of the sampler uniform used for sprites.
+pub const SPRITE_SAMPLER: &str = "uTex0";
+/// Name of the sampler uniform used for the palette [`Texture`] when palette swapping is enabled.
+pub const PALETTE_SAMPLER: &str = "uPalette";
+
 /// A [`Quad`] is a simple mesh definition with four [`Vertices`](Vertex).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Quad([Vertex; 4]);
 
 impl Default for Quad {
@@ -173,23 +186,60 @@ impl Quad {
         ])
     }
 
+    /// Create a new [`Quad`] from a given position, rotation in radians, size, and a reference to a [`SubTexture`].
+    ///
+    /// The [`Quad`] is rotated about its center.
+    pub fn new_with_rotation(
+        pos_x: f32,
+        pos_y: f32,
+        rotation: f32,
+        size_x: f32,
+        size_y: f32,
+        sprite: &SubTexture,
+    ) -> Self {
+        let mut quad =
+            Self::new_from_position_and_size_and_sprite(0.0, 0.0, size_x, size_y, sprite);
+        let (sin_theta, cos_theta) = rotation.sin_cos();
+        for vertex in quad.0.iter_mut() {
+            let [x, y] = vertex.position;
+            vertex.position = [
+                pos_x + x * cos_theta - y * sin_theta,
+                pos_y + x * sin_theta + y * cos_theta,
+            ];
+        }
+        quad
+    }
+
+    /// Tint the [`Quad`] by multiplying the color of its vertices with a given [`Color32`].
+    ///
+    /// The default [`Shader`] multiplies the sprite by the vertex color, so a WHITE tint leaves it unchanged.
+    pub fn with_tint(mut self, tint: Color32) -> Self {
+        let tint = <[f32; 4]>::from(tint);
+        for vertex in self.0.iter_mut() {
+            for (channel, factor) in vertex.color.iter_mut().zip(tint) {
+                *channel *= factor;
+            }
+        }
+        self
+    }
+
     /// Create a new [`Quad`] using a given [`Transform`] for its position and scale.
     pub fn new_from_transform(transform: Transform) -> Self {
         Self::new_from_position_and_size(
-            transform.position.x,
-            transform.position.x,
-            transform.scale.x,
-            transform.scale.y,
+            transform.position().x,
+            transform.position().x,
+            transform.scale().x,
+            transform.scale().y,
         )
     }
 
     /// Create a new [`Quad`] using a given [`Transform`] for its position and scale, and a reference to [`SubTexture`].
     pub fn new_from_transform_and_sprite(transform: Transform, sprite: &SubTexture) -> Self {
         Self::new_from_position_and_size_and_sprite(
-            transform.position.x,
-            transform.position.x,
-            transform.scale.x,
-            transform.scale.y,
+            transform.position().x,
+            transform.position().x,
+            transform.scale().x,
+            transform.scale().y,
             sprite,
         )
     }
@@ -200,6 +250,141 @@ impl Quad {
     }
 }
 
+/// The per-instance data of a sprite drawn with [`Renderer::draw_instanced`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct InstanceData {
+    /// Position of the center of the instance.
+    pub position: [f32; 2],
+    /// Size of the instance.
+    pub size: [f32; 2],
+    /// Rotation of the instance about its center, in radians.
+    pub rotation: f32,
+    /// The region of the sprite to draw, relative to it, in the same order as [`SubTexture::region`].
+    pub uv: [f32; 4],
+    /// The color the sprite is multiplied by.
+    pub color: [f32; 4],
+}
+
+impl Default for InstanceData {
+    fn default() -> Self {
+        Self {
+            position: [0.0, 0.0],
+            size: [1.0, 1.0],
+            rotation: 0.0,
+            uv: [0.0, 1.0, 0.0, 1.0],
+            color: [1.0, 1.0, 1.0, 1.0],
+        }
+    }
+}
+
+impl InstanceData {
+    /// Create a new [`InstanceData`] with a given position and size, showing the whole sprite.
+    pub fn new(pos_x: f32, pos_y: f32, size_x: f32, size_y: f32) -> Self {
+        Self {
+            position: [pos_x, pos_y],
+            size: [size_x, size_y],
+            ..Default::default()
+        }
+    }
+
+    /// Get the [`BufferLayout`] of an [`InstanceData`], advancing once per instance.
+    ///
+    /// Position, size, rotation, UV region and color are bound to attribute locations 3 to 7.
+    pub fn layout() -> BufferLayout {
+        BufferLayout::new(std::mem::size_of::<InstanceData>() as i32, 1)
+            .with_attribute(3, 2, 0)
+            .with_attribute(4, 2, 8)
+            .with_attribute(5, 1, 16)
+            .with_attribute(6, 4, 20)
+            .with_attribute(7, 4, 36)
+    }
+
+    /// Get a [`Quad`] that looks the same as the instance of a sprite, for drawing without instancing.
+    pub fn quad(&self, sprite: &SubTexture) -> Quad {
+        let [u_min, u_max, v_min, v_max] = self.uv;
+        let [red, green, blue, alpha] = self.color;
+        Quad::new_with_rotation(
+            self.position[0],
+            self.position[1],
+            self.rotation,
+            self.size[0],
+            self.size[1],
+            &sprite.region(Color32(u_min, u_max, v_min, v_max)),
+        )
+        .with_tint(Color32(red, green, blue, alpha))
+    }
+}
+
+/// How the colors drawn by the [`Renderer`] are combined with those already on screen.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Blending is disabled, and colors are replaced.
+    None,
+    /// Colors are blended using their alpha, for transparent sprites.
+    #[default]
+    Alpha,
+    /// Colors are added using their alpha, for glowing effects like fire and particles.
+    Additive,
+    /// Colors are multiplied, for shadows and tinting.
+    Multiply,
+}
+
+impl BlendMode {
+    /// Set the blend state of the WebGL context to match the [`BlendMode`].
+    pub fn apply(&self, gl: &GL) {
+        let (source, destination) = match self {
+            BlendMode::None => {
+                gl.disable(GL::BLEND);
+                return;
+            }
+            BlendMode::Alpha => (GL::SRC_ALPHA, GL::ONE_MINUS_SRC_ALPHA),
+            BlendMode::Additive => (GL::SRC_ALPHA, GL::ONE),
+            BlendMode::Multiply => (GL::DST_COLOR, GL::ZERO),
+        };
+        gl.enable(GL::BLEND);
+        gl.blend_func(source, destination);
+    }
+}
+
+/// Which faces of triangles are culled, and not drawn.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CullMode {
+    /// Cull the faces pointing away from the [`Camera`].
+    #[default]
+    Back,
+    /// Cull the faces pointing towards the [`Camera`].
+    Front,
+    /// Cull all faces.
+    FrontAndBack,
+}
+
+impl CullMode {
+    /// Get the WebGL enum of the [`CullMode`], as passed to `cullFace`.
+    pub fn as_gl(&self) -> u32 {
+        match self {
+            CullMode::Back => GL::BACK,
+            CullMode::Front => GL::FRONT,
+            CullMode::FrontAndBack => GL::FRONT_AND_BACK,
+        }
+    }
+}
+
+/// Counters of the work done by the [`Renderer`] in a frame.
+///
+/// The counters are reset by [`begin_frame`](Renderer::begin_frame).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RenderStats {
+    /// Number of draw calls issued for batches of [`Quads`](Quad). Post-processing passes are counted separately.
+    pub draw_calls: u32,
+    /// Number of [`Quads`](Quad) submitted.
+    pub quad_count: u32,
+    /// Number of batches of [`Quads`](Quad) created.
+    pub batch_count: u32,
+    /// Number of post-processing passes run.
+    pub post_passes: u32,
+}
+
 /// The [`Renderer`] is responsible for drawing on the screen. It handles the [`Camera`] and [`Shader`]s.
 pub struct Renderer {
     /// The [`WebGl2RenderingContext`](web_sys::WebGl2RenderingContext) used by the [`Renderer`].
@@ -209,14 +394,34 @@ pub struct Renderer {
     /// The [`Camera`] used by the [`Renderer`].
     pub camera: Camera,
     batches: Vec<Mesh>,
-    /// [`Components`](Component) that can be added to the [`Renderer`].
-    pub components: BTreeMap<&'static str, Box<dyn Component>>,
+    batch_textures: Vec<Option<Rc<Texture>>>,
+    selected_texture: Option<Rc<Texture>>,
+    lines: Vec<Line>,
+    line_width: Option<f32>,
+    font: Option<Font>,
     textures: BTreeMap<&'static str, Rc<Texture>>,
-    u_time: Option<WebGlUniformLocation>,
-    u_color: Option<WebGlUniformLocation>,
-    u_model_matrix: Option<WebGlUniformLocation>,
-    u_view_matrix: Option<WebGlUniformLocation>,
-    u_projection_matrix: Option<WebGlUniformLocation>,
+    palette: Option<Rc<Texture>>,
+    /// The [`TextureUnits`] allocated to the sampler uniforms of the [`Renderer`].
+    pub texture_units: TextureUnits,
+    log_depth: bool,
+    custom_program: bool,
+    tonemap: Option<Tonemap>,
+    render_resolution: Option<(u32, u32)>,
+    blit: Option<Blit>,
+    lights: Vec<Light>,
+    ambient_light: Color32,
+    light_pass: Option<LightPass>,
+    blend_mode: BlendMode,
+    depth_test: bool,
+    cull_face: Option<CullMode>,
+    stats: RenderStats,
+    outline_program: Option<Shader>,
+    immediate_mesh: Option<Mesh>,
+    instance_program: Option<Shader>,
+    instance_mesh: Option<Mesh>,
+    instance_buffer_size: usize,
+    instancing_failed: bool,
+    material: Option<Rc<Material>>,
 }
 
 impl Default for Renderer {
@@ -226,25 +431,52 @@ impl Default for Renderer {
         Self {
             camera: Camera::default(),
             batches: Vec::new(),
-            components: BTreeMap::new(),
-            u_time: program.get_uniform_location(&gl, "uTime"),
-            u_color: program.get_uniform_location(&gl, "uColor"),
-            u_model_matrix: program.get_uniform_location(&gl, "uModel"),
-            u_view_matrix: program.get_uniform_location(&gl, "uView"),
-            u_projection_matrix: program.get_uniform_location(&gl, "uProj"),
+            batch_textures: Vec::new(),
+            selected_texture: None,
+            lines: Vec::new(),
+            line_width: None,
+            font: None,
             program,
-            textures: {
-                let mut textues = BTreeMap::<&str, Rc<Texture>>::new();
-                textues.insert("WHITE", Rc::new(Texture::white(&gl)));
-                textues.insert("MAGENTA", Rc::new(Texture::colored(&gl, Color32::MAGENTA)));
-                textues.insert("CHECKERBOARD", Rc::new(Texture::checkerboard(&gl)));
-                textues
+            textures: default_textures(&gl),
+            palette: None,
+            texture_units: {
+                let mut texture_units = TextureUnits::new();
+                texture_units.allocate(SPRITE_SAMPLER);
+                texture_units
             },
+            log_depth: false,
+            custom_program: false,
+            tonemap: None,
+            render_resolution: None,
+            blit: None,
+            lights: Vec::new(),
+            ambient_light: DEFAULT_AMBIENT_LIGHT,
+            light_pass: None,
+            blend_mode: BlendMode::default(),
+            depth_test: false,
+            cull_face: None,
+            stats: RenderStats::default(),
+            outline_program: None,
+            immediate_mesh: None,
+            instance_program: None,
+            instance_mesh: None,
+            instance_buffer_size: 0,
+            instancing_failed: false,
+            material: None,
             gl,
         }
     }
 }
 
+/// Create the built-in [`Textures`](Texture) of a [`Renderer`].
+fn default_textures(gl: &GL) -> BTreeMap<&'static str, Rc<Texture>> {
+    let mut textures = BTreeMap::<&str, Rc<Texture>>::new();
+    textures.insert("WHITE", Rc::new(Texture::white(gl)));
+    textures.insert("MAGENTA", Rc::new(Texture::colored(gl, Color32::MAGENTA)));
+    textures.insert("CHECKERBOARD", Rc::new(Texture::checkerboard(gl)));
+    textures
+}
+
 impl fmt::Debug for Renderer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Renderer")
@@ -252,12 +484,33 @@ impl fmt::Debug for Renderer {
             .field("program", &self.program)
             .field("camera", &self.camera)
             .field("batches", &self.batches)
+            .field("batch_textures", &self.batch_textures)
+            .field("selected_texture", &self.selected_texture)
+            .field("lines", &self.lines)
+            .field("line_width", &self.line_width)
+            .field("font", &self.font)
             .field("textures", &self.textures)
-            .field("u_time", &self.u_time)
-            .field("u_color", &self.u_color)
-            .field("u_model_matrix", &self.u_model_matrix)
-            .field("u_view_matrix", &self.u_view_matrix)
-            .field("u_projection_matrix", &self.u_projection_matrix)
+            .field("palette", &self.palette)
+            .field("texture_units", &self.texture_units)
+            .field("log_depth", &self.log_depth)
+            .field("custom_program", &self.custom_program)
+            .field("tonemap", &self.tonemap)
+            .field("render_resolution", &self.render_resolution)
+            .field("blit", &self.blit)
+            .field("lights", &self.lights)
+            .field("ambient_light", &self.ambient_light)
+            .field("light_pass", &self.light_pass)
+            .field("blend_mode", &self.blend_mode)
+            .field("depth_test", &self.depth_test)
+            .field("cull_face", &self.cull_face)
+            .field("stats", &self.stats)
+            .field("outline_program", &self.outline_program)
+            .field("immediate_mesh", &self.immediate_mesh)
+            .field("instance_program", &self.instance_program)
+            .field("instance_mesh", &self.instance_mesh)
+            .field("instance_buffer_size", &self.instance_buffer_size)
+            .field("instancing_failed", &self.instancing_failed)
+            .field("material", &self.material)
             .finish()
     }
 }
@@ -278,12 +531,8 @@ impl Renderer {
 
         Self {
             camera,
-            u_time: program.get_uniform_location(&gl, "uTime"),
-            u_color: program.get_uniform_location(&gl, "uColor"),
-            u_model_matrix: program.get_uniform_location(&gl, "uModel"),
-            u_view_matrix: program.get_uniform_location(&gl, "uView"),
-            u_projection_matrix: program.get_uniform_location(&gl, "uProj"),
             program,
+            custom_program: true,
             gl,
             ..Default::default()
         }
@@ -295,26 +544,85 @@ impl Renderer {
     }
 
     /// Set the [`Shader`] that the [`Renderer`] will use.
+    ///
+    /// A custom [`Shader`] is kept when the logarithmic depth buffer is toggled, see [`set_log_depth`](Renderer::set_log_depth).
     pub fn set_shader(&mut self, program: Shader) {
-        let gl = &self.gl;
-
-        self.u_time = program.get_uniform_location(gl, "uTime");
-        self.u_color = program.get_uniform_location(gl, "uColor");
-        self.u_model_matrix = program.get_uniform_location(gl, "uModel");
-        self.u_view_matrix = program.get_uniform_location(gl, "uView");
-        self.u_projection_matrix = program.get_uniform_location(gl, "uProj");
         self.program = program;
+        self.custom_program = true;
+    }
+
+    /// Set the [`Shader`] to the built-in one matching the palette and logarithmic depth settings.
+    fn set_builtin_shader(&mut self) {
+        let defines = self.shader_defines();
+        self.program = if self.palette.is_some() {
+            Shader::new_palette_with_defines(&self.gl, defines)
+        } else {
+            Shader::new_with_defines(&self.gl, defines)
+        };
+        self.custom_program = false;
+    }
+
+    /// Get the `#define` names that the [`Shaders`](Shader) of the [`Renderer`] are compiled with.
+    fn shader_defines(&self) -> &'static [&'static str] {
+        if self.log_depth {
+            &[LOG_DEPTH_DEFINE]
+        } else {
+            &[]
+        }
+    }
+
+    /// Compile a new [`Shader`] from the given sources, and swap it in.
+    ///
+    /// The sources are compiled with [`LOG_DEPTH_DEFINE`] defined if the logarithmic depth buffer is enabled.
+    /// Returns the compile or link log on failure, in which case the current [`Shader`] stays active.
+    pub fn reload_shader(
+        &mut self,
+        vertex_source: &str,
+        fragment_source: &str,
+    ) -> Result<(), String> {
+        let defines = self.shader_defines();
+        let program = Shader::new_with_sources(
+            &self.gl,
+            &define(vertex_source, defines),
+            &define(fragment_source, defines),
+            "Reloaded Shader",
+        )?;
+        self.set_shader(program);
+        self.init_shader();
+        Ok(())
     }
 
     /// Handle screen resizes.
+    ///
+    /// Updates the aspect ratio of the [`Camera`] and the viewport, and uploads the new projection matrix.
     pub fn resize(&mut self, width: f32, height: f32) {
+        self.resize_with_pixel_ratio(width, height, 1.0);
+    }
+
+    /// Handle screen resizes on a display with a given device pixel ratio.
+    ///
+    /// The width and height are in CSS pixels, which the [`Camera`] uses for screen coordinates.
+    /// The backing size of the canvas and the viewport are scaled by the pixel ratio, so the render stays crisp on HiDPI screens.
+    pub fn resize_with_pixel_ratio(&mut self, width: f32, height: f32, pixel_ratio: f32) {
+        let pixel_ratio = if pixel_ratio > 0.0 { pixel_ratio } else { 1.0 };
+        let buffer_width = (width * pixel_ratio).round().max(1.0) as u32;
+        let buffer_height = (height * pixel_ratio).round().max(1.0) as u32;
+        if let Some(canvas) = self
+            .gl
+            .canvas()
+            .and_then(|canvas| canvas.dyn_into::<Canvas>().ok())
+        {
+            canvas.set_width(buffer_width);
+            canvas.set_height(buffer_height);
+        }
+
         self.camera.set_width_and_height(width, height);
-        self.gl.viewport(0, 0, width as i32, height as i32);
-        self.gl.uniform_matrix4fv_with_f32_array(
-            self.u_projection_matrix.as_ref(),
-            false,
-            self.camera.projection(),
-        );
+        if height > 0.0 {
+            self.camera.set_aspect_ratio(width / height);
+        }
+        self.gl
+            .viewport(0, 0, buffer_width as i32, buffer_height as i32);
+        self.set_projection_uniforms();
     }
 
     /// Initialise the uniforms for the current [`Shader`].
@@ -322,23 +630,143 @@ impl Renderer {
         let gl = &self.gl;
         self.program.bind(gl);
 
-        gl.uniform1f(self.u_time.as_ref(), 0.0);
-        gl.uniform4f(self.u_color.as_ref(), 1.0, 1.0, 1.0, 1.0);
-        gl.uniform_matrix4fv_with_f32_array(
-            self.u_view_matrix.as_ref(),
-            false,
-            crate::Mat4::identity().as_slice(),
-        );
-        gl.uniform_matrix4fv_with_f32_array(
-            self.u_view_matrix.as_ref(),
-            false,
-            self.camera.transform.matrix_slice(),
-        );
-        gl.uniform_matrix4fv_with_f32_array(
-            self.u_projection_matrix.as_ref(),
-            false,
-            self.camera.projection(),
-        );
+        let program = &self.program;
+        program.set_uniform_f32(gl, "uTime", 0.0);
+        program.set_uniform_vec4(gl, "uColor", &Vec4::new(1.0, 1.0, 1.0, 1.0));
+        program.set_uniform_mat4(gl, "uView", &self.camera.view_matrix());
+        program.set_uniform_mat4(gl, "uProj", self.camera.projection_kind().as_matrix());
+        program.set_uniform_f32(gl, "uLogDepthFC", self.log_depth_coefficient());
+        for (sampler, unit) in self.texture_units.iter() {
+            program.set_uniform_i32(gl, sampler, unit as i32);
+        }
+    }
+
+    /// Get the [`Shader`] used to draw, which is that of the bound [`Material`] if any.
+    fn active_shader(&self) -> &Shader {
+        self.material
+            .as_ref()
+            .map_or(&self.program, |material| material.shader())
+    }
+
+    /// Set the `uProj` and `uLogDepthFC` uniforms of the [`Shader`] and that of the bound [`Material`], if any.
+    ///
+    /// The active [`Shader`] is left bound.
+    fn set_projection_uniforms(&self) {
+        let gl = &self.gl;
+        let material_shader = self
+            .material
+            .as_ref()
+            .map(|material| material.shader().as_ref());
+        for program in std::iter::once(&self.program).chain(material_shader) {
+            program.bind(gl);
+            program.set_uniform_mat4(gl, "uProj", self.camera.projection_kind().as_matrix());
+            program.set_uniform_f32(gl, "uLogDepthFC", self.log_depth_coefficient());
+        }
+        self.active_shader().bind(gl);
+    }
+
+    /// Draw and clear any batched [`Quads`](Quad), using the active [`Shader`], and start a new batch.
+    ///
+    /// Does nothing if no batch has been started with [`begin_draw`](Renderer::begin_draw).
+    fn flush_batches(&mut self) {
+        if self.batches.is_empty() {
+            return;
+        }
+        let gl = &self.gl;
+        let program = self.active_shader();
+        program.bind(gl);
+        program.set_uniform_mat4(gl, "uView", &self.camera.view_matrix());
+        for index in 0..self.batches.len() {
+            self.draw_batch(index);
+        }
+        self.batches.clear();
+        self.batch_textures.clear();
+        self.push_batch(None);
+    }
+
+    /// Bind a [`Material`], so the following draws use its [`Shader`] and uniform values.
+    ///
+    /// Any batched [`Quads`](Quad) are drawn first with the previous [`Shader`]. The camera uniforms and samplers are set before the values of the [`Material`], which can override them.
+    pub fn bind_material(&mut self, material: Rc<Material>) {
+        self.flush_batches();
+
+        let gl = &self.gl;
+        let program = material.shader();
+        program.bind(gl);
+        program.set_uniform_mat4(gl, "uView", &self.camera.view_matrix());
+        program.set_uniform_mat4(gl, "uProj", self.camera.projection_kind().as_matrix());
+        program.set_uniform_f32(gl, "uLogDepthFC", self.log_depth_coefficient());
+        for (sampler, unit) in self.texture_units.iter() {
+            program.set_uniform_i32(gl, sampler, unit as i32);
+        }
+        material.apply(gl);
+        self.material = Some(material);
+    }
+
+    /// Unbind the current [`Material`], returning to the default [`Shader`] of the [`Renderer`].
+    ///
+    /// Any batched [`Quads`](Quad) are drawn first with the [`Material`].
+    pub fn clear_material(&mut self) {
+        self.flush_batches();
+        self.material = None;
+        self.program.bind(&self.gl);
+    }
+
+    /// Get the bound [`Material`], if any.
+    pub fn material(&self) -> Option<&Rc<Material>> {
+        self.material.as_ref()
+    }
+
+    /// Set the `uTime` uniform of the current [`Shader`], in seconds.
+    pub fn set_time(&self, time: f32) {
+        let program = self.active_shader();
+        program.bind(&self.gl);
+        program.set_uniform_f32(&self.gl, "uTime", time);
+    }
+
+    /// Enable or disable the logarithmic depth buffer.
+    ///
+    /// This reduces Z-fighting on distant geometry when the near and far planes of the [`Camera`] are far apart.
+    /// The built-in [`Shaders`](Shader) are recompiled with [`LOG_DEPTH_DEFINE`], as writing the depth from the fragment shader disables early depth testing.
+    /// A custom [`Shader`] is kept, and only its `uLogDepthFC` uniform is updated, along with that of the bound [`Material`].
+    pub fn set_log_depth(&mut self, enabled: bool) {
+        if enabled != self.log_depth {
+            self.log_depth = enabled;
+            self.instance_program = None;
+            self.instancing_failed = false;
+            if !self.custom_program {
+                self.set_builtin_shader();
+                self.init_shader();
+            }
+        }
+        self.set_projection_uniforms();
+    }
+
+    /// Get the coefficient used for the logarithmic depth buffer, or 0 if it is disabled.
+    pub fn log_depth_coefficient(&self) -> f32 {
+        if self.log_depth {
+            2.0 / (self.camera.far() + 1.0).log2()
+        } else {
+            0.0
+        }
+    }
+
+    /// Bind a [`Texture`] to a sampler uniform of the current [`Shader`].
+    ///
+    /// A texture unit is allocated for the sampler if it does not already have one, and the sampler uniform is updated to match.
+    /// Returns the texture unit, or [`None`] if no units are free.
+    pub fn bind_texture_to_sampler(
+        &mut self,
+        texture: &Texture,
+        sampler: &'static str,
+    ) -> Option<u32> {
+        let gl = &self.gl;
+        let unit = self.texture_units.allocate(sampler)?;
+        texture.bind_to_unit(gl, unit);
+
+        self.program.bind(gl);
+        self.program.set_uniform_i32(gl, sampler, unit as i32);
+        Some(unit)
     }
 
     /// Add a [`Texture`] to the [`Renderer`].
@@ -351,13 +779,26 @@ impl Renderer {
     /// Use the requested [`Texture`].
     ///
     /// Sets the currently bound [`Texture`] to the one that matches the key. If no such texture is found, a default MAGENTA one is found.
-    pub fn use_texture(&self, key: &str) {
-        let gl = &self.gl;
-        if let Some(texture) = self.textures.get(key) {
-            texture.bind(gl);
-        } else {
-            self.textures.get("MAGENTA").unwrap().bind(gl);
-        }
+    /// Plain [`Quads`](Quad) added with [`add_quad`](Renderer::add_quad) afterwards are batched with this [`Texture`].
+    pub fn use_texture(&mut self, key: &str) {
+        let texture = self.get_texture(key);
+        self.bind_sprite_texture(&texture);
+        self.selected_texture = Some(texture);
+    }
+
+    /// Get the [`Texture`] selected with [`use_texture`](Renderer::use_texture), or WHITE if none is.
+    fn selected_texture(&self) -> Rc<Texture> {
+        Rc::clone(
+            self.selected_texture
+                .as_ref()
+                .unwrap_or_else(|| self.textures.get("WHITE").unwrap()),
+        )
+    }
+
+    /// Bind a [`Texture`] to the texture unit of the [`SPRITE_SAMPLER`].
+    fn bind_sprite_texture(&self, texture: &Texture) {
+        let unit = self.texture_units.get(SPRITE_SAMPLER).unwrap_or(0);
+        texture.bind_to_unit(&self.gl, unit);
     }
 
     /// Get the requested [`Texture`], or MAGENTA if none is found.
@@ -369,176 +810,736 @@ impl Renderer {
         )
     }
 
+    /// Check if the WebGL context has been lost, in which case nothing can be drawn until it is restored.
+    pub fn is_context_lost(&self) -> bool {
+        self.gl.is_context_lost()
+    }
+
+    /// Recreate the WebGL objects of the [`Renderer`] after the context has been restored.
+    ///
+    /// The default [`Shader`] and built-in [`Textures`](Texture) are recreated, and post-processing passes are rebuilt when next used.
+    /// Other [`Textures`](Texture), the palette, the [`Font`] and any reloaded [`Shader`] were lost with the context, and must be set again.
+    /// This can be called more than once.
+    pub fn restore_context(&mut self) {
+        self.batches.clear();
+        self.batch_textures.clear();
+        self.selected_texture = None;
+        if self.palette.take().is_some() {
+            self.texture_units.free(PALETTE_SAMPLER);
+        }
+        self.textures = default_textures(&self.gl);
+        self.blit = None;
+        self.light_pass = None;
+        self.outline_program = None;
+        self.immediate_mesh = None;
+        self.instance_program = None;
+        self.instance_mesh = None;
+        self.instance_buffer_size = 0;
+        self.instancing_failed = false;
+        self.material = None;
+        if let Some(exposure) = self.tonemap.take().map(|tonemap| tonemap.exposure) {
+            self.enable_tonemapping(exposure);
+        }
+        self.set_builtin_shader();
+        self.init_shader();
+        self.blend_mode.apply(&self.gl);
+        self.apply_depth_and_cull();
+    }
+
+    /// Enable palette swapping, using the given [`Texture`] as the palette.
+    ///
+    /// The red channel of every sprite is used as an index into the palette, which is bound to the [`PALETTE_SAMPLER`].
+    /// Calling this again with a different palette recolors all sprites using it.
+    pub fn set_palette(&mut self, mut palette: Texture) {
+        let was_enabled = self.palette.is_some();
+        if let Some(unit) = self.bind_texture_to_sampler(&palette, PALETTE_SAMPLER) {
+            palette.slot = unit;
+        }
+        self.palette = Some(Rc::new(palette));
+        if !was_enabled {
+            self.set_builtin_shader();
+            self.init_shader();
+        }
+    }
+
+    /// Disable palette swapping, and return to the default [`Shader`].
+    pub fn clear_palette(&mut self) {
+        if let Some(palette) = self.palette.take() {
+            palette.unbind(&self.gl);
+            self.texture_units.free(PALETTE_SAMPLER);
+            self.set_builtin_shader();
+            self.init_shader();
+        }
+    }
+
+    /// Get the current palette [`Texture`], if palette swapping is enabled.
+    pub fn get_palette(&self) -> Option<Rc<Texture>> {
+        self.palette.as_ref().map(Rc::clone)
+    }
+
     /// Clear the batch queue and start a new batch.
     pub fn begin_draw(&mut self) {
-        let gl = &self.gl;
-
         self.batches.clear();
+        self.batch_textures.clear();
+        self.lines.clear();
+        self.push_batch(None);
+    }
 
+    /// Add an empty batch using a given [`Texture`], or the one selected with [`use_texture`](Renderer::use_texture) if [`None`].
+    fn push_batch(&mut self, texture: Option<Rc<Texture>>) {
         let mesh = Mesh::new(
-            gl,
+            &self.gl,
             Vec::with_capacity(MAX_BATCH_VERTICES as usize),
             Vec::with_capacity(MAX_BATCH_INDICES as usize),
         );
-
         self.batches.push(mesh);
+        self.batch_textures.push(texture);
+        self.stats.batch_count += 1;
     }
 
     /// Add a [`Quad`] to the batching queue.
+    ///
+    /// The [`Quad`] is drawn with the [`Texture`] selected with [`use_texture`](Renderer::use_texture), or WHITE if none is.
+    /// A new batch is started if the last one uses a different [`Texture`], such as that of a sprite, or if it is full.
     pub fn add_quad(&mut self, quad: &Quad) {
-        let gl = &self.gl;
+        let texture = self.selected_texture();
+        self.add_quad_with_texture(quad, texture);
+    }
 
+    /// Add a [`Quad`] using a sprite to the batching queue.
+    ///
+    /// A new batch is started if the [`Texture`] of the sprite differs from that of the last batch, or if the last one is full.
+    /// A sprite without a [`Texture`] is drawn like [`add_quad`](Renderer::add_quad).
+    pub fn add_sprite_quad(&mut self, quad: &Quad, sprite: &SubTexture) {
+        let texture = sprite
+            .texture()
+            .cloned()
+            .unwrap_or_else(|| self.selected_texture());
+        self.add_quad_with_texture(quad, texture);
+    }
+
+    /// Add a [`Quad`] to the last batch if it uses the same [`Texture`] and is not full, or to a new batch otherwise.
+    fn add_quad_with_texture(&mut self, quad: &Quad, texture: Rc<Texture>) {
         // Get last batch. This should never be empty becase begin_draw should have been called before.
-        let mut batch = self
+        let batch = self
             .batches
-            .last_mut()
+            .last()
             .expect("Batch list empty. Check if begin_draw was called before.");
-        if batch.vertices.len() + 4 > MAX_BATCH_VERTICES as usize {
-            let mesh = Mesh::new(
-                gl,
-                Vec::with_capacity(MAX_BATCH_VERTICES as usize),
-                Vec::with_capacity(MAX_BATCH_INDICES as usize),
-            );
-            self.batches.push(mesh);
-
-            batch = self.batches.last_mut().unwrap();
+        let same_texture = match self.batch_textures.last().cloned().flatten() {
+            Some(current) => Rc::ptr_eq(&current, &texture),
+            // An untextured batch can adopt the texture if it has no quads yet
+            None => batch.vertices.is_empty(),
+        };
+        if !same_texture || batch.vertices.len() + 4 > MAX_BATCH_VERTICES as usize {
+            self.push_batch(Some(texture));
+        } else if let Some(last) = self.batch_textures.last_mut() {
+            *last = Some(texture);
         }
+        self.append_quad(quad);
+    }
 
+    /// Append the vertices and indices of a [`Quad`] to the last batch.
+    fn append_quad(&mut self, quad: &Quad) {
+        self.stats.quad_count += 1;
+        let batch = self.batches.last_mut().unwrap();
         let last = batch.vertices.len() as u32;
         batch.vertices.append(&mut quad.get_vertices());
         let mut indices = vec![last, last + 2, last + 1, last, last + 3, last + 2];
         batch.indices.append(&mut indices);
     }
 
+    /// Get the number of batches queued since [`begin_draw`](Renderer::begin_draw).
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
     /// Begin a new layer.
     ///
     /// A new mesh is added to the batches and subsequent calls are made on this layer.
     pub fn begin_layer(&mut self) {
-        let gl = &self.gl;
-        let mesh = Mesh::new(
-            gl,
-            Vec::with_capacity(MAX_BATCH_VERTICES as usize),
-            Vec::with_capacity(MAX_BATCH_INDICES as usize),
-        );
-
-        self.batches.push(mesh);
+        self.push_batch(None);
     }
 
     /// Remove the last layer.
     pub fn delete_layer(&mut self) -> Option<Mesh> {
+        self.batch_textures.pop();
         self.batches.pop()
     }
 
     /// Draw the current layer.
-    pub fn draw_layer(&self) {
-        let gl = &self.gl;
-        if let Some(batch) = self.batches.last() {
-            batch.setup(gl);
-            gl.draw_elements_with_i32(
-                GL::TRIANGLES,
-                batch.indices.len() as i32,
-                GL::UNSIGNED_INT,
-                0,
-            );
+    pub fn draw_layer(&mut self) {
+        if let Some(index) = self.batches.len().checked_sub(1) {
+            self.draw_batch(index);
         }
     }
 
-    /// Draw all batched geometry.
+    /// Draw all batched geometry, with one draw call for each non-empty batch.
     pub fn end_draw(&mut self) {
         let gl = &self.gl;
-        gl.uniform_matrix4fv_with_f32_array(
-            self.u_view_matrix.as_ref(),
-            false,
-            self.camera.transform.matrix_slice(),
+        let program = self.active_shader();
+        program.bind(gl);
+        program.set_uniform_mat4(gl, "uView", &self.camera.view_matrix());
+        for index in 0..self.batches.len() {
+            self.draw_batch(index);
+        }
+        self.draw_lines();
+    }
+
+    /// Queue a [`Line`] between two points, drawn over the batched [`Quads`](Quad) by [`end_draw`](Renderer::end_draw).
+    pub fn draw_line(&mut self, start: Vec2, end: Vec2, color: Color32) {
+        self.lines.push(Line::new(start, end, color));
+    }
+
+    /// Queue the outline of an [`AABB`], such as a collider, drawn by [`end_draw`](Renderer::end_draw).
+    pub fn draw_aabb_outline(&mut self, aabb: &AABB, color: Color32) {
+        self.lines.extend(Line::aabb_outline(aabb, color));
+    }
+
+    /// Set the [`Font`] used by [`draw_text`](Renderer::draw_text).
+    pub fn set_font(&mut self, font: Font) {
+        self.font = Some(font);
+    }
+
+    /// Get the [`Font`] used by [`draw_text`](Renderer::draw_text), if any.
+    pub fn font(&self) -> Option<&Font> {
+        self.font.as_ref()
+    }
+
+    /// Add a [`Quad`] for each character of some text to the batching queue, with its top-left corner at a given position.
+    ///
+    /// Nothing is drawn if no [`Font`] is set. See [`Font::quads`] for how the text is laid out.
+    pub fn draw_text(&mut self, text: &str, position: Vec2, scale: f32, color: Color32) {
+        let font = match self.font.take() {
+            Some(font) => font,
+            None => return,
+        };
+        for quad in font.quads(text, position, scale, color) {
+            self.add_sprite_quad(&quad, font.atlas());
+        }
+        self.font = Some(font);
+    }
+
+    /// Set the width of queued [`Lines`](Line) in world units, or [`None`] to draw them one pixel wide.
+    ///
+    /// WebGL ignores line widths other than one on most platforms, so wide lines are drawn as thin [`Quads`](Quad) instead.
+    pub fn set_line_width(&mut self, width: Option<f32>) {
+        self.line_width = width;
+    }
+
+    /// Get the width of queued [`Lines`](Line) in world units, if they are drawn as [`Quads`](Quad).
+    pub fn line_width(&self) -> Option<f32> {
+        self.line_width
+    }
+
+    /// Draw and clear the queued [`Lines`](Line), in a single draw call, with the WHITE [`Texture`].
+    ///
+    /// The [`Texture`] selected with [`use_texture`](Renderer::use_texture) is not changed.
+    fn draw_lines(&mut self) {
+        if self.lines.is_empty() {
+            return;
+        }
+        let white = self.get_texture("WHITE");
+        self.bind_sprite_texture(&white);
+        let lines = std::mem::take(&mut self.lines);
+        if let Some(width) = self.line_width {
+            let quads: Vec<Quad> = lines.iter().map(|line| line.get_quad(width)).collect();
+            self.draw_quads(&quads);
+            return;
+        }
+
+        let gl = &self.gl;
+        let mut mesh = Mesh::new(
+            gl,
+            Vec::with_capacity(lines.len() * 2),
+            (0..lines.len() as u32 * 2).collect(),
         );
-        self.program.bind(gl);
-        for batch in self.batches.iter() {
-            batch.setup(gl);
-            gl.draw_elements_with_i32(
-                GL::TRIANGLES,
-                batch.indices.len() as i32,
-                GL::UNSIGNED_INT,
-                0,
-            );
+        for line in lines.iter() {
+            let color = <[f32; 4]>::from(line.color);
+            for point in [line.start, line.end] {
+                mesh.vertices.push(Vertex {
+                    position: [point.x, point.y],
+                    color,
+                    ..Default::default()
+                });
+            }
         }
+        mesh.setup(gl);
+        mesh.draw_with_mode(gl, GL::LINES);
+        self.stats.draw_calls += 1;
     }
 
-    /// Clear the screen with a given Color.
-    pub fn clear(&mut self, color: [f32; 4]) {
+    /// Bind the [`Texture`] of a batch, or the one selected with [`use_texture`](Renderer::use_texture) if it has none, and draw it.
+    fn draw_batch(&mut self, index: usize) {
+        if self.batches[index].indices.is_empty() {
+            return;
+        }
+        let texture = self.batch_textures[index]
+            .clone()
+            .unwrap_or_else(|| self.selected_texture());
+        self.bind_sprite_texture(&texture);
         let gl = &self.gl;
-        gl.clear_color(color[0], color[1], color[2], color[3]);
-        gl.clear(GL::COLOR_BUFFER_BIT);
+        let batch = &self.batches[index];
+        batch.setup(gl);
+        batch.draw(gl);
+        self.stats.draw_calls += 1;
+    }
+
+    /// Enable the [`Tonemap`] post-processing pass with a given exposure.
+    ///
+    /// The scene is rendered off-screen between [`begin_frame`](Renderer::begin_frame) and [`end_frame`](Renderer::end_frame), and tonemapped onto the screen.
+    pub fn enable_tonemapping(&mut self, exposure: f32) {
+        if let Some(tonemap) = self.tonemap.as_mut() {
+            tonemap.exposure = exposure;
+        } else {
+            let (width, height) = self.render_target_size();
+            self.tonemap = Some(Tonemap::new(&self.gl, width, height, exposure));
+        }
+    }
+
+    /// Disable the [`Tonemap`] post-processing pass.
+    pub fn disable_tonemapping(&mut self) {
+        self.tonemap = None;
+        self.texture_units.free(SCENE_SAMPLER);
+    }
+
+    /// Get the [`Tonemap`] pass, if it is enabled.
+    pub fn tonemap(&self) -> Option<&Tonemap> {
+        self.tonemap.as_ref()
+    }
+
+    /// Render the scene at a fixed resolution, and upscale it to the canvas with nearest filtering.
+    pub fn set_render_resolution(&mut self, width: u32, height: u32) {
+        self.render_resolution = Some((width.max(1), height.max(1)));
+    }
+
+    /// Render the scene at the resolution of the canvas.
+    pub fn clear_render_resolution(&mut self) {
+        self.render_resolution = None;
+        self.blit = None;
+    }
+
+    /// Get the size of the off-screen render target.
+    ///
+    /// This is the render resolution if set, or the size of the canvas.
+    pub fn render_target_size(&self) -> (u32, u32) {
+        self.render_resolution.unwrap_or((
+            self.gl.drawing_buffer_width() as u32,
+            self.gl.drawing_buffer_height() as u32,
+        ))
+    }
+
+    /// Get the off-screen render target the scene is drawn into, if any.
+    pub fn render_target(&self) -> Option<&Framebuffer> {
+        if let Some(tonemap) = self.tonemap.as_ref() {
+            Some(&tonemap.framebuffer)
+        } else {
+            self.blit.as_ref().map(|blit| &blit.framebuffer)
+        }
+    }
+
+    /// Add a [`Light`] to the scene.
+    ///
+    /// While any lights are present, the scene is multiplied by the accumulated lights on [`end_frame`](Renderer::end_frame).
+    pub fn add_light(&mut self, light: Light) {
+        self.lights.push(light);
+    }
+
+    /// Remove all [`Lights`](Light) from the scene.
+    pub fn clear_lights(&mut self) {
+        self.lights.clear();
+        self.light_pass = None;
+    }
+
+    /// Get the [`Lights`](Light) in the scene.
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    /// Set the ambient light, used where no [`Light`] reaches.
+    pub fn set_ambient_light(&mut self, color: Color32) {
+        self.ambient_light = color;
+    }
+
+    /// Get the ambient light.
+    pub fn ambient_light(&self) -> Color32 {
+        self.ambient_light
+    }
+
+    /// Set the [`BlendMode`] used to draw, and apply it.
+    pub fn set_blend_mode(&mut self, mode: BlendMode) {
+        self.blend_mode = mode;
+        mode.apply(&self.gl);
+    }
+
+    /// Get the [`BlendMode`] used to draw.
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend_mode
+    }
+
+    /// Enable or disable depth testing, and apply it.
+    ///
+    /// This can be changed between draws in a frame, such as to draw a 2D [`TileMap`](crate::tilemap::TileMap) and then a 3D model.
+    /// The depth buffer is cleared by [`begin_frame`](Renderer::begin_frame) either way.
+    pub fn set_depth_test(&mut self, enabled: bool) {
+        self.depth_test = enabled;
+        self.apply_depth_and_cull();
+    }
+
+    /// Check if depth testing is enabled.
+    pub fn depth_test(&self) -> bool {
+        self.depth_test
+    }
+
+    /// Set which faces are culled, or [`None`] to draw all faces, and apply it.
+    pub fn set_cull_face(&mut self, mode: Option<CullMode>) {
+        self.cull_face = mode;
+        self.apply_depth_and_cull();
+    }
+
+    /// Get which faces are culled, if any.
+    pub fn cull_face(&self) -> Option<CullMode> {
+        self.cull_face
     }
 
-    /// Add a [`Component`] to the [`Renderer`].
-    pub fn add_component(&mut self, name: &'static str, component: Box<dyn Component>) {
-        self.components.insert(name, component);
+    /// Set the depth test and face culling state of the WebGL context to match the [`Renderer`].
+    fn apply_depth_and_cull(&self) {
+        let gl = &self.gl;
+        if self.depth_test {
+            gl.enable(GL::DEPTH_TEST);
+        } else {
+            gl.disable(GL::DEPTH_TEST);
+        }
+        match self.cull_face {
+            Some(mode) => {
+                gl.enable(GL::CULL_FACE);
+                gl.cull_face(mode.as_gl());
+            }
+            None => gl.disable(GL::CULL_FACE),
+        }
     }
 
-    /// (Re)Initialize the [`Components`](Component) of the [`Renderer`].
-    pub fn init_components(&mut self) {
-        for component in self.components.values_mut() {
-            component.init()
+    /// Get the [`RenderStats`] of the current frame.
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    /// Get the number of post-processing passes run in the last frame.
+    pub fn post_passes(&self) -> u32 {
+        self.stats.post_passes
+    }
+
+    /// Get the number of draw calls issued in the current frame.
+    pub fn draw_calls(&self) -> u32 {
+        self.stats.draw_calls
+    }
+
+    /// Begin a new frame, and clear it with a given Color.
+    ///
+    /// If post-processing is enabled, the off-screen render target is bound, and resized to match the canvas if needed.
+    pub fn begin_frame(&mut self, color: [f32; 4]) {
+        self.stats = RenderStats::default();
+
+        let (width, height) = self.render_target_size();
+        let gl = &self.gl;
+        if let Some(tonemap) = self.tonemap.as_mut() {
+            let framebuffer = &tonemap.framebuffer;
+            if framebuffer.width() != width || framebuffer.height() != height {
+                tonemap.resize(gl, width, height);
+            }
+            tonemap.framebuffer.bind(gl);
+        } else if self.render_resolution.is_some() {
+            let blit = self
+                .blit
+                .get_or_insert_with(|| Blit::new(gl, width, height));
+            let framebuffer = &blit.framebuffer;
+            if framebuffer.width() != width || framebuffer.height() != height {
+                blit.resize(gl, width, height);
+            }
+            blit.framebuffer.bind(gl);
+        }
+        self.blend_mode.apply(gl);
+        self.apply_depth_and_cull();
+        self.active_shader().bind(&self.gl);
+        self.clear(color);
+    }
+
+    /// End the current frame, running any post-processing passes.
+    ///
+    /// The [`Tonemap`] pass also upscales the scene if a render resolution is set, so at most one pass is run.
+    pub fn end_frame(&mut self) {
+        // Full screen passes must not be depth tested or culled
+        self.gl.disable(GL::DEPTH_TEST);
+        self.gl.disable(GL::CULL_FACE);
+        if !self.lights.is_empty() {
+            self.run_light_pass();
+            self.blend_mode.apply(&self.gl);
+        }
+
+        let gl = &self.gl;
+        if self.tonemap.is_some() || self.blit.is_some() {
+            if let Some(unit) = self.texture_units.allocate(SCENE_SAMPLER) {
+                if let Some(tonemap) = self.tonemap.as_ref() {
+                    tonemap.run(gl, unit);
+                } else if let Some(blit) = self.blit.as_ref() {
+                    blit.run(gl, unit);
+                }
+                self.stats.post_passes += 1;
+            }
         }
+        self.active_shader().bind(gl);
+        self.apply_depth_and_cull();
     }
 
-    /// Update the [`Components`](Component) of the [`Renderer`].
-    pub fn update_components(&mut self, delta_time: f32) {
-        for component in self.components.values_mut() {
-            component.update(delta_time)
+    /// Accumulate the [`Lights`](Light) into the [`LightPass`], and multiply the scene by them.
+    fn run_light_pass(&mut self) {
+        let unit = match self.texture_units.allocate(SCENE_SAMPLER) {
+            Some(unit) => unit,
+            None => return,
+        };
+        let (width, height) = self.render_target_size();
+        let gl = &self.gl;
+        let light_pass = self
+            .light_pass
+            .get_or_insert_with(|| LightPass::new(gl, width, height));
+        let framebuffer = &light_pass.framebuffer;
+        if framebuffer.width() != width || framebuffer.height() != height {
+            light_pass.resize(gl, width, height);
         }
+        light_pass.begin(
+            gl,
+            self.ambient_light,
+            &self.camera.view_matrix(),
+            self.camera.projection_kind().as_matrix(),
+        );
+
+        let quads: Vec<Quad> = self.lights.iter().map(Light::get_quad).collect();
+        self.draw_quads(&quads);
+
+        self.bind_render_target();
+        if let Some(light_pass) = self.light_pass.as_ref() {
+            light_pass.composite(&self.gl, unit);
+        }
+        self.stats.post_passes += 1;
     }
 
-    /// Get a [`Components`](Component) using a key, and ty to cast it to a given type.
-    pub fn get_component<T: 'static + Component>(&self, key: &'static str) -> Result<&T, String> {
-        self.components
-            .get(key)
-            .unwrap()
-            .as_any()
-            .downcast_ref::<T>()
-            .ok_or_else(|| String::from("Could not cast to type."))
+    /// Bind the off-screen render target the scene is drawn into, or the canvas if there is none.
+    fn bind_render_target(&self) {
+        let gl = &self.gl;
+        if let Some(target) = self.render_target() {
+            target.bind(gl);
+        } else {
+            gl.bind_framebuffer(GL::FRAMEBUFFER, None);
+            gl.viewport(0, 0, gl.drawing_buffer_width(), gl.drawing_buffer_height());
+        }
     }
 
-    /// Get a mutable [`Components`](Component) using a key, and ty to cast it to a given type.
-    pub fn get_mut_component<T: 'static + Component>(
-        &mut self,
-        key: &'static str,
-    ) -> Result<&mut T, String> {
-        self.components
-            .get_mut(key)
-            .unwrap()
-            .as_mut_any()
-            .downcast_mut::<T>()
-            .ok_or_else(|| String::from("Could not cast to type."))
-    }
-
-    /// Draw the [`Components`](Component) of the [`Renderer`].
-    pub fn draw_components(&mut self) {
+    /// Clear the screen with a given Color, along with the depth buffer.
+    ///
+    /// The depth buffer is always cleared, as depth testing may be enabled later in the frame.
+    pub fn clear(&mut self, color: [f32; 4]) {
         let gl = &self.gl;
-        let mut layers: Vec<Vec<Quad>> = self
-            .components
-            .values()
-            .filter_map(|component| component.get_quads())
-            .collect();
-        for layer in layers.iter_mut() {
-            let mut mesh = Mesh::new(
-                gl,
-                Vec::with_capacity(layer.len() * 4),
-                Vec::with_capacity(layer.len() * 6),
-            );
-            for (id, quad) in layer.iter_mut().enumerate() {
-                let last: u32 = id as u32 * 4;
-                mesh.vertices.append(&mut quad.get_vertices());
-                let mut indices = vec![last, last + 2, last + 1, last, last + 3, last + 2];
-                mesh.indices.append(&mut indices);
+        gl.clear_color(color[0], color[1], color[2], color[3]);
+        gl.clear(GL::COLOR_BUFFER_BIT | GL::DEPTH_BUFFER_BIT);
+    }
+
+    /// Draw the [`Components`](crate::component::Component) of a [`Scene`], with the [`Texture`] selected with [`use_texture`](Renderer::use_texture).
+    ///
+    /// The `alpha` is how far the frame is between the last two simulation steps, see [`Scene::layers`].
+    pub fn draw_components(&mut self, scene: &Scene, alpha: f32) {
+        let texture = self.selected_texture();
+        self.bind_sprite_texture(&texture);
+        let gl = &self.gl;
+        self.active_shader()
+            .set_uniform_mat4(gl, "uView", &self.camera.view_matrix());
+        for layer in scene.layers(alpha).iter() {
+            self.draw_quads(layer);
+        }
+    }
+
+    /// Draw a slice of [`Quads`](Quad) in a single draw call, using the currently bound [`Shader`].
+    ///
+    /// The same [`Mesh`] is reused by every call, so no GPU objects are created after the first one.
+    pub fn draw_quads(&mut self, quads: &[Quad]) {
+        let gl = &self.gl;
+        let mut mesh = self
+            .immediate_mesh
+            .take()
+            .unwrap_or_else(|| Mesh::new(gl, Vec::new(), Vec::new()));
+        mesh.vertices.clear();
+        mesh.indices.clear();
+        for (id, quad) in quads.iter().enumerate() {
+            let last: u32 = id as u32 * 4;
+            mesh.vertices.append(&mut quad.get_vertices());
+            let mut indices = vec![last, last + 2, last + 1, last, last + 3, last + 2];
+            mesh.indices.append(&mut indices);
+        }
+        mesh.setup(gl);
+        mesh.draw(gl);
+        self.immediate_mesh = Some(mesh);
+        self.stats.draw_calls += 1;
+        self.stats.quad_count += quads.len() as u32;
+        self.stats.batch_count += 1;
+    }
+
+    /// Draw many instances of a sprite in a single instanced draw call.
+    ///
+    /// The instances are uploaded as a per-instance buffer, and a unit [`Quad`] is transformed by each on the GPU.
+    /// The quad and instance buffer are kept between calls, and the buffer only grows when more instances are drawn than before.
+    /// Any batched [`Quads`](Quad) are drawn first, so they stay behind the instances.
+    /// If the instancing [`Shader`] cannot be created, the instances are drawn as a batch of [`Quads`](Quad) instead.
+    pub fn draw_instanced(&mut self, sprite: &SubTexture, instances: &[InstanceData]) {
+        if instances.is_empty() {
+            return;
+        }
+        self.flush_batches();
+        if self.instance_program.is_none() && !self.instancing_failed {
+            let defines = self.shader_defines();
+            self.instance_program = Shader::new_with_sources(
+                &self.gl,
+                &define(include_str!("../res/shader/instanced.vert.glsl"), defines),
+                &define(include_str!("../res/shader/default.frag.glsl"), defines),
+                "Instanced Shader",
+            )
+            .ok();
+            self.instancing_failed = self.instance_program.is_none();
+        }
+
+        let unit = self.texture_units.get(SPRITE_SAMPLER).unwrap_or(0);
+        if let Some(texture) = sprite.texture() {
+            texture.bind_to_unit(&self.gl, unit);
+        }
+
+        let program = match self.instance_program.as_ref() {
+            Some(program) => program,
+            None => {
+                let quads: Vec<Quad> = instances
+                    .iter()
+                    .map(|instance| instance.quad(sprite))
+                    .collect();
+                self.draw_quads(&quads);
+                return;
             }
+        };
+
+        let gl = &self.gl;
+        let uv = sprite.get_uv_coords();
+        program.bind(gl);
+        program.set_uniform_mat4(gl, "uView", &self.camera.view_matrix());
+        program.set_uniform_mat4(gl, "uProj", self.camera.projection_kind().as_matrix());
+        program.set_uniform_vec4(gl, "uColor", &Vec4::new(1.0, 1.0, 1.0, 1.0));
+        program.set_uniform_f32(gl, "uLogDepthFC", self.log_depth_coefficient());
+        program.set_uniform_i32(gl, SPRITE_SAMPLER, unit as i32);
+        program.set_uniform_vec4(
+            gl,
+            "uSpriteRect",
+            &Vec4::new(uv[0][0], uv[0][1], uv[2][0], uv[2][1]),
+        );
+
+        let instance_bytes = unsafe {
+            std::slice::from_raw_parts(
+                instances.as_ptr() as *const u8,
+                std::mem::size_of_val(instances),
+            )
+        };
+        let mesh = self.instance_mesh.get_or_insert_with(|| {
+            let mut mesh = Mesh::quad(gl);
+            mesh.add_buffer(gl, InstanceData::layout(), Vec::new());
             mesh.setup(gl);
-            gl.draw_elements_with_i32(
-                GL::TRIANGLES,
-                mesh.indices.len() as i32,
-                GL::UNSIGNED_INT,
-                0,
+            mesh
+        });
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(mesh.buffers[0].buffer()));
+        if instance_bytes.len() > self.instance_buffer_size {
+            gl.buffer_data_with_u8_array(GL::ARRAY_BUFFER, instance_bytes, GL::DYNAMIC_DRAW);
+            self.instance_buffer_size = instance_bytes.len();
+        } else {
+            gl.buffer_sub_data_with_i32_and_u8_array(GL::ARRAY_BUFFER, 0, instance_bytes);
+        }
+        mesh.draw_instanced(gl, instances.len() as i32);
+
+        self.active_shader().bind(gl);
+        self.stats.draw_calls += 1;
+        self.stats.quad_count += instances.len() as u32;
+        self.stats.batch_count += 1;
+    }
+
+    /// Draw a sprite with a solid colored outline around its opaque pixels.
+    ///
+    /// The outline is drawn behind the sprite, by dilating its alpha by `thickness` texels.
+    /// The outline [`Quad`] is grown by `thickness` texels on each side, so the outline can extend beyond the edges of the sprite.
+    pub fn draw_sprite_outlined(
+        &mut self,
+        sprite: &SubTexture,
+        rect: &AABB,
+        outline_color: Color32,
+        thickness: f32,
+    ) {
+        let center = (rect.min + rect.max) / 2.0;
+        let size = rect.max - rect.min;
+        let quad =
+            Quad::new_from_position_and_size_and_sprite(center.x, center.y, size.x, size.y, sprite);
+
+        let uv = sprite.get_uv_coords();
+        let (uv_min, uv_max) = (uv[0], uv[2]);
+        let uv_rect = Vec4::new(
+            uv_min[0].min(uv_max[0]),
+            uv_min[1].min(uv_max[1]),
+            uv_min[0].max(uv_max[0]),
+            uv_min[1].max(uv_max[1]),
+        );
+        let unit = self.texture_units.get(SPRITE_SAMPLER).unwrap_or(0);
+        let texel_size = if let Some(texture) = sprite.texture() {
+            texture.bind_to_unit(&self.gl, unit);
+            Vec2::new(1.0 / texture.width as f32, 1.0 / texture.height as f32)
+        } else {
+            Vec2::new(1.0, 1.0)
+        };
+
+        // The padding of each side, relative to the size of the sprite
+        let padding = Vec2::new(
+            thickness * texel_size.x / (uv_rect.z - uv_rect.x).max(f32::EPSILON),
+            thickness * texel_size.y / (uv_rect.w - uv_rect.y).max(f32::EPSILON),
+        );
+        let outline_quad = Quad::new_from_position_and_size_and_sprite(
+            center.x,
+            center.y,
+            size.x * (1.0 + 2.0 * padding.x),
+            size.y * (1.0 + 2.0 * padding.y),
+            &sprite.region(Color32(
+                -padding.x,
+                1.0 + padding.x,
+                -padding.y,
+                1.0 + padding.y,
+            )),
+        );
+
+        if self.outline_program.is_none() {
+            self.outline_program = Shader::new_with_sources(
+                &self.gl,
+                include_str!("../res/shader/default.vert.glsl"),
+                include_str!("../res/shader/outline.frag.glsl"),
+                "Outline Shader",
+            )
+            .ok();
+        }
+
+        if let Some(outline) = self.outline_program.as_ref() {
+            let gl = &self.gl;
+            outline.bind(gl);
+            outline.set_uniform_mat4(gl, "uView", &self.camera.view_matrix());
+            outline.set_uniform_mat4(gl, "uProj", self.camera.projection_kind().as_matrix());
+            outline.set_uniform_i32(gl, SPRITE_SAMPLER, unit as i32);
+            outline.set_uniform_vec4(
+                gl,
+                "uOutlineColor",
+                &Vec4::from(<[f32; 4]>::from(outline_color)),
             );
+            outline.set_uniform_f32(gl, "uOutlineThickness", thickness);
+            outline.set_uniform_vec2(gl, "uTexelSize", &texel_size);
+            outline.set_uniform_vec4(gl, "uUVRect", &uv_rect);
+            self.draw_quads(&[outline_quad]);
         }
+
+        self.active_shader().bind(&self.gl);
+        self.draw_quads(&[quad]);
     }
 }
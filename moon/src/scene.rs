@@ -0,0 +1,105 @@
+//! The [`Scene`] struct, which holds the [`Components`](Component) of the game.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::component::Component;
+use crate::renderer::Quad;
+
+/// A collection of named [`Components`](Component), that are updated by the simulation and drawn by the [`Renderer`](crate::renderer::Renderer).
+///
+/// A [`Scene`] does not need a WebGL context, so game logic can be stepped and tested without a browser.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::game::Player;
+/// use moon_engine::scene::Scene;
+///
+/// let mut scene = Scene::new();
+/// scene.add_component("PLAYER", Box::new(Player::new_with_position(0.0, 0.0)));
+/// scene.update_components(0.5);
+///
+/// assert!(scene.get_component::<Player>("PLAYER").unwrap().position.y > 0.0);
+/// ```
+#[derive(Default)]
+pub struct Scene {
+    /// [`Components`](Component) that can be added to the [`Scene`].
+    pub components: BTreeMap<&'static str, Box<dyn Component>>,
+    paused_components: BTreeSet<&'static str>,
+}
+
+impl Scene {
+    /// Create a new, empty [`Scene`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a [`Component`] to the [`Scene`].
+    pub fn add_component(&mut self, name: &'static str, component: Box<dyn Component>) {
+        self.components.insert(name, component);
+    }
+
+    /// (Re)Initialize the [`Components`](Component) of the [`Scene`].
+    pub fn init_components(&mut self) {
+        for component in self.components.values_mut() {
+            component.init()
+        }
+    }
+
+    /// Update the [`Components`](Component) of the [`Scene`], skipping any that are paused.
+    pub fn update_components(&mut self, delta_time: f32) {
+        for (name, component) in self.components.iter_mut() {
+            if !self.paused_components.contains(name) {
+                component.update(delta_time)
+            }
+        }
+    }
+
+    /// Pause a [`Component`] using a key, so it is still drawn but no longer updated.
+    pub fn pause_component(&mut self, key: &'static str) {
+        self.paused_components.insert(key);
+    }
+
+    /// Resume updating a paused [`Component`] using a key.
+    pub fn resume_component(&mut self, key: &'static str) {
+        self.paused_components.remove(key);
+    }
+
+    /// Check if a [`Component`] is paused using a key.
+    pub fn is_component_paused(&self, key: &str) -> bool {
+        self.paused_components.contains(key)
+    }
+
+    /// Get a [`Components`](Component) using a key, and ty to cast it to a given type.
+    pub fn get_component<T: 'static + Component>(&self, key: &'static str) -> Result<&T, String> {
+        self.components
+            .get(key)
+            .ok_or_else(|| format!("No component with key {}.", key))?
+            .as_any()
+            .downcast_ref::<T>()
+            .ok_or_else(|| String::from("Could not cast to type."))
+    }
+
+    /// Get a mutable [`Components`](Component) using a key, and ty to cast it to a given type.
+    pub fn get_mut_component<T: 'static + Component>(
+        &mut self,
+        key: &'static str,
+    ) -> Result<&mut T, String> {
+        self.components
+            .get_mut(key)
+            .ok_or_else(|| format!("No component with key {}.", key))?
+            .as_mut_any()
+            .downcast_mut::<T>()
+            .ok_or_else(|| String::from("Could not cast to type."))
+    }
+
+    /// Get the [`Quads`](Quad) of each [`Component`] that can be drawn, one layer per [`Component`].
+    ///
+    /// The `alpha` is passed to [`Component::get_interpolated_quads`], to draw between the last two updates.
+    pub fn layers(&self, alpha: f32) -> Vec<Vec<Quad>> {
+        self.components
+            .values()
+            .filter_map(|component| component.get_interpolated_quads(alpha))
+            .collect()
+    }
+}
@@ -1,11 +1,16 @@
 //! The [`Shader`] struct, and [`ShaderType`] enum.
 
+use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::fmt;
 use web_sys::WebGlProgram;
 use web_sys::WebGlShader;
 use web_sys::WebGlUniformLocation;
 
-use crate::{gl, GL};
+use crate::{console_log, gl, Mat4, Vec2, Vec3, Vec4, GL};
+
+/// The `#define` name that enables writing a logarithmic depth in the built-in Fragment Shaders.
+pub const LOG_DEPTH_DEFINE: &str = "LOG_DEPTH";
 
 /// Type of Shader
 #[repr(u32)]
@@ -24,6 +29,7 @@ pub struct Shader {
     /// A name to refer to the shader with, and for debugging purposes.
     pub name: &'static str,
     program: Option<WebGlProgram>,
+    uniforms: RefCell<BTreeMap<String, Option<WebGlUniformLocation>>>,
 }
 
 impl Default for Shader {
@@ -31,6 +37,7 @@ impl Default for Shader {
         Self {
             name: "Uninitialized Shader",
             program: None,
+            uniforms: RefCell::default(),
         }
     }
 }
@@ -58,19 +65,90 @@ impl gl::Bind for Shader {
 impl Shader {
     /// Create a new Shader Program with default Vertex and Fragment shaders.
     pub fn new(gl: &GL) -> Self {
-        let name = "Default Shader";
-        let vertex_shader =
-            Shader::create_vertex(gl, include_str!("../res/shader/default.vert.glsl"))
-                .expect("Could not create Vertex Shader!");
+        Self::new_with_defines(gl, &[])
+    }
 
-        let fragment_shader =
-            Shader::create_fragment(gl, include_str!("../res/shader/default.frag.glsl"))
-                .expect("Could not create Fragment Shader!");
+    /// Create a new Shader Program with default Vertex and Fragment shaders, compiled with the given `#define` names.
+    ///
+    /// Passing [`LOG_DEPTH_DEFINE`] compiles the variant that writes a logarithmic depth.
+    pub fn new_with_defines(gl: &GL, defines: &[&str]) -> Self {
+        Self::new_builtin(
+            gl,
+            include_str!("../res/shader/default.frag.glsl"),
+            defines,
+            "Default Shader",
+        )
+    }
+
+    /// Create a new Shader Program with the default Vertex Shader and a palette-swapping Fragment Shader.
+    ///
+    /// The red channel of the sprite is used as an index into a palette [`Texture`](crate::texture::Texture) bound to the `uPalette` sampler.
+    pub fn new_palette(gl: &GL) -> Self {
+        Self::new_palette_with_defines(gl, &[])
+    }
+
+    /// Create a new palette-swapping Shader Program like [`new_palette`](Shader::new_palette), compiled with the given `#define` names.
+    pub fn new_palette_with_defines(gl: &GL, defines: &[&str]) -> Self {
+        Self::new_builtin(
+            gl,
+            include_str!("../res/shader/palette.frag.glsl"),
+            defines,
+            "Palette Shader",
+        )
+    }
+
+    /// Create a built-in Shader Program from the default Vertex Shader and a Fragment Shader, panicking if it does not compile.
+    ///
+    /// If the program does not link, the error is logged and the [`Shader`] has no program.
+    fn new_builtin(gl: &GL, fragment_source: &str, defines: &[&str], name: &'static str) -> Self {
+        let vertex_shader = Shader::create_vertex(
+            gl,
+            &define(include_str!("../res/shader/default.vert.glsl"), defines),
+        )
+        .expect("Could not create Vertex Shader!");
 
+        let fragment_shader = Shader::create_fragment(gl, &define(fragment_source, defines))
+            .expect("Could not create Fragment Shader!");
+
+        let program = Shader::link_or_log(gl, &vertex_shader, &fragment_shader, name);
+
+        Self {
+            name,
+            program,
+            ..Default::default()
+        }
+    }
+
+    /// Create a new Shader Program from the given Vertex and Fragment Shader sources.
+    pub fn new_with_sources(
+        gl: &GL,
+        vertex_source: &str,
+        fragment_source: &str,
+        name: &'static str,
+    ) -> Result<Self, String> {
+        let vertex_shader = Shader::create_vertex(gl, vertex_source)?;
+        let fragment_shader = Shader::create_fragment(gl, fragment_source)?;
         let program =
-            Shader::program_with_vertex_and_fragment(gl, &vertex_shader, &fragment_shader).ok();
+            Shader::program_with_vertex_and_fragment(gl, &vertex_shader, &fragment_shader)?;
 
-        Self { name, program }
+        Ok(Self {
+            name,
+            program: Some(program),
+            ..Default::default()
+        })
+    }
+
+    /// Create a new Shader Program from the given sources, after resolving `#include` directives with [`preprocess`].
+    pub fn new_with_includes(
+        gl: &GL,
+        vertex_source: &str,
+        fragment_source: &str,
+        includes: &BTreeMap<&str, &str>,
+        name: &'static str,
+    ) -> Result<Self, String> {
+        let vertex_source = preprocess(vertex_source, includes)?;
+        let fragment_source = preprocess(fragment_source, includes)?;
+        Self::new_with_sources(gl, &vertex_source, &fragment_source, name)
     }
 
     /// Create a new Shader with default Fragment Shader and a custom Vertex Shader.
@@ -85,10 +163,25 @@ impl Shader {
             Shader::create_fragment(gl, include_str!("../res/shader/default.frag.glsl"))
                 .expect("Could not create Fragment Shader!");
 
-        let program =
-            Shader::program_with_vertex_and_fragment(gl, &vertex_shader, &fragment_shader).ok();
+        let program = Shader::link_or_log(gl, &vertex_shader, &fragment_shader, name);
 
-        Self { name, program }
+        Self {
+            name,
+            program,
+            ..Default::default()
+        }
+    }
+
+    /// Link a [`WebGlProgram`], logging the error and returning [`None`] if it fails.
+    fn link_or_log(
+        gl: &GL,
+        vertex_shader: &WebGlShader,
+        fragment_shader: &WebGlShader,
+        name: &str,
+    ) -> Option<WebGlProgram> {
+        Shader::program_with_vertex_and_fragment(gl, vertex_shader, fragment_shader)
+            .map_err(|error| console_log!("Could not link {}: {}", name, error))
+            .ok()
     }
 
     /// Create a fragment `WebGlShader`.
@@ -120,9 +213,11 @@ impl Shader {
         {
             Ok(shader)
         } else {
-            Err(gl
+            let log = gl
                 .get_shader_info_log(&shader)
-                .unwrap_or_else(|| String::from("Could not compile shader.")))
+                .unwrap_or_else(|| String::from("Could not compile shader."));
+            gl.delete_shader(Some(&shader));
+            Err(annotate_log(&log, source))
         }
     }
 
@@ -154,12 +249,85 @@ impl Shader {
         }
     }
 
+    /// Get the [`WebGlProgram`] of the `Shader`, if it was created successfully.
+    pub fn program(&self) -> Option<&WebGlProgram> {
+        self.program.as_ref()
+    }
+
     /// Get the location of a uniform on the `Shader`.
+    ///
+    /// Locations are looked up once, and cached by name.
     pub fn get_uniform_location(&self, gl: &GL, name: &str) -> Option<WebGlUniformLocation> {
-        self.program
-            .as_ref()
-            .map(|program| gl.get_uniform_location(program, name))
-            .unwrap()
+        let program = self.program.as_ref()?;
+        self.uniforms
+            .borrow_mut()
+            .entry(name.to_string())
+            .or_insert_with(|| gl.get_uniform_location(program, name))
+            .clone()
+    }
+
+    /// Set a [`Mat4`] uniform on the `Shader`. The `Shader` must be bound.
+    ///
+    /// Does nothing if the uniform does not exist, or was optimized out.
+    pub fn set_uniform_mat4(&self, gl: &GL, name: &str, value: &Mat4) {
+        if let Some(location) = self.get_uniform_location(gl, name) {
+            gl.uniform_matrix4fv_with_f32_array(Some(&location), false, value.as_slice());
+        }
+    }
+
+    /// Set a [`Vec4`] uniform on the `Shader`. The `Shader` must be bound.
+    ///
+    /// Does nothing if the uniform does not exist, or was optimized out.
+    pub fn set_uniform_vec4(&self, gl: &GL, name: &str, value: &Vec4) {
+        if let Some(location) = self.get_uniform_location(gl, name) {
+            gl.uniform4fv_with_f32_array(Some(&location), value.as_slice());
+        }
+    }
+
+    /// Set a [`Vec3`] uniform on the `Shader`. The `Shader` must be bound.
+    ///
+    /// Does nothing if the uniform does not exist, or was optimized out.
+    pub fn set_uniform_vec3(&self, gl: &GL, name: &str, value: &Vec3) {
+        if let Some(location) = self.get_uniform_location(gl, name) {
+            gl.uniform3fv_with_f32_array(Some(&location), value.as_slice());
+        }
+    }
+
+    /// Set a [`Vec2`] uniform on the `Shader`. The `Shader` must be bound.
+    ///
+    /// Does nothing if the uniform does not exist, or was optimized out.
+    pub fn set_uniform_vec2(&self, gl: &GL, name: &str, value: &Vec2) {
+        if let Some(location) = self.get_uniform_location(gl, name) {
+            gl.uniform2fv_with_f32_array(Some(&location), value.as_slice());
+        }
+    }
+
+    /// Set an [`f32`] uniform on the `Shader`. The `Shader` must be bound.
+    ///
+    /// Does nothing if the uniform does not exist, or was optimized out.
+    pub fn set_uniform_f32(&self, gl: &GL, name: &str, value: f32) {
+        if let Some(location) = self.get_uniform_location(gl, name) {
+            gl.uniform1f(Some(&location), value);
+        }
+    }
+
+    /// Set a [`Mat4`] uniform on the `Shader`, if it exists. An alias of [`set_uniform_mat4`](Shader::set_uniform_mat4).
+    pub fn set_mat4(&self, gl: &GL, name: &str, value: &Mat4) {
+        self.set_uniform_mat4(gl, name, value);
+    }
+
+    /// Set an [`f32`] uniform on the `Shader`, if it exists. An alias of [`set_uniform_f32`](Shader::set_uniform_f32).
+    pub fn set_f32(&self, gl: &GL, name: &str, value: f32) {
+        self.set_uniform_f32(gl, name, value);
+    }
+
+    /// Set an [`i32`] uniform on the `Shader`, such as a sampler. The `Shader` must be bound.
+    ///
+    /// Does nothing if the uniform does not exist, or was optimized out.
+    pub fn set_uniform_i32(&self, gl: &GL, name: &str, value: i32) {
+        if let Some(location) = self.get_uniform_location(gl, name) {
+            gl.uniform1i(Some(&location), value);
+        }
     }
 
     /// Get the location of an attribute on the `Shader`.
@@ -169,3 +337,117 @@ impl Shader {
             .map(|program| gl.get_attrib_location(program, name))
     }
 }
+
+/// Add `#define` lines for the given names to a shader source.
+///
+/// The lines are added after the `#version` directive, which must stay first, or at the start if there is none.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::shader::define;
+///
+/// let source = define("#version 300 es\nvoid main() {}", &["LOG_DEPTH"]);
+///
+/// assert_eq!(source, "#version 300 es\n#define LOG_DEPTH\nvoid main() {}");
+/// ```
+pub fn define(source: &str, defines: &[&str]) -> String {
+    if defines.is_empty() {
+        return source.to_string();
+    }
+    let lines: String = defines
+        .iter()
+        .map(|name| format!("#define {}\n", name))
+        .collect();
+    match source.strip_prefix("#version") {
+        Some(rest) => {
+            let (version, body) = rest.split_once('\n').unwrap_or((rest, ""));
+            format!("#version{}\n{}{}", version, lines, body)
+        }
+        None => format!("{}{}", lines, source),
+    }
+}
+
+/// Replace `#include "name"` lines in a shader source with the named snippets.
+///
+/// Snippets can include other snippets. Returns an error if a snippet is missing, or includes itself through a cycle.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use moon_engine::shader::preprocess;
+///
+/// let includes = BTreeMap::from([("square", "float square(float x) { return x * x; }")]);
+/// let source = preprocess("#include \"square\"\nvoid main() {}", &includes).unwrap();
+///
+/// assert_eq!(source, "float square(float x) { return x * x; }\nvoid main() {}");
+/// ```
+pub fn preprocess(source: &str, includes: &BTreeMap<&str, &str>) -> Result<String, String> {
+    let mut stack = Vec::new();
+    preprocess_with_stack(source, includes, &mut stack)
+}
+
+/// Recursively resolve includes, keeping track of the snippets being included to detect cycles.
+fn preprocess_with_stack<'a>(
+    source: &str,
+    includes: &BTreeMap<&'a str, &'a str>,
+    stack: &mut Vec<&'a str>,
+) -> Result<String, String> {
+    let mut lines = Vec::new();
+    for line in source.lines() {
+        let name = match line.trim().strip_prefix("#include") {
+            Some(name) => name.trim().trim_matches('"'),
+            None => {
+                lines.push(line.to_string());
+                continue;
+            }
+        };
+        let (&name, &snippet) = includes
+            .get_key_value(name)
+            .ok_or_else(|| format!("Missing shader include \"{}\".", name))?;
+        if stack.contains(&name) {
+            return Err(format!(
+                "Cyclic shader include: {} -> {}.",
+                stack.join(" -> "),
+                name
+            ));
+        }
+        stack.push(name);
+        lines.push(preprocess_with_stack(snippet, includes, stack)?);
+        stack.pop();
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Annotate a shader info log with the source lines that it refers to.
+///
+/// Lines of the log in the `ERROR: 0:<line>: <message>` format used by WebGL are followed by the numbered line of source.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::shader::annotate_log;
+///
+/// let source = "#version 300 es\nvoid main() { broken }";
+/// let log = annotate_log("ERROR: 0:2: 'broken' : undeclared identifier", source);
+///
+/// assert!(log.ends_with("   2 | void main() { broken }"));
+/// ```
+pub fn annotate_log(log: &str, source: &str) -> String {
+    let source_lines: Vec<&str> = source.lines().collect();
+    let mut annotated = String::new();
+    for line in log.lines() {
+        annotated.push_str(line);
+        let number = line
+            .split(':')
+            .nth(2)
+            .and_then(|number| number.trim().parse::<usize>().ok())
+            .filter(|number| (1..=source_lines.len()).contains(number));
+        if let Some(number) = number {
+            annotated.push_str(&format!("\n{:>4} | {}", number, source_lines[number - 1]));
+        }
+        annotated.push('\n');
+    }
+    annotated.trim_end().to_string()
+}
@@ -1,5 +1,6 @@
-//! The [`Texture`] and [`SubTexture`] structs.
+//! The [`Texture`], [`SubTexture`], [`TextureOptions`] and [`TextureUnits`] structs.
 
+use std::collections::BTreeMap;
 use std::rc::Rc;
 
 use wasm_bindgen::JsCast;
@@ -7,8 +8,183 @@ use web_sys::HtmlImageElement;
 use web_sys::WebGlTexture;
 
 use crate::gl::Bind;
-use crate::Color32;
 use crate::{gl, GL};
+use crate::{Color32, Color8};
+
+/// Options used when creating a [`Texture`].
+#[derive(Debug, Clone, Copy)]
+pub struct TextureOptions {
+    /// An optional color that is treated as fully transparent.
+    ///
+    /// Pixels whose RGB components match the color key have their alpha set to 0. Only applies to [`Textures`](Texture) created from pixels.
+    pub color_key: Option<Color32>,
+    /// The filter used when the [`Texture`] is minified, such as [`GL::NEAREST`] or [`GL::LINEAR`].
+    pub min_filter: u32,
+    /// The filter used when the [`Texture`] is magnified.
+    pub mag_filter: u32,
+    /// How the [`Texture`] wraps horizontally, such as [`GL::REPEAT`] or [`GL::CLAMP_TO_EDGE`].
+    pub wrap_s: u32,
+    /// How the [`Texture`] wraps vertically.
+    pub wrap_t: u32,
+    /// Whether to generate mipmaps, to reduce shimmering when the [`Texture`] is drawn smaller than its size.
+    pub generate_mipmaps: bool,
+}
+
+impl Default for TextureOptions {
+    fn default() -> Self {
+        Self {
+            color_key: None,
+            min_filter: GL::NEAREST,
+            mag_filter: GL::NEAREST,
+            wrap_s: GL::REPEAT,
+            wrap_t: GL::REPEAT,
+            generate_mipmaps: false,
+        }
+    }
+}
+
+impl TextureOptions {
+    /// Get the minification filter to use.
+    ///
+    /// If mipmaps are generated, a non-mipmapped filter is replaced by its mipmapped counterpart, blending between mipmap levels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::texture::TextureOptions;
+    /// use moon_engine::gl::GL;
+    ///
+    /// let options = TextureOptions {
+    ///     min_filter: GL::LINEAR,
+    ///     generate_mipmaps: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(options.effective_min_filter(), GL::LINEAR_MIPMAP_LINEAR);
+    /// ```
+    pub fn effective_min_filter(&self) -> u32 {
+        match (self.generate_mipmaps, self.min_filter) {
+            (true, GL::NEAREST) => GL::NEAREST_MIPMAP_LINEAR,
+            (true, GL::LINEAR) => GL::LINEAR_MIPMAP_LINEAR,
+            (_, filter) => filter,
+        }
+    }
+
+    /// Set the filtering and wrapping parameters of the currently bound [`Texture`].
+    fn apply_parameters(&self, gl: &GL) {
+        gl.tex_parameteri(
+            GL::TEXTURE_2D,
+            GL::TEXTURE_MIN_FILTER,
+            self.effective_min_filter() as i32,
+        );
+        gl.tex_parameteri(
+            GL::TEXTURE_2D,
+            GL::TEXTURE_MAG_FILTER,
+            self.mag_filter as i32,
+        );
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, self.wrap_s as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, self.wrap_t as i32);
+    }
+}
+
+/// Set the alpha of all pixels matching the RGB components of a color key to 0.
+///
+/// The pixels are expected to be tightly packed RGBA [`u8`]s.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::texture::apply_color_key;
+/// use moon_engine::Color32;
+///
+/// let mut pixels = [255, 0, 255, 255, 0, 0, 0, 255];
+/// apply_color_key(&mut pixels, Color32::MAGENTA);
+///
+/// assert_eq!(pixels, [255, 0, 255, 0, 0, 0, 0, 255]);
+/// ```
+pub fn apply_color_key(pixels: &mut [u8], color_key: Color32) {
+    let key = Color8::from(color_key);
+    for pixel in pixels.chunks_exact_mut(4) {
+        if pixel[0] == key.r() && pixel[1] == key.g() && pixel[2] == key.b() {
+            pixel[3] = 0;
+        }
+    }
+}
+
+/// Number of texture units guaranteed to be available in WebGL2.
+pub const MAX_TEXTURE_UNITS: u32 = 16;
+
+/// An allocator that hands out texture units to sampler uniforms.
+///
+/// Each sampler is given a distinct unit, so that binding a [`Texture`] for one sampler does not clobber another.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::texture::TextureUnits;
+///
+/// let mut units = TextureUnits::new();
+///
+/// assert_eq!(units.allocate("uTex0"), Some(0));
+/// assert_eq!(units.allocate("uPalette"), Some(1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextureUnits {
+    units: BTreeMap<&'static str, u32>,
+    max_units: u32,
+}
+
+impl Default for TextureUnits {
+    fn default() -> Self {
+        Self {
+            units: BTreeMap::new(),
+            max_units: MAX_TEXTURE_UNITS,
+        }
+    }
+}
+
+impl TextureUnits {
+    /// Create a new [`TextureUnits`] allocator with [`MAX_TEXTURE_UNITS`] units.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a new [`TextureUnits`] allocator with a given number of units.
+    pub fn new_with_max_units(max_units: u32) -> Self {
+        Self {
+            max_units,
+            ..Default::default()
+        }
+    }
+
+    /// Allocate a texture unit for a sampler.
+    ///
+    /// Returns the unit already held by the sampler if there is one, or the lowest free unit. Returns [`None`] if all units are in use.
+    pub fn allocate(&mut self, sampler: &'static str) -> Option<u32> {
+        if let Some(unit) = self.units.get(sampler) {
+            return Some(*unit);
+        }
+        let unit =
+            (0..self.max_units).find(|unit| !self.units.values().any(|used| used == unit))?;
+        self.units.insert(sampler, unit);
+        Some(unit)
+    }
+
+    /// Get the texture unit held by a sampler, if any.
+    pub fn get(&self, sampler: &str) -> Option<u32> {
+        self.units.get(sampler).copied()
+    }
+
+    /// Free the texture unit held by a sampler, so it can be reused.
+    pub fn free(&mut self, sampler: &str) -> Option<u32> {
+        self.units.remove(sampler)
+    }
+
+    /// Iterate over the samplers and their texture units.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, u32)> + '_ {
+        self.units.iter().map(|(sampler, unit)| (*sampler, *unit))
+    }
+}
 
 /// A [`Texture`] stores an Image that can be used while rendering, or to store data.
 #[derive(Debug)]
@@ -53,17 +229,27 @@ impl Drop for Texture {
 }
 
 impl Texture {
+    /// Bind the [`Texture`] to a given texture unit, instead of its slot.
+    pub fn bind_to_unit(&self, gl: &GL, unit: u32) {
+        gl.active_texture(GL::TEXTURE0 + unit);
+        gl.bind_texture(GL::TEXTURE_2D, self.texture.as_ref());
+    }
+
     /// Create a new [`Texture`] using an [`HtmlImageElement`].
     pub fn new(gl: &GL, image: &HtmlImageElement) -> Self {
+        Self::new_with_options(gl, image, &TextureOptions::default())
+    }
+
+    /// Create a new [`Texture`] from an [`HtmlImageElement`] and the given [`TextureOptions`].
+    ///
+    /// The color key of the [`TextureOptions`] is ignored.
+    pub fn new_with_options(gl: &GL, image: &HtmlImageElement, options: &TextureOptions) -> Self {
         let (width, height) = (image.width(), image.height());
 
         let texture = gl.create_texture();
         gl.active_texture(GL::TEXTURE0);
         gl.bind_texture(GL::TEXTURE_2D, texture.as_ref());
-        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
-        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
-        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::REPEAT as i32);
-        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::REPEAT as i32);
+        options.apply_parameters(gl);
         // Flip the Y-axis so the image displays the right way up
         gl.pixel_storei(GL::UNPACK_FLIP_Y_WEBGL, 1);
         gl.tex_image_2d_with_u32_and_u32_and_html_image_element(
@@ -75,7 +261,9 @@ impl Texture {
             image,
         )
         .expect("Failed to load texture");
-        gl.generate_mipmap(GL::TEXTURE_2D);
+        if options.generate_mipmaps {
+            gl.generate_mipmap(GL::TEXTURE_2D);
+        }
 
         Self {
             width,
@@ -103,14 +291,29 @@ impl Texture {
 
     /// Create a new [`Texture`] using a slice of [`u8`]s.
     pub fn new_from_pixels(gl: &GL, width: u32, height: u32, pixels: &[u8]) -> Self {
+        Self::new_from_pixels_with_options(gl, width, height, pixels, &TextureOptions::default())
+    }
+
+    /// Create a new [`Texture`] using a slice of [`u8`]s and the given [`TextureOptions`].
+    pub fn new_from_pixels_with_options(
+        gl: &GL,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        options: &TextureOptions,
+    ) -> Self {
         assert!(pixels.len() == (width * height * 4) as usize);
+        let keyed_pixels = options.color_key.map(|color_key| {
+            let mut pixels = pixels.to_vec();
+            apply_color_key(&mut pixels, color_key);
+            pixels
+        });
+        let pixels = keyed_pixels.as_deref().unwrap_or(pixels);
+
         let texture = gl.create_texture();
         gl.active_texture(GL::TEXTURE0);
         gl.bind_texture(GL::TEXTURE_2D, texture.as_ref());
-        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
-        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
-        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::REPEAT as i32);
-        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::REPEAT as i32);
+        options.apply_parameters(gl);
         gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
             GL::TEXTURE_2D,
             0,
@@ -123,7 +326,61 @@ impl Texture {
             Some(pixels),
         )
         .expect("Failed to generate texture");
-        gl.generate_mipmap(GL::TEXTURE_2D);
+        if options.generate_mipmaps {
+            gl.generate_mipmap(GL::TEXTURE_2D);
+        }
+
+        Self {
+            width,
+            height,
+            texture,
+            ..Default::default()
+        }
+    }
+
+    /// Create a new, empty [`Texture`] with a given width and height, to be used as a render target.
+    pub fn new_empty(gl: &GL, width: u32, height: u32) -> Self {
+        Self::new_empty_with_format(gl, width, height, GL::RGBA8, GL::UNSIGNED_BYTE)
+    }
+
+    /// Create a new, empty [`Texture`] with a high dynamic range, to be used as a render target.
+    ///
+    /// Uses a half-float format if `EXT_color_buffer_float` is supported, or falls back to [`new_empty`](Texture::new_empty).
+    pub fn new_empty_hdr(gl: &GL, width: u32, height: u32) -> Self {
+        if let Ok(Some(_)) = gl.get_extension("EXT_color_buffer_float") {
+            Self::new_empty_with_format(gl, width, height, GL::RGBA16F, GL::HALF_FLOAT)
+        } else {
+            Self::new_empty(gl, width, height)
+        }
+    }
+
+    /// Create a new, empty [`Texture`] with a given internal format and data type.
+    fn new_empty_with_format(
+        gl: &GL,
+        width: u32,
+        height: u32,
+        internal_format: u32,
+        data_type: u32,
+    ) -> Self {
+        let texture = gl.create_texture();
+        gl.active_texture(GL::TEXTURE0);
+        gl.bind_texture(GL::TEXTURE_2D, texture.as_ref());
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER, GL::NEAREST as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_S, GL::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_WRAP_T, GL::CLAMP_TO_EDGE as i32);
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            GL::TEXTURE_2D,
+            0,
+            internal_format as i32,
+            width as i32,
+            height as i32,
+            0,
+            GL::RGBA,
+            data_type,
+            None,
+        )
+        .expect("Failed to generate texture");
 
         Self {
             width,
@@ -133,6 +390,11 @@ impl Texture {
         }
     }
 
+    /// Get the underlying [`WebGlTexture`], if any.
+    pub fn texture(&self) -> Option<&WebGlTexture> {
+        self.texture.as_ref()
+    }
+
     /// A colored [`Texture`].
     ///
     /// Create a single pixel sized [`Texture`] with the specified [`Color32`].
@@ -150,6 +412,37 @@ impl Texture {
         Self::checkerboard_colored(gl, Color32::WHITE, Color32::BLACK)
     }
 
+    /// Get a cell of the [`Texture`] divided into a grid, as a [`SubTexture`].
+    ///
+    /// Cells are a whole number of pixels, so if the size of the [`Texture`] does not divide evenly, the leftover pixels on the right and bottom are not part of any cell.
+    pub fn sub_texture(
+        self: &Rc<Self>,
+        columns: u32,
+        rows: u32,
+        column: u32,
+        row: u32,
+    ) -> SubTexture {
+        let cell_width = (self.width / columns.max(1)) as f32 / self.width as f32;
+        let cell_height = (self.height / rows.max(1)) as f32 / self.height as f32;
+        let uv = Color32(
+            column as f32 * cell_width,
+            (column + 1) as f32 * cell_width,
+            row as f32 * cell_height,
+            (row + 1) as f32 * cell_height,
+        );
+        SubTexture::new_with_coords(Rc::clone(self), uv)
+    }
+
+    /// Divide the [`Texture`] into a grid of equally sized [`SubTextures`](SubTexture), in row-major order.
+    ///
+    /// See [`sub_texture`](Texture::sub_texture) for how uneven sizes are handled.
+    pub fn grid(self: &Rc<Self>, columns: u32, rows: u32) -> Vec<Rc<SubTexture>> {
+        (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (column, row)))
+            .map(|(column, row)| Rc::new(self.sub_texture(columns, rows, column, row)))
+            .collect()
+    }
+
     /// A checkerboard [`Texture`] with two [`Color32`]s.
     pub fn checkerboard_colored(gl: &GL, color1: Color32, color2: Color32) -> Self {
         let size = 8;
@@ -206,6 +499,40 @@ impl SubTexture {
         }
     }
 
+    /// Get a region of the [`SubTexture`], as a new [`SubTexture`] sharing the same [`Texture`].
+    ///
+    /// The UV co-ordinates of the region are relative to the [`SubTexture`], in the same order as [`new_with_coords`](SubTexture::new_with_coords).
+    pub fn region(&self, uv: Color32) -> SubTexture {
+        let lerp = |min: f32, max: f32, t: f32| min + (max - min) * t;
+        Self {
+            texture: self.texture.clone(),
+            min: [
+                lerp(self.min[0], self.max[0], uv.x()),
+                lerp(self.min[1], self.max[1], uv.z()),
+            ],
+            max: [
+                lerp(self.min[0], self.max[0], uv.y()),
+                lerp(self.min[1], self.max[1], uv.w()),
+            ],
+        }
+    }
+
+    /// Get a copy of the [`SubTexture`] mirrored horizontally, by swapping its `U` co-ordinates.
+    ///
+    /// The copy shares the same [`Texture`].
+    pub fn flipped_x(&self) -> SubTexture {
+        Self {
+            texture: self.texture.clone(),
+            min: [self.max[0], self.min[1]],
+            max: [self.min[0], self.max[1]],
+        }
+    }
+
+    /// Get the [`Texture`] the [`SubTexture`] is a part of, if any.
+    pub fn texture(&self) -> Option<&Rc<Texture>> {
+        self.texture.as_ref()
+    }
+
     /// Get the UV co-ordinates as an array of four two-component [`f32`].
     pub fn get_uv_coords(&self) -> [[f32; 2]; 4] {
         let (min, max) = (self.min, self.max);
@@ -0,0 +1,132 @@
+//! Loading of maps exported by the [Tiled](https://www.mapeditor.org) editor.
+//!
+//! Only the JSON format (`.tmj`) is supported, with the first tile layer and first embedded tileset. Requires the `serde` feature.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use serde_json::Value;
+
+use crate::texture::{SubTexture, Texture};
+use crate::tilemap::{Tile, TileMap};
+use crate::Color32;
+
+/// Bits of a Tiled GID that store flipping and rotation flags.
+const GID_FLAG_MASK: u32 = 0xF000_0000;
+
+/// A [`TileMap`] loaded from Tiled, along with the size of its tiles.
+#[derive(Debug, Clone)]
+pub struct TiledMap {
+    /// The [`TileMap`] of the first tile layer.
+    pub tilemap: TileMap,
+    /// Width of a tile, in pixels.
+    pub tile_width: u32,
+    /// Height of a tile, in pixels.
+    pub tile_height: u32,
+}
+
+/// Get a required unsigned integer field from a JSON object.
+fn get_u32(value: &Value, key: &str) -> Result<u32, String> {
+    value
+        .get(key)
+        .and_then(Value::as_u64)
+        .and_then(|number| u32::try_from(number).ok())
+        .ok_or(format!("Missing or invalid \"{}\".", key))
+}
+
+/// Remove the flipping and rotation flags from a Tiled GID.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::tiled::mask_gid;
+///
+/// assert_eq!(mask_gid(0x8000_0003), 3);
+/// ```
+pub fn mask_gid(gid: u32) -> u32 {
+    gid & !GID_FLAG_MASK
+}
+
+/// Load a map exported from Tiled as JSON, using a texture atlas for the tileset image.
+///
+/// Each GID in the first tile layer is resolved against the first tileset into a [`SubTexture`] of the atlas. Flipping and rotation flags are ignored, and a GID of `0` is left empty.
+pub fn load_tiled(json: &str, atlas: Rc<Texture>) -> Result<TiledMap, String> {
+    let map: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    let width = get_u32(&map, "width")?;
+    if width == 0 {
+        return Err("Map width must be greater than 0.".to_string());
+    }
+    let height = get_u32(&map, "height")?;
+    let tile_width = get_u32(&map, "tilewidth")?;
+    let tile_height = get_u32(&map, "tileheight")?;
+
+    let layer = map
+        .get("layers")
+        .and_then(Value::as_array)
+        .and_then(|layers| {
+            layers
+                .iter()
+                .find(|layer| layer.get("type").and_then(Value::as_str) == Some("tilelayer"))
+        })
+        .ok_or("No tile layer found.")?;
+    let data = layer
+        .get("data")
+        .and_then(Value::as_array)
+        .ok_or("Tile layer has no \"data\" array.")?;
+    if data.len() as u64 > width as u64 * height as u64 {
+        return Err(format!(
+            "Tile layer has {} tiles, more than the {}x{} map.",
+            data.len(),
+            width,
+            height
+        ));
+    }
+
+    let tileset = map
+        .get("tilesets")
+        .and_then(Value::as_array)
+        .and_then(|tilesets| tilesets.first())
+        .ok_or("No tileset found.")?;
+    let first_gid = get_u32(tileset, "firstgid")?;
+    let columns = get_u32(tileset, "columns")?.max(1);
+    let image_width = get_u32(tileset, "imagewidth")? as f32;
+    let image_height = get_u32(tileset, "imageheight")? as f32;
+    let margin = tileset.get("margin").and_then(Value::as_u64).unwrap_or(0) as f32;
+    let spacing = tileset.get("spacing").and_then(Value::as_u64).unwrap_or(0) as f32;
+
+    let mut tilemap = TileMap::new(width, height);
+    let mut sprites: BTreeMap<u32, Rc<SubTexture>> = BTreeMap::new();
+    for (index, gid) in data.iter().enumerate() {
+        let gid = gid
+            .as_u64()
+            .and_then(|gid| u32::try_from(gid).ok())
+            .map(mask_gid)
+            .ok_or(format!("Invalid GID at index {}.", index))?;
+        if gid == 0 {
+            continue;
+        }
+        let local = gid
+            .checked_sub(first_gid)
+            .ok_or(format!("GID {} is not in the tileset.", gid))?;
+        let sprite = sprites.entry(gid).or_insert_with(|| {
+            let left = margin + (local % columns) as f32 * (tile_width as f32 + spacing);
+            let top = margin + (local / columns) as f32 * (tile_height as f32 + spacing);
+            let uv = Color32(
+                left / image_width,
+                (left + tile_width as f32) / image_width,
+                top / image_height,
+                (top + tile_height as f32) / image_height,
+            );
+            Rc::new(SubTexture::new_with_coords(Rc::clone(&atlas), uv))
+        });
+        let sprite = Rc::clone(sprite);
+        let (x, y) = (index as u32 % width, index as u32 / width);
+        tilemap.set_tile(x as i32, y as i32, Tile::new(gid, "Tiled", sprite));
+    }
+
+    Ok(TiledMap {
+        tilemap,
+        tile_width,
+        tile_height,
+    })
+}
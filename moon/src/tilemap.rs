@@ -0,0 +1,473 @@
+//! The [`TileMap`] and [`Tile`] structs.
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::collider::{Collide, SpatialGrid, AABB};
+use crate::renderer::{Quad, Renderer};
+use crate::texture::SubTexture;
+use crate::Vec2;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize, Serializer};
+
+/// A single cell of a [`TileMap`].
+///
+/// A [`Tile`] refers to a sprite in a [`SubTexture`], and can optionally have an [`AABB`] collider.
+#[derive(Debug, Clone)]
+pub struct Tile {
+    /// An ID that identifies the [`Tile`] in a palette.
+    pub id: u32,
+    /// A name to refer to the [`Tile`] with, and for debugging purposes.
+    pub name: &'static str,
+    /// The sprite of the [`Tile`].
+    pub sprite: Rc<SubTexture>,
+    /// An optional collider for the [`Tile`].
+    ///
+    /// The [`AABB`] is relative to the world origin of the [`Tile`], in world units.
+    pub collider: Option<AABB>,
+}
+
+impl Tile {
+    /// Create a new [`Tile`] with an ID, name and sprite, with no collider.
+    pub fn new(id: u32, name: &'static str, sprite: Rc<SubTexture>) -> Self {
+        Self {
+            id,
+            name,
+            sprite,
+            collider: None,
+        }
+    }
+
+    /// Create a new [`Tile`] with an ID, name, sprite and an [`AABB`] collider.
+    pub fn new_with_collider(
+        id: u32,
+        name: &'static str,
+        sprite: Rc<SubTexture>,
+        collider: AABB,
+    ) -> Self {
+        Self {
+            collider: Some(collider),
+            ..Self::new(id, name, sprite)
+        }
+    }
+}
+
+/// The serializable form of a [`TileMap`], with each [`Tile`] stored as its palette ID.
+///
+/// The [`Tiles`](Tile) are rebuilt from a palette when loading, using [`TileMap::from_data`].
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TileMapData {
+    /// Number of [`Tiles`](Tile) along the X axis.
+    pub width: u32,
+    /// Number of [`Tiles`](Tile) along the Y axis.
+    pub height: u32,
+    /// Rows of [`Tile`] IDs, with [`None`] for empty cells.
+    pub tiles: Vec<Vec<Option<u32>>>,
+}
+
+/// A grid of [`Tiles`](Tile).
+///
+/// The [`Tiles`](Tile) are stored sparsely, with a key of `x + y * width`.
+#[derive(Debug, Clone, Default)]
+pub struct TileMap {
+    /// Number of [`Tiles`](Tile) along the X axis.
+    pub width: u32,
+    /// Number of [`Tiles`](Tile) along the Y axis.
+    pub height: u32,
+    tiles: BTreeMap<u32, Tile>,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for TileMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_data().serialize(serializer)
+    }
+}
+
+impl TileMap {
+    /// Create a new, empty [`TileMap`] with a given width and height.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            tiles: BTreeMap::new(),
+        }
+    }
+
+    /// Create a new [`TileMap`] with a given width and height, and a [`BTreeMap`] of [`Tiles`](Tile).
+    pub fn new_with_tiles(width: u32, height: u32, tiles: BTreeMap<u32, Tile>) -> Self {
+        Self {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    /// Create a new [`TileMap`] from rows of comma-separated [`Tile`] IDs, looked up in a palette.
+    ///
+    /// The width is that of the longest row, and the height is the number of rows. Empty cells and `-1` are left empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use moon_engine::tilemap::TileMap;
+    ///
+    /// let map = TileMap::from_csv("-1,-1\n,", &BTreeMap::new()).unwrap();
+    /// assert_eq!((map.width, map.height), (2, 2));
+    /// ```
+    pub fn from_csv(data: &str, palette: &BTreeMap<u32, Tile>) -> Result<Self, String> {
+        let rows = data
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                line.split(',')
+                    .map(|cell| match cell.trim() {
+                        "" | "-1" => Ok(None),
+                        id => id
+                            .parse::<u32>()
+                            .map(Some)
+                            .map_err(|_| format!("Invalid tile ID \"{}\".", id)),
+                    })
+                    .collect::<Result<Vec<_>, String>>()
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Self::from_rows(rows, palette)
+    }
+
+    /// Create a new [`TileMap`] from a JSON array of rows of [`Tile`] IDs, looked up in a palette.
+    ///
+    /// The width is that of the longest row, and the height is the number of rows. `-1` and `null` are left empty.
+    ///
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn from_json(data: &str, palette: &BTreeMap<u32, Tile>) -> Result<Self, String> {
+        let json: serde_json::Value = serde_json::from_str(data).map_err(|err| err.to_string())?;
+        let rows = json
+            .as_array()
+            .ok_or("Expected an array of rows.")?
+            .iter()
+            .map(|row| {
+                row.as_array()
+                    .ok_or_else(|| "Expected a row to be an array.".to_string())?
+                    .iter()
+                    .map(|cell| match cell {
+                        serde_json::Value::Null => Ok(None),
+                        cell if cell.as_i64() == Some(-1) => Ok(None),
+                        cell => cell
+                            .as_u64()
+                            .and_then(|id| u32::try_from(id).ok())
+                            .map(Some)
+                            .ok_or_else(|| format!("Invalid tile ID {}.", cell)),
+                    })
+                    .collect::<Result<Vec<_>, String>>()
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Self::from_rows(rows, palette)
+    }
+
+    /// Create a new [`TileMap`] from rows of optional [`Tile`] IDs, looked up in a palette.
+    fn from_rows(
+        rows: Vec<Vec<Option<u32>>>,
+        palette: &BTreeMap<u32, Tile>,
+    ) -> Result<Self, String> {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0) as u32;
+        let mut map = Self::new(width, rows.len() as u32);
+        map.set_rows(rows, palette)?;
+        Ok(map)
+    }
+
+    /// Set the [`Tiles`](Tile) from rows of optional [`Tile`] IDs, looked up in a palette.
+    fn set_rows(
+        &mut self,
+        rows: Vec<Vec<Option<u32>>>,
+        palette: &BTreeMap<u32, Tile>,
+    ) -> Result<(), String> {
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, id) in row.into_iter().enumerate() {
+                if let Some(id) = id {
+                    let tile = palette
+                        .get(&id)
+                        .ok_or(format!("Tile ID {} is not in the palette.", id))?;
+                    self.set_tile(x as i32, y as i32, tile.clone());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the rows of [`Tile`] IDs of the [`TileMap`], with [`None`] for empty cells.
+    #[cfg(feature = "serde")]
+    fn rows(&self) -> Vec<Vec<Option<u32>>> {
+        (0..self.height as i32)
+            .map(|y| {
+                (0..self.width as i32)
+                    .map(|x| self.get_tile(x, y).map(|tile| tile.id))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Get the [`TileMap`] as a JSON array of rows of [`Tile`] IDs, with `null` for empty cells.
+    ///
+    /// This is the format read by [`from_json`](TileMap::from_json), using the same palette.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::tilemap::TileMap;
+    ///
+    /// let map = TileMap::new(2, 1);
+    /// assert_eq!(map.to_json(), "[[null,null]]");
+    /// ```
+    ///
+    /// Requires the `serde` feature.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::Value::from(
+            self.rows()
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(serde_json::Value::from)
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+        )
+        .to_string()
+    }
+
+    /// Get the serializable [`TileMapData`] of the [`TileMap`].
+    #[cfg(feature = "serde")]
+    pub fn to_data(&self) -> TileMapData {
+        TileMapData {
+            width: self.width,
+            height: self.height,
+            tiles: self.rows(),
+        }
+    }
+
+    /// Create a new [`TileMap`] from a [`TileMapData`], looking up each [`Tile`] ID in a palette.
+    ///
+    /// [`Tiles`](Tile) outside the width and height of the [`TileMapData`] are ignored.
+    #[cfg(feature = "serde")]
+    pub fn from_data(data: &TileMapData, palette: &BTreeMap<u32, Tile>) -> Result<Self, String> {
+        let mut map = Self::new(data.width, data.height);
+        map.set_rows(data.tiles.clone(), palette)?;
+        Ok(map)
+    }
+
+    /// Get the number of non-empty [`Tiles`](Tile) in the [`TileMap`].
+    pub fn tile_count(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Get the key of the [`Tile`] at the given tile co-ordinates, or [`None`] if they are outside the [`TileMap`].
+    fn index(&self, x: i32, y: i32) -> Option<u32> {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            None
+        } else {
+            Some(x as u32 + y as u32 * self.width)
+        }
+    }
+
+    /// Get the [`Tile`] at the given tile co-ordinates, or [`None`] if the cell is empty or outside the [`TileMap`].
+    pub fn get_tile(&self, x: i32, y: i32) -> Option<&Tile> {
+        self.index(x, y).and_then(|index| self.tiles.get(&index))
+    }
+
+    /// Set the [`Tile`] at the given tile co-ordinates.
+    ///
+    /// Co-ordinates outside the [`TileMap`] are ignored.
+    pub fn set_tile(&mut self, x: i32, y: i32, tile: Tile) {
+        if let Some(index) = self.index(x, y) {
+            self.tiles.insert(index, tile);
+        }
+    }
+
+    /// Remove the [`Tile`] at the given tile co-ordinates, returning it if the cell was not empty.
+    ///
+    /// Co-ordinates outside the [`TileMap`] are ignored.
+    pub fn remove_tile(&mut self, x: i32, y: i32) -> Option<Tile> {
+        self.index(x, y).and_then(|index| self.tiles.remove(&index))
+    }
+
+    /// Get the 4 [`Tiles`](Tile) next to the given tile co-ordinates, such as to pick an autotile variant.
+    ///
+    /// The neighbors are in clockwise order: up, right, down and left, with `Y` pointing down. Empty cells and cells outside the [`TileMap`] are [`None`].
+    pub fn neighbors(&self, x: i32, y: i32) -> [Option<&Tile>; 4] {
+        [
+            self.get_tile(x, y - 1),
+            self.get_tile(x + 1, y),
+            self.get_tile(x, y + 1),
+            self.get_tile(x - 1, y),
+        ]
+    }
+
+    /// Get the 8 [`Tiles`](Tile) around the given tile co-ordinates, including the diagonals.
+    ///
+    /// The neighbors are in clockwise order starting from up: up, up-right, right, down-right, down, down-left, left and up-left.
+    /// Empty cells and cells outside the [`TileMap`] are [`None`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::rc::Rc;
+    /// use moon_engine::texture::SubTexture;
+    /// use moon_engine::tilemap::{Tile, TileMap};
+    ///
+    /// let mut map = TileMap::new(3, 3);
+    /// map.set_tile(2, 0, Tile::new(1, "Grass", Rc::new(SubTexture::default())));
+    ///
+    /// // A bitmask with a bit set for each neighbor that is not empty
+    /// let mask = map
+    ///     .neighbors_8(1, 1)
+    ///     .iter()
+    ///     .enumerate()
+    ///     .filter(|(_, tile)| tile.is_some())
+    ///     .fold(0u8, |mask, (bit, _)| mask | 1 << bit);
+    ///
+    /// assert_eq!(mask, 0b10);
+    /// ```
+    pub fn neighbors_8(&self, x: i32, y: i32) -> [Option<&Tile>; 8] {
+        [
+            self.get_tile(x, y - 1),
+            self.get_tile(x + 1, y - 1),
+            self.get_tile(x + 1, y),
+            self.get_tile(x + 1, y + 1),
+            self.get_tile(x, y + 1),
+            self.get_tile(x - 1, y + 1),
+            self.get_tile(x - 1, y),
+            self.get_tile(x - 1, y - 1),
+        ]
+    }
+
+    /// Replace the contiguous region of [`Tiles`](Tile) with the same ID as the one at the given tile co-ordinates.
+    ///
+    /// Empty cells form regions of their own. The fill is 4-connected, and bounded by the size of the [`TileMap`].
+    /// Returns the number of cells that were filled.
+    pub fn flood_fill(&mut self, x: i32, y: i32, tile: Tile) -> usize {
+        if self.index(x, y).is_none() {
+            return 0;
+        }
+        let target = self.get_tile(x, y).map(|tile| tile.id);
+        if target == Some(tile.id) {
+            return 0;
+        }
+
+        let mut filled = 0;
+        let mut stack = vec![(x, y)];
+        while let Some((x, y)) = stack.pop() {
+            let index = match self.index(x, y) {
+                Some(index) => index,
+                None => continue,
+            };
+            if self.tiles.get(&index).map(|tile| tile.id) != target {
+                continue;
+            }
+            self.tiles.insert(index, tile.clone());
+            filled += 1;
+            stack.extend([(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]);
+        }
+        filled
+    }
+
+    /// Check if a world-space position is inside the collider of the [`Tile`] under it.
+    ///
+    /// Returns false for empty cells, positions outside the [`TileMap`], and [`Tiles`](Tile) without a collider.
+    pub fn point_collides(&self, world: Vec2, tile_size: f32) -> bool {
+        let (x, y) = Self::world_to_tile(world, tile_size);
+        self.index(x, y)
+            .and_then(|index| self.tiles.get(&index))
+            .and_then(|tile| tile.collider.as_ref())
+            .map(|collider| collider.collide_with(&(world - Self::tile_to_world(x, y, tile_size))))
+            .unwrap_or(false)
+    }
+
+    /// Get a [`Quad`] for each non-empty [`Tile`] in the [`TileMap`], using its sprite.
+    ///
+    /// Each [`Quad`] fills its cell, in key order.
+    pub fn quads(&self, tile_size: f32) -> Vec<Quad> {
+        let half = tile_size / 2.0;
+        self.tiles
+            .iter()
+            .map(|(index, tile)| {
+                let origin = Self::tile_to_world(
+                    (index % self.width) as i32,
+                    (index / self.width) as i32,
+                    tile_size,
+                );
+                Quad::new_from_position_and_size_and_sprite(
+                    origin.x + half,
+                    origin.y + half,
+                    tile_size,
+                    tile_size,
+                    &tile.sprite,
+                )
+            })
+            .collect()
+    }
+
+    /// Submit the [`Quads`](Quad) of the [`TileMap`] to the [`Renderer`]'s batch, breaking it whenever the sprite's [`Texture`](crate::texture::Texture) changes.
+    ///
+    /// Empty cells are skipped. [`begin_draw`](Renderer::begin_draw) should have been called before.
+    pub fn render(&self, renderer: &mut Renderer, tile_size: f32) {
+        for (quad, tile) in self.quads(tile_size).iter().zip(self.tiles.values()) {
+            renderer.add_sprite_quad(quad, &tile.sprite);
+        }
+    }
+
+    /// Build a [`SpatialGrid`] of the colliders of the [`Tiles`](Tile) in the [`TileMap`], in world space.
+    ///
+    /// Each entry uses the key of its [`Tile`], `x + y * width`, as its ID, and the grid cells match the tiles.
+    pub fn spatial_grid(&self, tile_size: f32) -> SpatialGrid {
+        let mut grid = SpatialGrid::new(tile_size);
+        for index in self.tiles.keys() {
+            let (x, y) = ((index % self.width) as i32, (index / self.width) as i32);
+            if let Some(collider) = self.world_collider(x, y, tile_size) {
+                grid.insert(*index, collider);
+            }
+        }
+        grid
+    }
+
+    /// Get the collider of the [`Tile`] at the given tile co-ordinates, in world space.
+    ///
+    /// Returns [`None`] for empty cells, co-ordinates outside the [`TileMap`], and [`Tiles`](Tile) without a collider.
+    pub fn world_collider(&self, x: i32, y: i32, tile_size: f32) -> Option<AABB> {
+        let collider = self.get_tile(x, y)?.collider?;
+        let origin = Self::tile_to_world(x, y, tile_size);
+        Some(AABB {
+            min: collider.min + origin,
+            max: collider.max + origin,
+        })
+    }
+
+    /// Convert a world-space position to tile co-ordinates.
+    ///
+    /// Both components are rounded down, so a position exactly on the boundary between two tiles belongs to the tile with the larger co-ordinate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use moon_engine::tilemap::TileMap;
+    /// use moon_engine::Vec2;
+    ///
+    /// assert_eq!(TileMap::world_to_tile(Vec2::new(1.5, -0.5), 1.0), (1, -1));
+    /// ```
+    pub fn world_to_tile(world: Vec2, tile_size: f32) -> (i32, i32) {
+        (
+            (world.x / tile_size).floor() as i32,
+            (world.y / tile_size).floor() as i32,
+        )
+    }
+
+    /// Convert tile co-ordinates to a world-space position.
+    ///
+    /// Returns the world origin of the tile, i.e. the corner with the lowest `X` and `Y` components.
+    pub fn tile_to_world(x: i32, y: i32, tile_size: f32) -> Vec2 {
+        Vec2::new(x as f32 * tile_size, y as f32 * tile_size)
+    }
+}
@@ -1,32 +1,57 @@
 //! The [`Transform`] ans [`Transform2D`] structs.
 
+use std::cell::Cell;
 use std::ops::Add;
 
 use crate::Mat4;
+use crate::Quat;
 use crate::Vec2;
 use crate::Vec3;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 /// A 3D representation of an entity's position, rotation and scale.
 ///
 /// A [`Transform`] contains [`Vec3`]s for Position, ROtation and Scale.
+///
+/// The matrix is only recalculated when it is requested after a change.
+///
+/// With the `serde` feature, a [`Transform`] can be serialized. The matrix is skipped, and recalculated after loading.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Transform {
-    matrix: Mat4,
-    /// Position component of the [`Transform`].
-    pub position: Vec3,
-    /// Rotation component of the [`Transform`].
-    pub rotation: Vec3,
-    /// Scale component of the [`Transform`].
-    pub scale: Vec3,
+    #[cfg_attr(feature = "serde", serde(skip, default = "identity_matrix"))]
+    matrix: Cell<Mat4>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "dirty_flag"))]
+    dirty: Cell<bool>,
+    position: Vec3,
+    rotation: Vec3,
+    scale: Vec3,
+    orientation: Option<Quat>,
+}
+
+/// The matrix of a deserialized [`Transform`], before it is recalculated.
+#[cfg(feature = "serde")]
+fn identity_matrix() -> Cell<Mat4> {
+    Cell::new(Mat4::identity())
+}
+
+/// A deserialized [`Transform`] needs its matrix recalculated.
+#[cfg(feature = "serde")]
+fn dirty_flag() -> Cell<bool> {
+    Cell::new(true)
 }
 
 impl Default for Transform {
     fn default() -> Self {
         Self {
-            matrix: Mat4::identity(),
+            matrix: Cell::new(Mat4::identity()),
+            dirty: Cell::new(false),
             position: Vec3::zeros(),
             rotation: Vec3::zeros(),
             scale: Vec3::from_element(1.0),
+            orientation: None,
         }
     }
 }
@@ -41,44 +66,182 @@ impl Transform {
     pub fn new_with_position(position: Vec3) -> Self {
         Self {
             position,
-            matrix: Mat4::new_translation(&position),
+            matrix: Cell::new(Mat4::new_translation(&position)),
             ..Self::default()
         }
     }
 
-    /// Get a clone of the [`Mat4`].
+    /// Get a clone of the [`Mat4`], recalculating it if the [`Transform`] has changed.
     pub fn matrix(&self) -> Mat4 {
-        self.matrix
+        if self.dirty.get() {
+            self.recalculate_matrix();
+        }
+        self.matrix.get()
+    }
+
+    /// Get the world [`Mat4`] of the [`Transform`], given the world matrix of its parent.
+    pub fn world_matrix(&self, parent: &Mat4) -> Mat4 {
+        parent * self.matrix()
     }
 
     /// Get the [`Mat4`] representing the transform as a slice of [`f32`] to use with WebGL.
     pub fn matrix_slice(&mut self) -> &[f32] {
-        self.matrix.as_slice()
+        if self.dirty.get() {
+            self.recalculate_matrix();
+        }
+        self.matrix.get_mut().as_slice()
     }
 
     /// Recalculate the matrix of the [`Transform`].
-    fn recalculate_matrix(&mut self) {
-        self.matrix = Mat4::new_translation(&self.position)
-            * Mat4::new_rotation(self.rotation)
-            * Mat4::new_nonuniform_scaling(&self.scale);
+    ///
+    /// The quaternion rotation is used if set, otherwise the Euler angles are used.
+    fn recalculate_matrix(&self) {
+        self.matrix.set(
+            Mat4::new_translation(&self.position)
+                * self.rotation_quat().to_homogeneous()
+                * Mat4::new_nonuniform_scaling(&self.scale),
+        );
+        self.dirty.set(false);
+    }
+
+    /// Mark the matrix of the [`Transform`] to be recalculated when it is next requested.
+    pub fn mark_dirty(&mut self) {
+        self.dirty.set(true);
+    }
+
+    /// Get the position of the [`Transform`].
+    pub fn position(&self) -> Vec3 {
+        self.position
     }
 
-    /// Set the [`Transform`]'s position and calculate its matrix.
+    /// Get a mutable reference to the position of the [`Transform`], and mark it dirty.
+    pub fn position_mut(&mut self) -> &mut Vec3 {
+        self.mark_dirty();
+        &mut self.position
+    }
+
+    /// Get the rotation of the [`Transform`], as Euler angles in radians about the `X`, `Y` and `Z` axes.
+    pub fn rotation(&self) -> Vec3 {
+        self.rotation
+    }
+
+    /// Get a mutable reference to the Euler angles of the [`Transform`], and mark it dirty.
+    ///
+    /// This discards any quaternion rotation, so the Euler angles are used from then on.
+    pub fn rotation_mut(&mut self) -> &mut Vec3 {
+        self.orientation = None;
+        self.mark_dirty();
+        &mut self.rotation
+    }
+
+    /// Get the scale of the [`Transform`].
+    pub fn scale(&self) -> Vec3 {
+        self.scale
+    }
+
+    /// Get a mutable reference to the scale of the [`Transform`], and mark it dirty.
+    pub fn scale_mut(&mut self) -> &mut Vec3 {
+        self.mark_dirty();
+        &mut self.scale
+    }
+
+    /// Set the [`Transform`]'s position.
     pub fn set_position(&mut self, position: Vec3) {
         self.position = position;
-        self.recalculate_matrix();
+        self.mark_dirty();
     }
 
-    /// Set the [`Transform`]'s rotation and calculate its matrix.
+    /// Translate the [`Transform`] by a delta.
+    pub fn translate(&mut self, delta: Vec3) {
+        *self.position_mut() += delta;
+    }
+
+    /// Rotate the [`Transform`] by a delta of Euler angles in radians.
+    ///
+    /// This accumulates into the Euler angles, discarding any quaternion rotation.
+    pub fn rotate_euler(&mut self, delta: Vec3) {
+        *self.rotation_mut() += delta;
+    }
+
+    /// Set the [`Transform`]'s rotation about the `Z` axis.
     pub fn set_rotation(&mut self, rotation: f32) {
-        self.rotation = Vec3::z() * rotation;
-        self.recalculate_matrix();
+        self.set_rotation_euler(0.0, 0.0, rotation);
+    }
+
+    /// Set the [`Transform`]'s rotation using Euler angles in radians.
+    ///
+    /// The rotations are applied about the `X`, then `Y`, and then `Z` axes.
+    pub fn set_rotation_euler(&mut self, x: f32, y: f32, z: f32) {
+        self.set_rotation_quat(Quat::from_euler_angles(x, y, z));
+    }
+
+    /// Set the [`Transform`]'s rotation using a [`Quat`].
+    ///
+    /// The Euler angles of the [`Transform`] are updated to match.
+    pub fn set_rotation_quat(&mut self, rotation: Quat) {
+        let (x, y, z) = rotation.euler_angles();
+        self.rotation = Vec3::new(x, y, z);
+        self.orientation = Some(rotation);
+        self.mark_dirty();
+    }
+
+    /// Rotate the [`Transform`] by an angle in radians about an axis.
+    ///
+    /// The rotation is applied on top of the current rotation, in world space. A zero axis is ignored.
+    pub fn rotate(&mut self, axis: Vec3, angle: f32) {
+        if let Some(axis) = nalgebra::Unit::try_new(axis, f32::EPSILON) {
+            self.set_rotation_quat(Quat::from_axis_angle(&axis, angle) * self.rotation_quat());
+        }
+    }
+
+    /// Rotate the [`Transform`] so that its forward axis points at a target.
+    ///
+    /// The forward axis is `-Z`, matching the [`Camera`](crate::Camera). The rotation is left unchanged if the target is at the position of the [`Transform`], or directly along `up`.
+    pub fn look_at(&mut self, target: Vec3, up: Vec3) {
+        let direction = target - self.position;
+        if direction.norm_squared() <= f32::EPSILON
+            || direction.cross(&up).norm_squared() <= f32::EPSILON
+        {
+            return;
+        }
+        self.set_rotation_quat(Quat::face_towards(&-direction, &up));
     }
 
-    /// Set the [`Transform`]'s scale and calculate its matrix.
+    /// Get the forward axis of the [`Transform`] in world space, which is its rotated `-Z` axis.
+    ///
+    /// The basis is right-handed, like OpenGL: `X` is right, `Y` is up, and the forward axis points along `-Z`. Scale is not applied.
+    pub fn forward(&self) -> Vec3 {
+        self.rotation_quat() * -Vec3::z()
+    }
+
+    /// Get the right axis of the [`Transform`] in world space, which is its rotated `X` axis.
+    pub fn right(&self) -> Vec3 {
+        self.rotation_quat() * Vec3::x()
+    }
+
+    /// Get the up axis of the [`Transform`] in world space, which is its rotated `Y` axis.
+    pub fn up(&self) -> Vec3 {
+        self.rotation_quat() * Vec3::y()
+    }
+
+    /// Get the rotation of the [`Transform`] as a [`Quat`].
+    pub fn rotation_quat(&self) -> Quat {
+        self.orientation.unwrap_or_else(|| {
+            Quat::from_euler_angles(self.rotation.x, self.rotation.y, self.rotation.z)
+        })
+    }
+
+    /// Spherically interpolate between the rotations of two [`Transforms`](Transform).
+    ///
+    /// Returns the rotation of `self` when `t` is `0.0`, and of `other` when `t` is `1.0`.
+    pub fn slerp(&self, other: &Transform, t: f32) -> Quat {
+        self.rotation_quat().slerp(&other.rotation_quat(), t)
+    }
+
+    /// Set the [`Transform`]'s scale.
     pub fn set_scale(&mut self, scale: Vec3) {
         self.scale = scale;
-        self.recalculate_matrix();
+        self.mark_dirty();
     }
 
     /// Get the position as a slice.
@@ -97,10 +260,75 @@ impl Transform {
     }
 }
 
+/// A hierarchy of [`Transforms`](Transform), where each may have a parent.
+///
+/// A parent is always added before its children, so world matrices can be calculated in a single pass.
+#[derive(Debug, Default, Clone)]
+pub struct Scene {
+    transforms: Vec<Transform>,
+    parents: Vec<Option<usize>>,
+}
+
+impl Scene {
+    /// Create a new, empty [`Scene`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a [`Transform`] to the [`Scene`], with an optional parent, and return its index.
+    ///
+    /// # Panics
+    /// Panics if the parent index does not exist in the [`Scene`].
+    pub fn add(&mut self, transform: Transform, parent: Option<usize>) -> usize {
+        if let Some(parent) = parent {
+            assert!(parent < self.transforms.len(), "Parent does not exist!");
+        }
+        self.transforms.push(transform);
+        self.parents.push(parent);
+        self.transforms.len() - 1
+    }
+
+    /// Get the number of [`Transforms`](Transform) in the [`Scene`].
+    pub fn len(&self) -> usize {
+        self.transforms.len()
+    }
+
+    /// Check if the [`Scene`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// Get a reference to the [`Transform`] at an index.
+    pub fn get(&self, index: usize) -> Option<&Transform> {
+        self.transforms.get(index)
+    }
+
+    /// Get a mutable reference to the [`Transform`] at an index.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Transform> {
+        self.transforms.get_mut(index)
+    }
+
+    /// Get the parent index of the [`Transform`] at an index.
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        self.parents.get(index).copied().flatten()
+    }
+
+    /// Calculate the world matrices of all the [`Transforms`](Transform) in the [`Scene`], in order of their indices.
+    pub fn world_matrices(&self) -> Vec<Mat4> {
+        let mut matrices: Vec<Mat4> = Vec::with_capacity(self.transforms.len());
+        for (transform, parent) in self.transforms.iter().zip(&self.parents) {
+            let parent = parent.map_or_else(Mat4::identity, |parent| matrices[parent]);
+            matrices.push(transform.world_matrix(&parent));
+        }
+        matrices
+    }
+}
+
 /// A 2D counterpart for the [`Transform`].
 ///
 /// A [`Transform2D`] contains Position and Scale [`Vec2`]s and a float for rotation.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Transform2D {
     /// Position of the [`Transform2D`].
     pub position: Vec2,
@@ -155,8 +383,24 @@ impl Transform2D {
         self.position.y += delta_y;
     }
 
+    /// Linearly interpolate the position, rotation and scale between the [`Transform2D`] and another.
+    ///
+    /// The factor is clamped between 0 and 1.
+    pub fn lerp(&self, other: &Transform2D, factor: f32) -> Self {
+        let factor = factor.clamp(0.0, 1.0);
+        Self {
+            position: self.position.lerp(&other.position, factor),
+            rotation: self.rotation + (other.rotation - self.rotation) * factor,
+            scale: self.scale.lerp(&other.scale, factor),
+        }
+    }
+
     /// Get a [`Mat4`] of the [`Transform2D`].
+    ///
+    /// The matrix scales, then rotates about the `Z` axis, and then translates.
     pub fn matrix(&self) -> Mat4 {
         Mat4::new_translation(&Vec3::new(self.position.x, self.position.y, 0.0))
+            * Mat4::new_rotation(Vec3::z() * self.rotation)
+            * Mat4::new_nonuniform_scaling(&Vec3::new(self.scale.x, self.scale.y, 1.0))
     }
 }
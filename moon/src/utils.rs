@@ -1,5 +1,16 @@
 //! Utilities that make working with Rust and WASM much easier.
 
+use std::collections::VecDeque;
+
+/// Default number of frames averaged by a [`DeltaSmoother`].
+pub const DEFAULT_SMOOTHING_FRAMES: usize = 8;
+/// Default number of frames averaged by a [`FrameTimer`].
+pub const DEFAULT_TIMER_FRAMES: usize = 60;
+/// Default duration of a step of a [`FixedTimestep`], in seconds.
+pub const DEFAULT_TIMESTEP: f32 = 1.0 / 60.0;
+/// Maximum number of steps a [`FixedTimestep`] runs for a single frame.
+pub const MAX_STEPS_PER_FRAME: u32 = 8;
+
 /// When the `console_error_panic_hook` feature is enabled, we can call the
 /// `set_panic_hook` function at least once during initialization, and then
 /// we will get better error messages if our code ever panics.
@@ -10,3 +21,223 @@ pub fn set_panic_hook() {
     #[cfg(feature = "console_error_panic_hook")]
     console_error_panic_hook::set_once();
 }
+
+/// Smooths delta time by averaging the last few frame times.
+///
+/// The shortest and longest frame times are discarded as outliers before averaging, once enough frames have been recorded.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::utils::DeltaSmoother;
+///
+/// let mut smoother = DeltaSmoother::new();
+///
+/// assert_eq!(smoother.smooth(0.016), 0.016);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeltaSmoother {
+    samples: VecDeque<f32>,
+    frames: usize,
+}
+
+impl Default for DeltaSmoother {
+    fn default() -> Self {
+        Self::new_with_frames(DEFAULT_SMOOTHING_FRAMES)
+    }
+}
+
+impl DeltaSmoother {
+    /// Create a new [`DeltaSmoother`] that averages [`DEFAULT_SMOOTHING_FRAMES`] frames.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a new [`DeltaSmoother`] that averages a given number of frames.
+    pub fn new_with_frames(frames: usize) -> Self {
+        let frames = frames.max(1);
+        Self {
+            samples: VecDeque::with_capacity(frames),
+            frames,
+        }
+    }
+
+    /// Record a new delta time, and get the smoothed delta time.
+    pub fn smooth(&mut self, delta_time: f32) -> f32 {
+        if self.samples.len() == self.frames {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(delta_time);
+
+        let sum: f32 = self.samples.iter().sum();
+        let count = self.samples.len();
+        if count < 3 {
+            return sum / count as f32;
+        }
+        let min = self.samples.iter().copied().fold(f32::INFINITY, f32::min);
+        let max = self
+            .samples
+            .iter()
+            .copied()
+            .fold(f32::NEG_INFINITY, f32::max);
+        (sum - min - max) / (count - 2) as f32
+    }
+
+    /// Discard all recorded frame times.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
+/// Accumulates frame times, to run updates in steps of a fixed duration.
+///
+/// Running the simulation with a fixed delta time makes it behave the same at any frame rate.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::utils::FixedTimestep;
+///
+/// let mut timestep = FixedTimestep::new_with_step(0.01);
+///
+/// assert_eq!(timestep.advance(0.025), 2);
+/// assert!((timestep.alpha() - 0.5).abs() < 1e-4);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTimestep {
+    step: f32,
+    accumulator: f32,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new_with_step(DEFAULT_TIMESTEP)
+    }
+}
+
+impl FixedTimestep {
+    /// Create a new [`FixedTimestep`] with a step of [`DEFAULT_TIMESTEP`].
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a new [`FixedTimestep`] with a given step, in seconds.
+    pub fn new_with_step(step: f32) -> Self {
+        Self {
+            step: step.max(f32::EPSILON),
+            accumulator: 0.0,
+        }
+    }
+
+    /// Get the duration of a step, in seconds.
+    pub fn step(&self) -> f32 {
+        self.step
+    }
+
+    /// Set the duration of a step, in seconds.
+    pub fn set_step(&mut self, step: f32) {
+        self.step = step.max(f32::EPSILON);
+    }
+
+    /// Add the time of a frame, and get how many steps to run.
+    ///
+    /// At most [`MAX_STEPS_PER_FRAME`] steps are run, and any time left over beyond that is dropped so a slow frame cannot snowball.
+    pub fn advance(&mut self, delta_time: f32) -> u32 {
+        self.accumulator += delta_time.max(0.0);
+        let steps = (self.accumulator / self.step) as u32;
+        if steps > MAX_STEPS_PER_FRAME {
+            self.accumulator = 0.0;
+            return MAX_STEPS_PER_FRAME;
+        }
+        self.accumulator -= steps as f32 * self.step;
+        steps
+    }
+
+    /// Get how far the time left over is into the next step, from `0.0` to `1.0`.
+    ///
+    /// This can be used to interpolate between the last two simulated states when drawing.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+
+    /// Discard any accumulated time.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
+
+/// Records recent frame times, to report a smoothed frame rate.
+///
+/// # Examples
+///
+/// ```
+/// use moon_engine::utils::FrameTimer;
+///
+/// let mut timer = FrameTimer::new();
+/// timer.record(0.02);
+///
+/// assert_eq!(timer.fps(), 50.0);
+/// assert_eq!(timer.average_frame_ms(), 20.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FrameTimer {
+    samples: VecDeque<f32>,
+    frames: usize,
+}
+
+impl Default for FrameTimer {
+    fn default() -> Self {
+        Self::new_with_frames(DEFAULT_TIMER_FRAMES)
+    }
+}
+
+impl FrameTimer {
+    /// Create a new [`FrameTimer`] that averages [`DEFAULT_TIMER_FRAMES`] frames.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Create a new [`FrameTimer`] that averages a given number of frames.
+    pub fn new_with_frames(frames: usize) -> Self {
+        let frames = frames.max(1);
+        Self {
+            samples: VecDeque::with_capacity(frames),
+            frames,
+        }
+    }
+
+    /// Record the delta time of a frame, in seconds.
+    pub fn record(&mut self, delta_time: f32) {
+        if self.samples.len() == self.frames {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(delta_time);
+    }
+
+    /// Get the average frame time over the recorded frames, in milliseconds.
+    ///
+    /// Returns `0.0` if no frames have been recorded.
+    pub fn average_frame_ms(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32 * 1000.0
+    }
+
+    /// Get the average frames per second over the recorded frames.
+    ///
+    /// Returns `0.0` if no frames have been recorded.
+    pub fn fps(&self) -> f32 {
+        let average_ms = self.average_frame_ms();
+        if average_ms > 0.0 {
+            1000.0 / average_ms
+        } else {
+            0.0
+        }
+    }
+
+    /// Discard all recorded frame times.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
@@ -8,6 +8,7 @@ pub type Canvas = web_sys::HtmlCanvasElement;
 
 #[wasm_bindgen]
 extern "C" {
+    /// Log a message to the browser console.
     #[wasm_bindgen(js_namespace=console)]
     pub fn log(s: &str);
 }
@@ -15,7 +16,7 @@ extern "C" {
 /// A macro that can be used to write output to the browser
 #[macro_export]
 macro_rules! console_log {
-    ($($t:tt)*) => (crate::web::log(&format_args!($($t)*).to_string()))
+    ($($t:tt)*) => ($crate::web::log(&format_args!($($t)*).to_string()))
 }
 
 /// Get the time in seconds using [`Performance`](web_sys::Performance)
@@ -23,7 +24,7 @@ macro_rules! console_log {
 /// # Examples
 ///
 /// ```no_run
-/// # use moon::web::now_sec;
+/// # use moon_engine::web::now_sec;
 /// let time = now_sec();
 /// println!("The current time is: {}", time);
 /// ```
@@ -31,6 +32,34 @@ pub fn now_sec() -> f64 {
     web_sys::window().unwrap().performance().unwrap().now() / 1000.0
 }
 
+/// Get the ratio of physical pixels to CSS pixels of the display.
+///
+/// Returns `1.0` outside of a browser window.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use moon_engine::web::device_pixel_ratio;
+/// let ratio = device_pixel_ratio();
+/// println!("Each CSS pixel is {} physical pixels wide", ratio);
+/// ```
+pub fn device_pixel_ratio() -> f64 {
+    web_sys::window().map_or(1.0, |window| window.device_pixel_ratio())
+}
+
+/// Listen for the `webglcontextlost` event of a canvas, and prevent its default behavior so the context can be restored.
+///
+/// Call [`Application::on_context_restored`](crate::Application::on_context_restored) from a `webglcontextrestored` listener to recreate the resources.
+pub fn handle_context_loss(canvas: &Canvas) -> Result<(), JsValue> {
+    let closure = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        event.prevent_default();
+    }) as Box<dyn FnMut(_)>);
+    canvas
+        .add_event_listener_with_callback("webglcontextlost", closure.as_ref().unchecked_ref())?;
+    closure.forget();
+    Ok(())
+}
+
 /// Initialize document-level callbacks
 pub fn setup_document_events() -> Result<(), JsValue> {
     let document = web_sys::window().unwrap().document().unwrap();
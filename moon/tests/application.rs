@@ -0,0 +1,101 @@
+use moon_engine::game::Player;
+use moon_engine::input::Key;
+use moon_engine::particle::{ParticleProps, ParticleSystem};
+use moon_engine::{Application, Vec2};
+
+fn headless_with_smoke() -> Application {
+    let mut app = Application::new_headless();
+    let smoke = ParticleSystem::new_from_emission_and_position(ParticleProps::smoke(), 5.0, 0.0);
+    app.scene_mut().add_component("SMOKE", Box::new(smoke));
+    app.scene_mut().init_components();
+    app
+}
+
+fn smoke_position(app: &Application) -> Vec2 {
+    app.scene()
+        .get_component::<ParticleSystem>("SMOKE")
+        .unwrap()
+        .transform
+        .position
+}
+
+#[test]
+fn headless_step_moves_smoke_under_held_input() {
+    let mut app = headless_with_smoke();
+    assert!(app.renderer().is_none());
+
+    app.input_code("KeyD", true);
+    for _ in 0..10 {
+        app.step(0.1);
+    }
+    let position = smoke_position(&app);
+    assert!((position.x - 6.0).abs() < 1e-4);
+    assert_eq!(position.y, 0.0);
+}
+
+#[test]
+fn headless_render_steps_without_drawing() {
+    let mut app = headless_with_smoke();
+    app.set_fixed_timestep(0.1);
+    app.input_code("KeyD", true);
+    app.render_with_delta(0.35);
+
+    // Three whole steps fit in the frame
+    let position = smoke_position(&app);
+    assert!((position.x - 5.3).abs() < 1e-4);
+    assert!(!app.is_context_lost());
+    assert_eq!(app.mouse_world_position(), None);
+    assert!(app.input_manager().get_key_state(Key::D));
+}
+
+#[test]
+fn input_takes_key_characters() {
+    let mut app = Application::new_headless();
+    app.input(b'w', true);
+    app.input(b'1', true);
+    assert!(app.input_manager().get_key_state(Key::W));
+    assert!(app.input_manager().get_key_state(Key::Num1));
+
+    app.input(b'W', false);
+    assert!(!app.input_manager().get_key_state(Key::W));
+}
+
+#[test]
+fn init_spawns_player_driven_by_step() {
+    let mut app = Application::new_headless();
+    app.init();
+
+    // Fall onto the floor
+    for _ in 0..20 {
+        app.step(0.1);
+    }
+    let player = app.scene().get_component::<Player>("PLAYER").unwrap();
+    assert!(player.is_grounded);
+    let start = player.position;
+
+    app.input_code("KeyD", true);
+    app.step(0.1);
+    let player = app.scene().get_component::<Player>("PLAYER").unwrap();
+    assert!(player.position.x > start.x);
+    assert!(player.is_grounded);
+
+    app.input_code("Space", true);
+    app.step(0.1);
+    let player = app.scene().get_component::<Player>("PLAYER").unwrap();
+    assert!(player.is_jumping);
+    assert!(player.position.y < start.y);
+}
+
+#[test]
+fn presses_while_paused_do_not_fire_after_resume() {
+    let mut app = Application::new_headless();
+    app.set_fixed_timestep(0.1);
+    app.pause();
+    app.input_code("Space", true);
+    assert!(app.input_manager().get_key_pressed(Key::Space));
+    app.render_with_delta(0.05);
+
+    app.resume();
+    assert!(!app.input_manager().get_key_pressed(Key::Space));
+    assert!(app.input_manager().get_key_state(Key::Space));
+}
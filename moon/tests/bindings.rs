@@ -0,0 +1,67 @@
+use moon_engine::bindings::*;
+use moon_engine::input::{InputManager, Key};
+use moon_engine::Vec2;
+
+#[test]
+fn default_bindings_match_wasd() {
+    let bindings = Bindings::default();
+    let mut input = InputManager::new();
+    input.key_down(Key::D);
+    input.key_down(Key::W);
+    assert_eq!(bindings.movement_axis(&input), input.movement_axis());
+    assert_eq!(bindings.get_key(Action::Jump), Some(Key::Space))
+}
+
+#[test]
+fn rebound_action() {
+    let mut bindings = Bindings::default();
+    bindings.bind(Action::MoveRight, Key::ArrowRight);
+    let mut input = InputManager::new();
+    input.key_down(Key::D);
+    assert!(!bindings.is_action_active(&input, Action::MoveRight));
+    input.key_down(Key::ArrowRight);
+    assert_eq!(bindings.movement_axis(&input), Vec2::new(1.0, 0.0))
+}
+
+#[test]
+fn unbound_action_inactive() {
+    let mut bindings = Bindings::new();
+    let mut input = InputManager::new();
+    input.key_down(Key::Space);
+    assert!(!bindings.is_action_active(&input, Action::Jump));
+    bindings.bind(Action::Jump, Key::Space);
+    bindings.unbind(Action::Jump);
+    assert!(!bindings.is_action_active(&input, Action::Jump))
+}
+
+#[test]
+fn gamepad_button_activates_action() {
+    use moon_engine::input::GamepadState;
+
+    let mut bindings = Bindings::default();
+    let mut input = InputManager::new();
+    input.set_gamepad(Some(GamepadState::new(vec![true], Vec::new())));
+    assert!(bindings.is_action_active(&input, Action::Jump));
+    assert!(!bindings.is_action_active(&input, Action::MoveLeft));
+
+    bindings.unbind_button(Action::Jump);
+    assert!(!bindings.is_action_active(&input, Action::Jump));
+    bindings.bind_button(Action::MoveLeft, 0);
+    assert_eq!(bindings.movement_axis(&input), Vec2::new(-1.0, 0.0))
+}
+
+#[test]
+fn left_stick_moves() {
+    use moon_engine::input::GamepadState;
+
+    let bindings = Bindings::default();
+    let mut input = InputManager::new();
+    // Fully right and pushed up, past the deadzone
+    input.set_gamepad(Some(GamepadState::new(Vec::new(), vec![1.0, -1.0])));
+    let axis = bindings.movement_axis(&input);
+    assert!((axis - Vec2::new(1.0, 1.0).normalize()).norm() < 1e-6);
+
+    // A stick inside the deadzone does not move
+    input.set_gamepad(Some(GamepadState::new(Vec::new(), vec![0.1, 0.0])));
+    assert_eq!(bindings.movement_axis(&input), Vec2::zeros())
+}
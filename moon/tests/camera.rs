@@ -0,0 +1,348 @@
+use moon_engine::camera::*;
+use moon_engine::collider::AABB;
+use moon_engine::input::{InputManager, MouseButton};
+use moon_engine::{Vec2, Vec3};
+
+#[test]
+fn view_matrix_inverts_transform() {
+    use moon_engine::{Vec3, Vec4};
+
+    let mut camera = Camera::new();
+    camera.transform.set_position(Vec3::new(3.0, -2.0, 0.0));
+    let point = camera.view_matrix() * Vec4::new(3.0, -2.0, 0.0, 1.0);
+    assert_eq!(point, Vec4::new(0.0, 0.0, 0.0, 1.0))
+}
+
+#[test]
+fn view_projection_applies_view_first() {
+    use moon_engine::{Vec3, Vec4};
+
+    let mut camera = Camera::new();
+    camera.transform.set_position(Vec3::new(3.0, -2.0, 0.0));
+    let center = camera.view_projection_matrix() * Vec4::new(3.0, -2.0, 0.0, 1.0);
+    assert_eq!((center.x, center.y), (0.0, 0.0));
+    let edge = camera.view_projection_matrix() * Vec4::new(3.0 + FIXED_WIDTH / 2.0, -2.0, 0.0, 1.0);
+    assert_eq!(edge.x, 1.0)
+}
+
+/// Create an [`InputManager`] with the middle button held or not, and a mouse delta in pixels.
+fn middle_drag(is_dragging: bool, delta: Vec2) -> InputManager {
+    let mut input = InputManager::new();
+    if is_dragging {
+        input.mouse_button_down(MouseButton::Middle);
+    }
+    input.set_mouse_position(0.0, 0.0);
+    input.set_mouse_position(delta.x, delta.y);
+    input
+}
+
+#[test]
+fn drag_pan_moves_camera() {
+    let mut camera = Camera::new();
+    camera.drag_pan(&middle_drag(true, Vec2::new(10.0, 0.0)));
+    assert_eq!(camera.transform.position(), Vec3::new(-10.0, 0.0, 0.0))
+}
+
+#[test]
+fn drag_pan_inactive() {
+    let mut camera = Camera::new();
+    camera.drag_pan(&middle_drag(false, Vec2::new(10.0, 0.0)));
+    assert_eq!(camera.transform.position(), Vec3::zeros())
+}
+
+#[test]
+fn drag_pan_within_bounds() {
+    let mut camera = Camera::new();
+    camera.set_bounds(Some(AABB::new_size(4.0, 4.0)));
+    camera.drag_pan(&middle_drag(true, Vec2::new(10.0, -1.0)));
+    assert_eq!(camera.transform.position(), Vec3::new(-2.0, 1.0, 0.0))
+}
+
+#[test]
+fn set_view_overrides_view_matrix() {
+    use moon_engine::Mat4;
+
+    let mut camera = Camera::new();
+    let view = Mat4::new_translation(&Vec3::new(-3.0, 2.0, 0.0)) * Mat4::new_scaling(2.0);
+    camera.set_view(view);
+    assert_eq!(camera.view_matrix(), view);
+    assert_eq!(camera.transform.position(), Vec3::new(1.5, -1.0, 0.0));
+
+    camera.clear_view();
+    assert!(!camera.has_custom_view())
+}
+
+#[test]
+fn drag_pan_clears_custom_view() {
+    use moon_engine::Mat4;
+
+    let mut camera = Camera::new();
+    camera.set_view(Mat4::new_translation(&Vec3::new(-3.0, 0.0, 0.0)));
+    camera.drag_pan(&middle_drag(true, Vec2::new(1.0, 0.0)));
+    assert!(!camera.has_custom_view());
+    assert_eq!(camera.transform.position(), Vec3::new(2.0, 0.0, 0.0))
+}
+
+#[test]
+fn zoom_at_keeps_cursor_fixed() {
+    use moon_engine::Vec4;
+
+    let mut camera = Camera::new();
+    camera.transform.set_position(Vec3::new(1.0, -2.0, 0.0));
+    let cursor = Vec2::new(3.0, 1.5);
+    let point = Vec4::new(cursor.x, cursor.y, 0.0, 1.0);
+
+    let before = camera.view_projection_matrix() * point;
+    camera.zoom_at(0.5, cursor);
+    let after = camera.view_projection_matrix() * point;
+
+    assert_eq!(camera.zoom(), 1.5);
+    assert!((before - after).norm() < 1e-5)
+}
+
+#[test]
+fn zoom_clamped() {
+    let mut camera = Camera::new();
+    camera.set_zoom(-1.0);
+    assert_eq!(camera.zoom(), MIN_ZOOM)
+}
+
+#[test]
+fn orthographic_projection_has_no_perspective() {
+    use moon_engine::Vec4;
+
+    let camera = Camera::new_orthographic(-2.0, 2.0, -1.0, 1.0, 0.0, 100.0);
+    assert!(matches!(
+        camera.projection_kind(),
+        ProjectionKind::Orthographic(_)
+    ));
+    let near = camera.view_projection_matrix() * Vec4::new(2.0, 1.0, -1.0, 1.0);
+    let far = camera.view_projection_matrix() * Vec4::new(2.0, 1.0, -50.0, 1.0);
+    assert_eq!((near.x, near.y, near.w), (1.0, 1.0, 1.0));
+    assert_eq!((far.x, far.y, far.w), (1.0, 1.0, 1.0))
+}
+
+#[test]
+fn perspective_projection_near_far() {
+    let camera = Camera::new_perspective(16.0 / 9.0, 1.0, 0.1, 500.0);
+    assert!(matches!(
+        camera.projection_kind(),
+        ProjectionKind::Perspective(_)
+    ));
+    assert!((camera.near() - 0.1).abs() < 1e-6);
+    assert!((camera.far() - 500.0).abs() < 0.5)
+}
+
+#[test]
+fn default_camera_is_orthographic() {
+    assert!(matches!(
+        Camera::new().projection_kind(),
+        ProjectionKind::Orthographic(_)
+    ))
+}
+
+#[test]
+fn zoom_by_scales_extents() {
+    let mut camera = Camera::new_orthographic(-2.0, 2.0, -1.0, 1.0, 0.0, 100.0);
+    camera.zoom_by(1.0);
+    assert_eq!(camera.zoom(), 2.0);
+    // The visible width is halved, from 4 to 2 world units
+    assert_eq!(camera.projection()[0], 1.0);
+
+    camera.zoom_by(-10.0);
+    assert_eq!(camera.zoom(), MIN_ZOOM)
+}
+
+#[test]
+fn follow_lags_with_smoothing() {
+    let mut camera = Camera::new();
+    let target = Vec3::new(10.0, 0.0, 0.0);
+    camera.follow(target, 0.5, 0.1);
+    let first = camera.transform.position().x;
+    assert!(first > 0.0 && first < 10.0);
+
+    for _ in 0..100 {
+        camera.follow(target, 0.5, 0.1);
+    }
+    assert!((camera.transform.position().x - 10.0).abs() < 1e-3)
+}
+
+#[test]
+fn follow_frame_rate_independent() {
+    let target = Vec3::new(10.0, 0.0, 0.0);
+    let mut once = Camera::new();
+    once.follow(target, 0.25, 0.2);
+    let mut twice = Camera::new();
+    twice.follow(target, 0.25, 0.1);
+    twice.follow(target, 0.25, 0.1);
+    assert!((once.transform.position().x - twice.transform.position().x).abs() < 1e-4)
+}
+
+#[test]
+fn center_ray_points_forward() {
+    let mut camera = Camera::new_perspective(1.0, 1.0, 0.1, 100.0);
+    camera.transform.set_position(Vec3::new(1.0, 2.0, 5.0));
+    let (origin, direction) = camera.screen_to_world_ray(Vec2::zeros());
+    assert!((direction - camera.forward()).norm() < 1e-4);
+    assert!((origin - Vec3::new(1.0, 2.0, 4.9)).norm() < 1e-3)
+}
+
+#[test]
+fn orthographic_ray_origin_follows_point() {
+    let camera = Camera::new_orthographic(-2.0, 2.0, -1.0, 1.0, 0.0, 100.0);
+    let (origin, direction) = camera.screen_to_world_ray(Vec2::new(1.0, -1.0));
+    assert!((direction - Vec3::new(0.0, 0.0, -1.0)).norm() < 1e-5);
+    assert!((origin - Vec3::new(2.0, -1.0, 0.0)).norm() < 1e-4)
+}
+
+#[test]
+fn aspect_ratio_keeps_vertical_extent() {
+    let mut camera = Camera::new_orthographic(-2.0, 2.0, -1.0, 1.0, 0.0, 100.0);
+    camera.set_aspect_ratio(4.0);
+    // The visible width is now 8 world units, and the height is unchanged
+    assert_eq!(camera.projection()[0], 0.25);
+    assert_eq!(camera.projection()[5], 1.0)
+}
+
+#[test]
+fn aspect_ratio_updates_perspective() {
+    let mut camera = Camera::new_perspective(1.0, 1.0, 0.1, 100.0);
+    let before = camera.projection()[0];
+    camera.set_aspect_ratio(2.0);
+    assert!((camera.projection()[0] - before / 2.0).abs() < 1e-6);
+    camera.set_aspect_ratio(0.0);
+    assert!((camera.projection()[0] - before / 2.0).abs() < 1e-6)
+}
+
+#[test]
+fn screen_to_world_corners() {
+    let mut camera = Camera::new();
+    camera.set_width_and_height(800.0, 600.0);
+    let (x, y) = camera.screen_to_world_coordinates(0.0, 0.0);
+    assert!((x + FIXED_WIDTH / 2.0).abs() < 1e-4);
+    assert!((y + FIXED_HEIGHT / 2.0).abs() < 1e-4)
+}
+
+#[test]
+fn set_fov_and_clip_planes() {
+    let mut camera = Camera::new_perspective(1.0, 1.0, 0.1, 100.0);
+    camera.set_fov(90.0);
+    // cot(45 degrees) is 1
+    assert!((camera.projection()[5] - 1.0).abs() < 1e-5);
+
+    camera.set_near(1.0);
+    camera.set_far(50.0);
+    assert!((camera.near() - 1.0).abs() < 1e-4);
+    assert!((camera.far() - 50.0).abs() < 1e-2)
+}
+
+#[test]
+fn invalid_clip_planes_ignored() {
+    let mut camera = Camera::new_perspective(1.0, 1.0, 1.0, 10.0);
+    camera.set_near(0.0);
+    camera.set_near(20.0);
+    camera.set_far(0.5);
+    camera.set_fov(180.0);
+    assert!((camera.near() - 1.0).abs() < 1e-4);
+    assert!((camera.far() - 10.0).abs() < 1e-3);
+    assert!((camera.projection()[5] - 1.0 / 0.5f32.tan()).abs() < 1e-5)
+}
+
+#[test]
+fn shake_trauma_clamped() {
+    let mut camera = Camera::new();
+    camera.add_shake(0.75);
+    camera.add_shake(0.75);
+    assert_eq!(camera.trauma(), 1.0)
+}
+
+#[test]
+fn shake_decays_without_drift() {
+    let mut camera = Camera::new();
+    camera.transform.set_position(Vec3::new(2.0, 1.0, 0.0));
+    let view = camera.view_matrix();
+    camera.add_shake(0.5);
+    camera.update_shake(1.0);
+    assert_eq!(camera.trauma(), 0.0);
+    assert_eq!(camera.shake_offset(), Vec2::zeros());
+    assert_eq!(camera.view_matrix(), view);
+    assert_eq!(camera.transform.position(), Vec3::new(2.0, 1.0, 0.0))
+}
+
+#[test]
+fn new_2d_is_centered_with_y_up() {
+    use moon_engine::Vec4;
+
+    let camera = Camera::new_2d(16.0, 9.0);
+    let top_right = camera.view_projection_matrix() * Vec4::new(8.0, 4.5, 0.0, 1.0);
+    assert_eq!((top_right.x, top_right.y), (1.0, 1.0));
+    let bottom_left = camera.view_projection_matrix() * Vec4::new(-8.0, -4.5, 0.0, 1.0);
+    assert_eq!((bottom_left.x, bottom_left.y), (-1.0, -1.0))
+}
+
+#[test]
+fn new_2d_with_bottom_left_origin() {
+    use moon_engine::Vec4;
+
+    let camera = Camera::new_2d_with_origin(320.0, 180.0, CameraOrigin::BottomLeft);
+    let origin = camera.view_projection_matrix() * Vec4::new(0.0, 0.0, 0.0, 1.0);
+    assert_eq!((origin.x, origin.y), (-1.0, -1.0));
+    let corner = camera.view_projection_matrix() * Vec4::new(320.0, 180.0, 0.0, 1.0);
+    assert_eq!((corner.x, corner.y), (1.0, 1.0))
+}
+
+#[test]
+fn aspect_ratio_keeps_bottom_left_origin() {
+    use moon_engine::Vec4;
+
+    let mut camera = Camera::new_2d_with_origin(320.0, 180.0, CameraOrigin::BottomLeft);
+    assert_eq!(camera.origin(), CameraOrigin::BottomLeft);
+    camera.set_aspect_ratio(4.0 / 3.0);
+    let origin = camera.view_projection_matrix() * Vec4::new(0.0, 0.0, 0.0, 1.0);
+    assert_eq!((origin.x, origin.y), (-1.0, -1.0));
+    let corner = camera.view_projection_matrix() * Vec4::new(240.0, 180.0, 0.0, 1.0);
+    assert!((corner.x - 1.0).abs() < 1e-6);
+    assert_eq!(corner.y, 1.0)
+}
+
+#[test]
+fn world_to_screen_flips_y() {
+    let camera = Camera::new_2d(16.0, 9.0);
+    let viewport = Vec2::new(1600.0, 900.0);
+    assert_eq!(
+        camera.world_to_screen(Vec3::zeros(), viewport),
+        Some(Vec2::new(800.0, 450.0))
+    );
+    assert_eq!(
+        camera.world_to_screen(Vec3::new(-8.0, -4.5, 0.0), viewport),
+        Some(Vec2::new(0.0, 900.0))
+    )
+}
+
+#[test]
+fn world_to_screen_inverts_screen_to_world_ray() {
+    let mut camera = Camera::new_perspective(16.0 / 9.0, 1.0, 0.1, 100.0);
+    camera.transform.set_position(Vec3::new(1.0, 2.0, 10.0));
+    let (origin, direction) = camera.screen_to_world_ray(Vec2::new(0.5, -0.25));
+    let screen = camera
+        .world_to_screen(origin + direction * 5.0, Vec2::new(2.0, 2.0))
+        .unwrap();
+    assert!((screen - Vec2::new(1.5, 1.25)).norm() < 1e-3)
+}
+
+#[test]
+fn world_to_screen_behind_perspective_camera() {
+    let camera = Camera::new_perspective(1.0, 1.0, 0.1, 100.0);
+    assert_eq!(
+        camera.world_to_screen(Vec3::new(0.0, 0.0, 5.0), Vec2::new(100.0, 100.0)),
+        None
+    )
+}
+
+#[test]
+fn drag_pan_scales_with_zoom() {
+    let mut camera = Camera::new();
+    camera.set_zoom(2.0);
+    camera.drag_pan(&middle_drag(true, Vec2::new(10.0, 0.0)));
+    assert!((camera.transform.position() - Vec3::new(-5.0, 0.0, 0.0)).norm() < 1e-5)
+}
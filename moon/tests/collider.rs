@@ -1,5 +1,7 @@
+#![allow(clippy::bool_assert_comparison)]
+
 use moon_engine::collider::*;
-use moon_engine::Point;
+use moon_engine::{Point, Vec3};
 
 #[test]
 fn point_and_itself() {
@@ -76,3 +78,128 @@ fn circle_and_circle_edges() {
     let c2 = Circle::new_position(2.0, 0.0);
     assert_eq!(c1.collide_with(&c2), false)
 }
+
+#[test]
+fn aabb_touching_edge() {
+    let a = AABB::new_position(0.0, 0.0);
+    let b = AABB::new_position(1.0, 0.0);
+    assert!(!a.intersects(&b));
+    assert!(!a.contains_point(Point::new(0.5, 0.0)))
+}
+
+#[test]
+fn aabb_fully_contained() {
+    let outer = AABB::new_size(4.0, 4.0);
+    let inner = AABB::new_position_and_size(0.5, 0.5, 1.0, 1.0);
+    assert!(outer.intersects(&inner));
+    assert!(inner.intersects(&outer));
+    assert!(outer.contains_point(Point::new(1.5, -1.5)))
+}
+
+#[test]
+fn aabb_disjoint() {
+    let a = AABB::new_position(0.0, 0.0);
+    let b = AABB::new_position(3.0, 3.0);
+    assert!(!a.intersects(&b));
+    assert!(!a.contains_point(Point::new(3.0, 3.0)))
+}
+
+#[test]
+fn aabb_resolve_pushes_out() {
+    let player = AABB::new_position(0.0, 0.8);
+    let tile = AABB::new_position(0.0, 0.0);
+    let push = player.resolve(&tile).unwrap();
+    assert!((push - Point::new(0.0, 0.2)).norm() < 1e-6);
+
+    let moved = AABB {
+        min: player.min + push,
+        max: player.max + push,
+    };
+    assert!(!moved.intersects(&tile));
+    assert!((tile.resolve(&player).unwrap() + push).norm() < 1e-6)
+}
+
+#[test]
+fn aabb_resolve_equal_overlap_and_disjoint() {
+    let a = AABB::new_position(-0.75, -0.75);
+    let b = AABB::new_position(0.0, 0.0);
+    assert_eq!(a.resolve(&b), Some(Point::new(-0.25, 0.0)));
+    assert_eq!(a.resolve(&AABB::new_position(5.0, 0.0)), None)
+}
+
+#[test]
+fn circle_intersections() {
+    let coin = Circle::new_position_and_size(1.0, 0.0, 0.5);
+    assert!(coin.intersects_circle(&Circle::new_size(0.6)));
+    assert!(!coin.intersects_circle(&Circle::new_size(0.4)));
+    assert!(coin.intersects_aabb(&AABB::new_position(0.2, 0.0)));
+    assert!(!coin.intersects_aabb(&AABB::new_position(0.0, 1.5)));
+    assert!(coin.contains_point(Point::new(1.2, 0.2)));
+    assert!(!coin.contains_point(Point::new(1.5, 0.0)))
+}
+
+#[test]
+fn circle_bounding_box_uses_radius() {
+    let circle = Circle::new_position_and_size(1.0, 2.0, 0.25);
+    assert_eq!(
+        circle.get_bounding_box(),
+        AABB::new_position_and_size(1.0, 2.0, 0.5, 0.5)
+    )
+}
+
+#[test]
+fn aabb_ray_hits() {
+    let tile = AABB::new_position(2.0, 1.0);
+    let hit = tile.ray_intersect(Vec3::new(2.2, 0.8, 10.0), Vec3::new(0.0, 0.0, -1.0));
+    assert_eq!(hit, Some(10.0));
+    assert_eq!(
+        tile.ray_intersect(Vec3::new(0.0, 1.0, 0.0), Vec3::x()),
+        Some(1.5)
+    )
+}
+
+#[test]
+fn aabb_ray_misses() {
+    let tile = AABB::new_position(2.0, 1.0);
+    let down = Vec3::new(0.0, 0.0, -1.0);
+    assert_eq!(tile.ray_intersect(Vec3::new(0.0, 0.0, 10.0), down), None);
+    assert_eq!(tile.ray_intersect(Vec3::new(2.0, 1.0, 10.0), -down), None)
+}
+
+#[test]
+fn aabb_ray_starts_inside() {
+    let tile = AABB::new_position(2.0, 1.0);
+    assert_eq!(
+        tile.ray_intersect(Vec3::new(2.0, 1.0, 0.0), Vec3::x()),
+        Some(0.0)
+    )
+}
+
+#[test]
+fn spatial_grid_query_region() {
+    let mut grid = SpatialGrid::new(1.0);
+    grid.insert(0, AABB::new_position(0.5, 0.5));
+    grid.insert(1, AABB::new_position(2.5, 0.5));
+    grid.insert(2, AABB::new_position_and_size(5.0, 5.0, 4.0, 4.0));
+    grid.insert(3, AABB::new_position(-3.5, 0.5));
+
+    let area = AABB::new_position_and_size(2.0, 1.0, 3.0, 1.0);
+    assert_eq!(grid.query_region(area), vec![0, 1]);
+    assert_eq!(grid.query_region(AABB::new_position(4.0, 4.0)), vec![2]);
+
+    grid.remove(1);
+    assert_eq!(grid.query_region(area), vec![0]);
+    assert_eq!(grid.len(), 3)
+}
+
+#[test]
+#[should_panic]
+fn spatial_grid_rejects_zero_cell_size() {
+    SpatialGrid::new(0.0);
+}
+
+#[test]
+#[should_panic]
+fn spatial_grid_rejects_nan_cell_size() {
+    SpatialGrid::new(f32::NAN);
+}
@@ -0,0 +1,42 @@
+use moon_engine::collider::AABB;
+use moon_engine::debug::*;
+use moon_engine::Color32;
+
+#[test]
+fn grid_line_count() {
+    let mut overlay = DebugOverlay::new();
+    overlay.draw_grid(
+        2.0,
+        Color32::WHITE,
+        AABB::new_position_and_size(5.0, 5.0, 10.0, 10.0),
+    );
+    assert_eq!(overlay.lines().len(), 12)
+}
+
+#[test]
+fn grid_invalid_spacing() {
+    let mut overlay = DebugOverlay::new();
+    overlay.draw_grid(0.0, Color32::WHITE, AABB::new_size(10.0, 10.0));
+    assert!(overlay.lines().is_empty())
+}
+
+#[test]
+fn clear_lines() {
+    let mut overlay = DebugOverlay::new();
+    overlay.draw_grid(1.0, Color32::WHITE, AABB::new_size(2.0, 2.0));
+    overlay.clear();
+    assert!(overlay.lines().is_empty())
+}
+
+#[test]
+fn aabb_outline_closes_loop() {
+    let lines = Line::aabb_outline(&AABB::new_size(2.0, 2.0), Color32::WHITE);
+    for (index, line) in lines.iter().enumerate() {
+        assert_eq!(line.end, lines[(index + 1) % 4].start);
+    }
+    assert_eq!(lines[0].start, AABB::new_size(2.0, 2.0).min);
+
+    let mut overlay = DebugOverlay::new();
+    overlay.draw_aabb_outline(&AABB::default(), Color32::WHITE);
+    assert_eq!(overlay.lines().len(), 4)
+}
@@ -0,0 +1,51 @@
+use moon_engine::font::*;
+use moon_engine::texture::SubTexture;
+use moon_engine::{Color32, Vec2};
+
+fn ascii_font() -> Font {
+    Font::new_grid(SubTexture::default(), 16, 16)
+}
+
+#[test]
+fn glyphs_are_row_major() {
+    let font = ascii_font();
+    // 'A' is 65, the second cell of the fifth row
+    let uv = font.glyph('A').unwrap().get_uv_coords();
+    assert_eq!(uv[0], [1.0 / 16.0, 4.0 / 16.0]);
+    assert_eq!(uv[2], [2.0 / 16.0, 5.0 / 16.0]);
+    assert!(font.glyph('é').is_some());
+    assert!(font.glyph('€').is_none())
+}
+
+#[test]
+fn text_layout_handles_newlines_and_spaces() {
+    let font = ascii_font();
+    let quads = font.quads("a b\nc", Vec2::new(1.0, 2.0), 0.5, Color32::WHITE);
+    assert_eq!(quads.len(), 3);
+
+    let center = |quad: &moon_engine::renderer::Quad| {
+        let vertices = quad.get_vertices();
+        [
+            (vertices[0].position[0] + vertices[2].position[0]) / 2.0,
+            (vertices[0].position[1] + vertices[2].position[1]) / 2.0,
+        ]
+    };
+    assert_eq!(center(&quads[0]), [1.25, 2.25]);
+    assert_eq!(center(&quads[1]), [2.25, 2.25]);
+    assert_eq!(center(&quads[2]), [1.25, 2.75])
+}
+
+#[test]
+fn unknown_glyphs_fall_back() {
+    let digits = Font::new_grid_with_first_char(SubTexture::default(), 10, 1, '0');
+    let quads = digits.quads("1x", Vec2::zeros(), 1.0, Color32::WHITE);
+    assert_eq!(quads.len(), 1);
+
+    let font = ascii_font();
+    let fallback = font.quads("€", Vec2::zeros(), 1.0, Color32::WHITE);
+    let expected = font.quads("?", Vec2::zeros(), 1.0, Color32::WHITE);
+    assert_eq!(
+        fallback[0].get_vertices()[0].uv,
+        expected[0].get_vertices()[0].uv
+    )
+}
@@ -0,0 +1,152 @@
+use std::rc::Rc;
+
+use moon_engine::bindings::Bindings;
+use moon_engine::collider::AABB;
+use moon_engine::component::Component;
+use moon_engine::game::*;
+use moon_engine::input::{InputManager, Key};
+use moon_engine::renderer::Quad;
+use moon_engine::texture::SubTexture;
+use moon_engine::tilemap::{Tile, TileMap};
+use moon_engine::Vec2;
+
+fn grounded_player() -> Player {
+    let mut player = Player {
+        floor: Some(0.5),
+        ..Default::default()
+    };
+    player.update(0.1);
+    player
+}
+
+#[test]
+fn gravity_pulls_down() {
+    let mut player = Player::default();
+    player.update(0.5);
+    assert!(player.velocity.y > 0.0);
+    assert!(player.position.y > 0.0);
+    assert!(!player.is_grounded)
+}
+
+#[test]
+fn lands_on_floor() {
+    let player = grounded_player();
+    assert!(player.is_grounded);
+    assert_eq!(player.velocity.y, 0.0);
+    assert_eq!(player.position.y, 0.0)
+}
+
+#[test]
+fn jump_only_when_grounded() {
+    let mut player = Player::default();
+    assert!(!player.jump());
+
+    let mut player = grounded_player();
+    assert!(player.jump());
+    assert!(player.is_jumping);
+    assert_eq!(player.velocity.y, -DEFAULT_JUMP_SPEED);
+    assert!(!player.jump());
+
+    for _ in 0..100 {
+        player.update(0.02);
+    }
+    assert!(player.is_grounded);
+    assert!(!player.is_jumping)
+}
+
+#[test]
+fn space_jumps_through_handle_input() {
+    let mut player = grounded_player();
+    let mut input = InputManager::new();
+    input.key_down(Key::Space);
+    input.key_down(Key::D);
+    player.handle_input(&input, &Bindings::default());
+    assert!(player.is_jumping);
+    assert_eq!(player.velocity.x, DEFAULT_SPEED)
+}
+
+fn floor_map(gap: Option<i32>) -> TileMap {
+    let sprite = Rc::new(SubTexture::default());
+    let mut map = TileMap::new(4, 4);
+    for x in (0..4).filter(|x| Some(*x) != gap) {
+        map.set_tile(
+            x,
+            2,
+            Tile::new_with_collider(
+                1,
+                "Ground",
+                Rc::clone(&sprite),
+                AABB::new_position(0.5, 0.5),
+            ),
+        );
+    }
+    map
+}
+
+#[test]
+fn lands_on_tiles() {
+    let map = floor_map(None);
+    let mut player = Player::new_with_position(1.5, 1.7);
+    player.velocity.y = 3.0;
+    player.resolve_tilemap_collision(&map, 1.0);
+    assert!(player.is_grounded);
+    assert_eq!(player.velocity.y, 0.0);
+    assert!((player.position.y - 1.5).abs() < 1e-6)
+}
+
+#[test]
+fn falls_past_gaps() {
+    let map = floor_map(Some(1));
+    let mut player = Player::new_with_position(1.5, 1.7);
+    player.size.x = 0.8;
+    player.resolve_tilemap_collision(&map, 1.0);
+    assert!(!player.is_grounded);
+    assert_eq!(player.position.y, 1.7)
+}
+
+#[test]
+fn faces_last_movement_direction() {
+    let mut player = Player::default();
+    let bindings = Bindings::default();
+    let mut input = InputManager::new();
+    input.key_down(Key::A);
+    player.handle_input(&input, &bindings);
+    assert!(player.facing_left);
+
+    input.key_up(Key::A);
+    player.handle_input(&input, &bindings);
+    assert!(player.facing_left);
+
+    player.sprite = Some(Rc::new(SubTexture::default()));
+    let uv = player.quad().get_vertices()[0].uv;
+    assert_eq!(uv, SubTexture::default().flipped_x().get_uv_coords()[0])
+}
+
+#[test]
+fn gamepad_moves_and_jumps_through_handle_input() {
+    use moon_engine::input::GamepadState;
+
+    let mut player = grounded_player();
+    let mut input = InputManager::new();
+    input.set_gamepad(Some(GamepadState::new(vec![true], vec![-1.0, 0.0])));
+    player.handle_input(&input, &Bindings::default());
+    assert!(player.is_jumping);
+    assert!(player.facing_left);
+    assert_eq!(player.velocity.x, -DEFAULT_SPEED)
+}
+
+#[test]
+fn interpolated_quad_lies_between_updates() {
+    let mut player = Player::new_with_position(0.0, 0.0);
+    player.velocity.x = DEFAULT_SPEED;
+    player.update(0.1);
+    assert_eq!(player.previous_position, Vec2::zeros());
+
+    let center = |quad: Quad| {
+        let vertices = quad.get_vertices();
+        (vertices[0].position[0] + vertices[2].position[0]) / 2.0
+    };
+    let halfway = center(player.interpolated_quad(0.5));
+    assert!((halfway - player.position.x / 2.0).abs() < 1e-6);
+    assert_eq!(center(player.interpolated_quad(1.0)), center(player.quad()));
+}
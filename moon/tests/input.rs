@@ -0,0 +1,287 @@
+use moon_engine::input::*;
+
+#[test]
+fn key_from_lowercase_byte() {
+    assert_eq!(Key::from(b'w'), Key::W)
+}
+
+#[test]
+fn key_from_uppercase_byte() {
+    assert_eq!(Key::from(b'W'), Key::W)
+}
+
+#[test]
+fn key_from_digit_byte() {
+    assert_eq!(Key::from(b'6'), Key::Num6)
+}
+
+#[test]
+fn key_from_unknown_byte() {
+    assert_eq!(Key::from(b'~'), Key::Unknown)
+}
+
+#[test]
+fn key_state_by_byte() {
+    let mut input = InputManager::new();
+    input.key_down(b'w');
+    assert!(input.get_key_state(Key::W));
+    input.key_up(Key::W);
+    assert!(!input.get_key_state(b'w'))
+}
+
+#[test]
+fn key_from_str() {
+    assert_eq!(Key::from("w"), Key::W);
+    assert_eq!(Key::from("W"), Key::W);
+    assert_eq!(Key::from(" "), Key::Space);
+    assert_eq!(Key::from("Dead"), Key::Unknown)
+}
+
+#[test]
+fn key_from_code() {
+    assert_eq!(Key::from_code("KeyA"), Key::A);
+    assert_eq!(Key::from_code("Digit0"), Key::Num0);
+    assert_eq!(Key::from_code("ArrowLeft"), Key::ArrowLeft);
+    assert_eq!(Key::from_code("ControlRight"), Key::Control);
+    assert_eq!(Key::from_code("Escape"), Key::Escape);
+    assert_eq!(Key::from_code("Minus"), Key::Unknown);
+    assert_eq!(Key::from_code("KeyAB"), Key::Unknown)
+}
+
+#[test]
+fn shifted_digit_released_by_code() {
+    let mut input = InputManager::new();
+    // With Shift held, the `key` of the 1 key is "!" but its `code` is still "Digit1"
+    input.key_down(Key::from_code("Digit1"));
+    input.key_up(Key::from_code("Digit1"));
+    assert!(!input.get_key_state(Key::Num1))
+}
+
+#[test]
+fn consume_key_state_reads_once() {
+    let mut input = InputManager::new();
+    input.key_down(Key::E);
+    assert!(input.consume_key_state(Key::E));
+    assert!(!input.consume_key_state(Key::E));
+    assert!(!input.get_key_state(Key::E))
+}
+
+#[test]
+fn consume_key_pressed_once_while_held() {
+    let mut input = InputManager::new();
+    input.key_down(Key::Space);
+    assert!(input.consume_key_pressed(Key::Space));
+    assert!(!input.consume_key_pressed(Key::Space));
+
+    input.new_frame();
+    assert!(!input.consume_key_pressed(Key::Space));
+    input.new_frame();
+    assert!(!input.consume_key_pressed(Key::Space));
+
+    input.key_up(Key::Space);
+    input.new_frame();
+    input.key_down(Key::Space);
+    assert!(input.consume_key_pressed(Key::Space));
+    assert!(input.get_key_state(Key::Space))
+}
+
+#[test]
+fn consume_key_pressed_keeps_tap_between_frames() {
+    let mut input = InputManager::new();
+    input.key_down(Key::Enter);
+    input.key_up(Key::Enter);
+    assert!(input.consume_key_pressed(Key::Enter));
+    assert!(!input.consume_key_pressed(Key::Enter));
+
+    input.key_down(Key::Enter);
+    input.key_up(Key::Enter);
+    input.new_frame();
+    assert!(!input.consume_key_pressed(Key::Enter))
+}
+
+#[test]
+fn mouse_button_from_event_button() {
+    assert_eq!(MouseButton::from(0), MouseButton::Left);
+    assert_eq!(MouseButton::from(1), MouseButton::Middle);
+    assert_eq!(MouseButton::from(2), MouseButton::Right);
+    assert_eq!(MouseButton::from(4), MouseButton::Unknown)
+}
+
+#[test]
+fn mouse_button_state() {
+    let mut input = InputManager::new();
+    input.mouse_button_down(MouseButton::Right);
+    assert!(input.get_mouse_button_state(2));
+    assert!(!input.get_mouse_button_state(MouseButton::Left));
+    input.mouse_button_up(2);
+    assert!(!input.get_mouse_button_state(MouseButton::Right))
+}
+
+#[test]
+fn key_pressed_and_released_edges() {
+    let mut input = InputManager::new();
+    input.key_down(Key::W);
+    assert!(input.get_key_pressed(Key::W));
+    assert!(!input.get_key_released(Key::W));
+
+    input.new_frame();
+    assert!(!input.get_key_pressed(Key::W));
+    assert!(input.get_key_state(Key::W));
+
+    input.key_up(Key::W);
+    assert!(input.get_key_released(Key::W));
+    input.new_frame();
+    assert!(!input.get_key_released(Key::W))
+}
+
+#[test]
+fn consume_key_state_with_edges() {
+    let mut input = InputManager::new();
+    input.key_down(Key::W);
+    input.new_frame();
+    assert!(input.consume_key_state(Key::W));
+    assert!(input.get_key_released(Key::W));
+    input.new_frame();
+    assert!(!input.get_key_state(Key::W))
+}
+
+#[test]
+fn movement_axis_idle() {
+    let input = InputManager::new();
+    assert_eq!(input.movement_axis(), moon_engine::Vec2::zeros())
+}
+
+#[test]
+fn movement_axis_diagonal_normalized() {
+    let mut input = InputManager::new();
+    input.key_down(Key::W);
+    input.key_down(Key::A);
+    let axis = input.movement_axis();
+    assert!((axis.norm() - 1.0).abs() < 1e-6);
+    assert!(axis.x < 0.0 && axis.y > 0.0)
+}
+
+#[test]
+fn movement_axis_opposite_keys_cancel() {
+    let mut input = InputManager::new();
+    input.key_down(Key::A);
+    input.key_down(Key::D);
+    input.key_down(Key::E);
+    assert_eq!(input.movement_axis(), moon_engine::Vec2::zeros());
+    assert_eq!(input.vertical_axis(), 1.0)
+}
+
+#[test]
+fn gamepad_axis_deadzone() {
+    let gamepad = GamepadState::new(vec![true], vec![0.05, -0.1, 0.575, 1.2]);
+    assert_eq!(gamepad.get_axis(0), 0.0);
+    assert_eq!(gamepad.get_axis(1), 0.0);
+    assert!((gamepad.get_axis(2) - 0.5).abs() < 1e-6);
+    assert_eq!(gamepad.get_axis(3), 1.0);
+    assert_eq!(gamepad.get_axis(4), 0.0)
+}
+
+#[test]
+fn gamepad_buttons() {
+    let mut input = InputManager::new();
+    assert!(!input.gamepad_connected());
+    input.set_gamepad(Some(GamepadState::new(vec![false, true], Vec::new())));
+    let gamepad = input.gamepad().unwrap();
+    assert!(gamepad.get_button(1));
+    assert!(!gamepad.get_button(0));
+    assert!(!gamepad.get_button(7))
+}
+
+#[test]
+fn number_keys_from_str() {
+    let expected = [
+        Key::Num0,
+        Key::Num1,
+        Key::Num2,
+        Key::Num3,
+        Key::Num4,
+        Key::Num5,
+        Key::Num6,
+        Key::Num7,
+        Key::Num8,
+        Key::Num9,
+    ];
+    for (digit, key) in expected.iter().enumerate() {
+        assert_eq!(Key::from(digit.to_string().as_str()), *key);
+    }
+}
+
+#[test]
+fn mouse_delta_accumulates_and_resets() {
+    use moon_engine::Vec2;
+
+    let mut input = InputManager::new();
+    input.set_mouse_position(1.0, 1.0);
+    input.new_frame();
+    input.set_mouse_position(2.0, 0.5);
+    input.set_mouse_position(4.0, 0.0);
+    assert_eq!(input.get_mouse_delta(), Vec2::new(3.0, -1.0));
+
+    input.new_frame();
+    assert_eq!(input.get_mouse_delta(), Vec2::zeros())
+}
+
+#[test]
+fn scroll_delta_accumulates_and_resets() {
+    let mut input = InputManager::new();
+    input.add_scroll(1.5);
+    input.add_scroll(-0.5);
+    assert_eq!(input.get_scroll_delta(), 1.0);
+    input.new_frame();
+    assert_eq!(input.get_scroll_delta(), 0.0)
+}
+
+#[test]
+fn named_keys_from_str() {
+    assert_eq!(Key::from("ArrowUp"), Key::ArrowUp);
+    assert_eq!(Key::from("ArrowDown"), Key::ArrowDown);
+    assert_eq!(Key::from("ArrowLeft"), Key::ArrowLeft);
+    assert_eq!(Key::from("ArrowRight"), Key::ArrowRight);
+    assert_eq!(Key::from("Shift"), Key::Shift);
+    assert_eq!(Key::from("Control"), Key::Control);
+    assert_eq!(Key::from("Alt"), Key::Alt);
+    assert_eq!(Key::from("Tab"), Key::Tab);
+    assert_eq!(Key::from("Enter"), Key::Enter);
+    assert_eq!(Key::from("Escape"), Key::Escape);
+    assert_eq!(Key::from("PageUp"), Key::Unknown)
+}
+
+#[test]
+fn control_key_codes() {
+    assert_eq!(Key::from(9), Key::Tab);
+    assert_eq!(Key::from(13), Key::Enter);
+    assert_eq!(Key::from(27), Key::Escape)
+}
+
+#[test]
+fn mouse_ndc_corners() {
+    use moon_engine::Vec2;
+
+    let mut input = InputManager::new();
+    input.set_mouse_position(800.0, 600.0);
+    assert_eq!(input.mouse_ndc(800.0, 600.0), Vec2::new(1.0, -1.0));
+    input.set_mouse_position(400.0, 150.0);
+    assert_eq!(input.mouse_ndc(800.0, 600.0), Vec2::new(0.0, 0.5))
+}
+
+#[test]
+fn mouse_ndc_empty_canvas() {
+    let input = InputManager::new();
+    assert_eq!(input.mouse_ndc(0.0, 600.0), moon_engine::Vec2::zeros())
+}
+
+#[test]
+fn first_mouse_position_has_no_delta() {
+    use moon_engine::Vec2;
+
+    let mut input = InputManager::new();
+    input.set_mouse_position(300.0, 200.0);
+    assert_eq!(input.get_mouse_delta(), Vec2::zeros());
+    input.set_mouse_position(310.0, 195.0);
+    assert_eq!(input.get_mouse_delta(), Vec2::new(10.0, -5.0))
+}
@@ -0,0 +1,20 @@
+use moon_engine::light::*;
+use moon_engine::{Color32, Vec2};
+
+#[test]
+fn light_quad_centered_on_position() {
+    let light = Light::new(Vec2::new(3.0, -2.0), 1.5, Color32::WHITE);
+    let vertices = light.get_quad().get_vertices();
+    assert_eq!(vertices[0].position, [1.5, -0.5]);
+    assert_eq!(vertices[2].position, [4.5, -3.5])
+}
+
+#[test]
+fn light_quad_uses_color() {
+    let color = Color32(1.0, 0.5, 0.25, 1.0);
+    let light = Light::new(Vec2::zeros(), 1.0, color);
+    let vertices = light.get_quad().get_vertices();
+    assert!(vertices
+        .iter()
+        .all(|vertex| vertex.color == [1.0, 0.5, 0.25, 1.0]))
+}
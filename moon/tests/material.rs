@@ -0,0 +1,45 @@
+use std::rc::Rc;
+
+use moon_engine::material::*;
+use moon_engine::shader::Shader;
+use moon_engine::{Vec2, Vec4};
+
+#[test]
+fn uniform_value_from_types() {
+    assert_eq!(UniformValue::from(0.5), UniformValue::F32(0.5));
+    assert_eq!(UniformValue::from(2), UniformValue::I32(2));
+    assert_eq!(
+        UniformValue::from(Vec2::new(1.0, 2.0)),
+        UniformValue::Vec2(Vec2::new(1.0, 2.0))
+    )
+}
+
+#[test]
+fn material_sets_and_replaces_uniforms() {
+    let mut material = Material::new(Rc::new(Shader::default()))
+        .with_uniform("uColor", Vec4::new(1.0, 0.0, 0.0, 1.0))
+        .with_uniform("uStrength", 0.5);
+    material.set_uniform("uStrength", 0.75);
+    assert_eq!(material.uniform("uStrength"), Some(UniformValue::F32(0.75)));
+    assert_eq!(
+        material
+            .uniforms()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>(),
+        vec!["uColor", "uStrength"]
+    );
+    assert_eq!(
+        material.remove_uniform("uColor"),
+        Some(UniformValue::Vec4(Vec4::new(1.0, 0.0, 0.0, 1.0)))
+    );
+    assert_eq!(material.uniform("uColor"), None)
+}
+
+#[test]
+fn materials_share_a_shader() {
+    let shader = Rc::new(Shader::default());
+    let red = Material::new(Rc::clone(&shader)).with_uniform("uTint", 1.0);
+    let blue = Material::new(Rc::clone(&shader)).with_uniform("uTint", 0.0);
+    assert!(Rc::ptr_eq(red.shader(), blue.shader()));
+    assert_eq!(Rc::strong_count(&shader), 3)
+}
@@ -0,0 +1,79 @@
+use moon_engine::math::*;
+
+#[test]
+fn color_from_hex() {
+    let color = Color32::from_hex("#3366ff80").unwrap();
+    assert_eq!(
+        color.to_array(),
+        Color32::from_rgba_u8(0x33, 0x66, 0xFF, 0x80).to_array()
+    );
+    assert_eq!(
+        Color32::from_hex("000000").unwrap().to_array(),
+        [0.0, 0.0, 0.0, 1.0]
+    )
+}
+
+#[test]
+fn color_from_invalid_hex() {
+    assert!(Color32::from_hex("#12345").is_err());
+    assert!(Color32::from_hex("#GG0000").is_err());
+    assert!(Color32::from_hex("#ÿÿÿ").is_err())
+}
+
+fn assert_color_eq(a: Color32, b: Color32) {
+    for (x, y) in a.to_array().iter().zip(b.to_array()) {
+        assert!((x - y).abs() < 1e-5, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn hsv_known_colors() {
+    assert_color_eq(
+        Color32::from_hsv(0.0, 1.0, 1.0),
+        Color32(1.0, 0.0, 0.0, 1.0),
+    );
+    assert_color_eq(
+        Color32::from_hsv(120.0, 1.0, 1.0),
+        Color32(0.0, 1.0, 0.0, 1.0),
+    );
+    assert_color_eq(
+        Color32::from_hsv(240.0, 1.0, 0.5),
+        Color32(0.0, 0.0, 0.5, 1.0),
+    );
+    assert_color_eq(Color32::from_hsv(300.0, 0.0, 1.0), Color32::WHITE);
+}
+
+#[test]
+fn hsv_hue_wraps_and_clamps() {
+    assert_color_eq(
+        Color32::from_hsv(360.0, 1.0, 1.0),
+        Color32::from_hsv(0.0, 1.0, 1.0),
+    );
+    assert_color_eq(Color32::from_hsv(-60.0, 2.0, 1.0), Color32::MAGENTA);
+}
+
+#[test]
+fn hsv_round_trip() {
+    let color = Color32(0.2, 0.6, 0.4, 1.0);
+    let (hue, saturation, value) = color.to_hsv();
+    assert!((hue - 150.0).abs() < 1e-3);
+    assert_color_eq(Color32::from_hsv(hue, saturation, value), color);
+}
+
+#[test]
+fn color_lerp() {
+    let smoke = Color32::WHITE.lerp(&Color32::ZEROES, 0.25);
+    assert_color_eq(smoke, Color32(0.75, 0.75, 0.75, 0.75));
+    assert_color_eq(Color32::WHITE.lerp(&Color32::BLACK, 2.0), Color32::BLACK);
+}
+
+#[test]
+fn named_colors_usable_in_statics() {
+    static PALETTE: [Color32; 3] = [Color32::RED, Color32::GREEN, Color32::BLUE];
+    for (index, color) in PALETTE.iter().enumerate() {
+        let mut expected = [0.0, 0.0, 0.0, 1.0];
+        expected[index] = 1.0;
+        assert_eq!(color.to_array(), expected);
+    }
+    assert_eq!(Color32::TRANSPARENT.a(), 0.0)
+}
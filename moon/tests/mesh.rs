@@ -0,0 +1,32 @@
+use moon_engine::mesh::*;
+
+#[test]
+fn vertex_layout() {
+    let layout = Vertex::layout();
+    assert_eq!(layout.stride, 32);
+    assert_eq!(layout.divisor, 0);
+    assert_eq!(layout.attributes.len(), 3);
+    assert_eq!(layout.attributes[2].offset, 16)
+}
+
+#[test]
+fn instance_layout() {
+    let layout = BufferLayout::new(16, 1).with_attribute(3, 4, 0);
+    assert_eq!(layout.divisor, 1);
+    assert_eq!(
+        layout.attributes,
+        vec![VertexAttribute {
+            location: 3,
+            size: 4,
+            offset: 0
+        }]
+    )
+}
+
+#[test]
+fn index_type_boundary() {
+    assert_eq!(IndexType::for_vertex_count(65535), IndexType::U16);
+    assert_eq!(IndexType::for_vertex_count(65536), IndexType::U32);
+    assert_eq!(IndexType::U16.size(), 2);
+    assert_eq!(IndexType::U32.size(), 4)
+}
@@ -0,0 +1,193 @@
+use moon_engine::component::Component;
+use moon_engine::math::Rng;
+use moon_engine::particle::{Ease, EmitterShape, ParticleProps, ParticleSystem};
+use moon_engine::renderer::Quad;
+use moon_engine::{Color32, Vec2};
+
+/// Get the center of each [`Quad`](moon_engine::renderer::Quad) of a [`ParticleSystem`].
+fn centers(system: &ParticleSystem) -> Vec<[f32; 2]> {
+    centers_of(&system.get_quads().unwrap())
+}
+
+/// Get the center of each [`Quad`].
+fn centers_of(quads: &[Quad]) -> Vec<[f32; 2]> {
+    quads
+        .iter()
+        .map(|quad| {
+            quad.get_vertices().iter().fold([0.0, 0.0], |sum, vertex| {
+                [
+                    sum[0] + vertex.position[0] / 4.0,
+                    sum[1] + vertex.position[1] / 4.0,
+                ]
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn rng_is_reproducible_and_in_range() {
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+    for _ in 0..100 {
+        let value = a.range(-2.0, 3.0);
+        assert_eq!(value, b.range(-2.0, 3.0));
+        assert!((-2.0..3.0).contains(&value));
+    }
+    assert_ne!(Rng::new(7).next_u32(), Rng::new(8).next_u32());
+    assert_ne!(Rng::new(0).next_u32(), 0);
+}
+
+#[test]
+fn seeded_systems_emit_the_same_particles() {
+    let props = ParticleProps {
+        shape: EmitterShape::Circle { radius: 1.0 },
+        ..Default::default()
+    };
+    let mut a = ParticleSystem::new_from_emission(props.clone());
+    let mut b = ParticleSystem::new_from_emission(props);
+    a.set_seed(3);
+    b.set_seed(3);
+    a.emit_many(10);
+    b.emit_many(10);
+    assert_eq!(centers(&a), centers(&b));
+}
+
+#[test]
+fn unseeded_systems_emit_different_particles() {
+    let props = ParticleProps {
+        shape: EmitterShape::Circle { radius: 1.0 },
+        ..Default::default()
+    };
+    let mut a = ParticleSystem::new_from_emission(props.clone());
+    let mut b = ParticleSystem::new_from_emission(props);
+    a.emit_many(10);
+    b.emit_many(10);
+    assert_ne!(centers(&a), centers(&b));
+}
+
+#[test]
+fn rect_shape_spawns_inside_area() {
+    let props = ParticleProps {
+        velocity: Vec2::zeros(),
+        velocity_modifier: Vec2::zeros(),
+        shape: EmitterShape::Rect {
+            size: Vec2::new(4.0, 2.0),
+        },
+        ..Default::default()
+    };
+    let mut system = ParticleSystem::new_from_emission_and_position(props, 10.0, 0.0);
+    system.emit_many(50);
+    for [x, y] in centers(&system) {
+        assert!((8.0..=12.0).contains(&x) && (-1.0..=1.0).contains(&y));
+    }
+}
+
+#[test]
+fn cone_shape_keeps_speed_within_angle() {
+    let props = ParticleProps {
+        velocity: Vec2::new(0.0, -1.0),
+        velocity_modifier: Vec2::zeros(),
+        lifetime: 10.0,
+        lifetime_modifier: 0.0,
+        burst_count: 0,
+        shape: EmitterShape::Cone { angle: 1.0 },
+        ..Default::default()
+    };
+    let mut system = ParticleSystem::new_from_emission(props);
+    system.init();
+    system.emit_many(50);
+    system.update(1.0);
+    for [x, y] in centers(&system) {
+        assert!(((x * x + y * y).sqrt() - 1.0).abs() < 1e-4);
+        // Within half of the angle of straight up
+        assert!(x.atan2(-y).abs() <= 0.5 + 1e-4);
+    }
+}
+
+#[test]
+fn lifetime_modifier_staggers_particles() {
+    let props = ParticleProps {
+        lifetime: 2.0,
+        lifetime_modifier: 1.0,
+        burst_count: 0,
+        ..Default::default()
+    };
+    let mut system = ParticleSystem::new_from_emission(props);
+    system.init();
+    system.emit_many(50);
+    system.update(2.0);
+    let alive = system.alive_count();
+    assert!(alive > 0 && alive < 50);
+    system.update(1.1);
+    assert_eq!(system.alive_count(), 0);
+}
+
+#[test]
+fn color_and_size_follow_eased_age() {
+    let props = ParticleProps {
+        lifetime: 2.0,
+        velocity: Vec2::zeros(),
+        velocity_modifier: Vec2::zeros(),
+        color_start: Color32::WHITE,
+        color_end: Color32::TRANSPARENT,
+        color_modifier: Color32::ZEROES,
+        size: Vec2::new(2.0, 2.0),
+        size_end: Vec2::zeros(),
+        burst_count: 0,
+        ease: Ease::EaseOut,
+        ..Default::default()
+    };
+    let mut system = ParticleSystem::new_from_emission(props);
+    system.init();
+    system.emit();
+    system.update(1.0);
+
+    // Half of the lifetime, eased out to three quarters of the way
+    let quads = system.get_quads().unwrap();
+    let vertex = quads[0].get_vertices()[0];
+    for channel in vertex.color {
+        assert!((channel - 0.25).abs() < 1e-5);
+    }
+    let half_diagonal = (vertex.position[0].powi(2) + vertex.position[1].powi(2)).sqrt();
+    assert!((half_diagonal - 0.5_f32.hypot(0.5) / 2.0).abs() < 1e-5);
+}
+
+#[test]
+fn pool_reuses_slots_when_full() {
+    let props = ParticleProps {
+        burst_count: 0,
+        ..Default::default()
+    };
+    let mut system = ParticleSystem::new_from_emission_with_capacity(props, 8);
+    assert_eq!(system.capacity(), 8);
+    system.init();
+    system.emit_many(20);
+    assert_eq!(system.alive_count(), 8);
+    assert_eq!(system.get_quads().unwrap().len(), 8);
+}
+
+#[test]
+fn interpolated_quads_lie_between_updates() {
+    let props = ParticleProps {
+        burst_count: 0,
+        velocity: Vec2::new(2.0, 0.0),
+        ..Default::default()
+    };
+    let mut system = ParticleSystem::new_from_emission(props);
+    system.init();
+    system.emit_many(4);
+    let before = centers(&system);
+    system.update(0.5);
+    let after = centers(&system);
+
+    assert_eq!(
+        centers_of(&system.get_interpolated_quads(1.0).unwrap()),
+        after
+    );
+    let halfway = centers_of(&system.get_interpolated_quads(0.5).unwrap());
+    for ((halfway, before), after) in halfway.iter().zip(&before).zip(&after) {
+        for axis in 0..2 {
+            assert!((halfway[axis] - (before[axis] + after[axis]) / 2.0).abs() < 1e-5);
+        }
+    }
+}
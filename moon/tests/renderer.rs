@@ -0,0 +1,64 @@
+use std::f32::consts::FRAC_PI_2;
+
+use moon_engine::renderer::{InstanceData, Quad};
+use moon_engine::texture::SubTexture;
+use moon_engine::Color32;
+
+#[test]
+fn rotated_quad_turns_about_center() {
+    let quad = Quad::new_with_rotation(5.0, 1.0, FRAC_PI_2, 2.0, 4.0, &SubTexture::default());
+    let vertices = quad.get_vertices();
+    let center = vertices.iter().fold([0.0, 0.0], |sum, vertex| {
+        [
+            sum[0] + vertex.position[0] / 4.0,
+            sum[1] + vertex.position[1] / 4.0,
+        ]
+    });
+    assert!((center[0] - 5.0).abs() < 1e-6 && (center[1] - 1.0).abs() < 1e-6);
+
+    // The first corner, (-1, 2) from the center, is rotated to (-2, -1)
+    let corner = vertices[0].position;
+    assert!((corner[0] - 3.0).abs() < 1e-6 && (corner[1] - 0.0).abs() < 1e-6);
+    assert_eq!(vertices[0].uv, SubTexture::default().get_uv_coords()[0])
+}
+
+#[test]
+fn tint_multiplies_vertex_color() {
+    let quad =
+        Quad::new_from_position_and_size_and_color(0.0, 0.0, 1.0, 1.0, Color32(0.5, 1.0, 1.0, 1.0))
+            .with_tint(Color32(1.0, 0.5, 0.0, 0.5));
+    for vertex in quad.get_vertices() {
+        assert_eq!(vertex.color, [0.5, 0.5, 0.0, 0.5]);
+    }
+}
+
+#[test]
+fn instance_layout_matches_struct() {
+    let layout = InstanceData::layout();
+    assert_eq!(layout.stride, 52);
+    assert_eq!(layout.divisor, 1);
+    let locations: Vec<u32> = layout
+        .attributes
+        .iter()
+        .map(|attribute| attribute.location)
+        .collect();
+    assert_eq!(locations, vec![3, 4, 5, 6, 7]);
+}
+
+#[test]
+fn instance_quad_uses_region_and_color() {
+    let instance = InstanceData {
+        rotation: FRAC_PI_2,
+        uv: [0.5, 1.0, 0.0, 0.5],
+        color: [1.0, 0.5, 0.0, 1.0],
+        ..InstanceData::new(5.0, 1.0, 2.0, 4.0)
+    };
+    let quad = instance.quad(&SubTexture::default());
+    let expected = Quad::new_with_rotation(5.0, 1.0, FRAC_PI_2, 2.0, 4.0, &SubTexture::default());
+    for (vertex, expected) in quad.get_vertices().iter().zip(expected.get_vertices()) {
+        assert_eq!(vertex.position, expected.position);
+        assert_eq!(vertex.color, [1.0, 0.5, 0.0, 1.0]);
+    }
+    assert_eq!(quad.get_vertices()[0].uv, [0.5, 0.0]);
+    assert_eq!(quad.get_vertices()[2].uv, [1.0, 0.5]);
+}
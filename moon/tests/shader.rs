@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use moon_engine::shader::{annotate_log, preprocess};
+
+#[test]
+fn annotate_log_with_source_line() {
+    let source = "#version 300 es\nprecision highp float;\nvoid main() { broken }";
+    let log = annotate_log("ERROR: 0:3: 'broken' : undeclared identifier\n", source);
+    assert_eq!(
+        log,
+        "ERROR: 0:3: 'broken' : undeclared identifier\n   3 | void main() { broken }"
+    )
+}
+
+#[test]
+fn annotate_log_without_line_number() {
+    let log = annotate_log("Could not compile shader.", "void main() {}");
+    assert_eq!(log, "Could not compile shader.")
+}
+
+#[test]
+fn annotate_log_out_of_range() {
+    let log = annotate_log("ERROR: 0:9: unexpected end of file", "void main() {}");
+    assert_eq!(log, "ERROR: 0:9: unexpected end of file")
+}
+
+#[test]
+fn preprocess_nested_includes() {
+    let includes = BTreeMap::from([
+        (
+            "lighting",
+            "#include \"common\"\nvec3 light() { return vec3(PI); }",
+        ),
+        ("common", "#define PI 3.14159"),
+    ]);
+    let source = preprocess(
+        "#version 300 es\n#include \"lighting\"\nvoid main() {}",
+        &includes,
+    );
+    assert_eq!(
+        source.unwrap(),
+        "#version 300 es\n#define PI 3.14159\nvec3 light() { return vec3(PI); }\nvoid main() {}"
+    )
+}
+
+#[test]
+fn preprocess_cycle() {
+    let includes = BTreeMap::from([("a", "#include \"b\""), ("b", "#include \"a\"")]);
+    let error = preprocess("#include \"a\"", &includes).unwrap_err();
+    assert_eq!(error, "Cyclic shader include: a -> b -> a.")
+}
+
+#[test]
+fn preprocess_missing_include() {
+    let error = preprocess("#include \"noise\"", &BTreeMap::new()).unwrap_err();
+    assert_eq!(error, "Missing shader include \"noise\".")
+}
+
+#[test]
+fn preprocess_repeated_include() {
+    let includes = BTreeMap::from([("one", "1")]);
+    let source = preprocess("#include \"one\"\n#include \"one\"", &includes);
+    assert_eq!(source.unwrap(), "1\n1")
+}
+
+#[test]
+fn define_after_version() {
+    use moon_engine::shader::define;
+
+    let source = define("#version 300 es\nvoid main() {}", &["A", "B"]);
+    assert_eq!(
+        source,
+        "#version 300 es\n#define A\n#define B\nvoid main() {}"
+    );
+    assert_eq!(
+        define("void main() {}", &["A"]),
+        "#define A\nvoid main() {}"
+    );
+    assert_eq!(define("void main() {}", &[]), "void main() {}")
+}
@@ -0,0 +1,85 @@
+use moon_engine::texture::*;
+use moon_engine::Color32;
+
+#[test]
+fn color_key_magenta() {
+    let mut pixels = vec![
+        255, 0, 255, 255, // Magenta
+        255, 0, 254, 255, // Almost magenta
+        0, 0, 0, 128, // Black
+        255, 0, 255, 200, // Magenta, partially transparent
+    ];
+    apply_color_key(&mut pixels, Color32::MAGENTA);
+    assert_eq!(
+        pixels,
+        vec![255, 0, 255, 0, 255, 0, 254, 255, 0, 0, 0, 128, 255, 0, 255, 0]
+    )
+}
+
+#[test]
+fn color_key_default_options() {
+    assert!(TextureOptions::default().color_key.is_none())
+}
+
+#[test]
+fn texture_units_distinct() {
+    let mut units = TextureUnits::new();
+    let albedo = units.allocate("uTex0").unwrap();
+    let palette = units.allocate("uPalette").unwrap();
+    assert_ne!(albedo, palette)
+}
+
+#[test]
+fn texture_units_same_sampler() {
+    let mut units = TextureUnits::new();
+    let first = units.allocate("uTex0");
+    assert_eq!(units.allocate("uTex0"), first)
+}
+
+#[test]
+fn texture_units_exhausted() {
+    let mut units = TextureUnits::new_with_max_units(1);
+    assert_eq!(units.allocate("uTex0"), Some(0));
+    assert_eq!(units.allocate("uTex1"), None)
+}
+
+#[test]
+fn texture_units_free() {
+    let mut units = TextureUnits::new_with_max_units(2);
+    units.allocate("uTex0");
+    units.allocate("uTex1");
+    units.free("uTex0");
+    assert_eq!(units.allocate("uTex2"), Some(0))
+}
+
+#[test]
+fn flipped_x_swaps_u() {
+    let sprite = SubTexture::default();
+    let flipped = sprite.flipped_x();
+    let (uv, flipped_uv) = (sprite.get_uv_coords(), flipped.get_uv_coords());
+    for (corner, flipped_corner) in uv.iter().zip(flipped_uv) {
+        assert_eq!(flipped_corner, [1.0 - corner[0], corner[1]]);
+    }
+    assert_eq!(flipped.flipped_x().get_uv_coords(), uv)
+}
+
+#[test]
+fn mipmaps_pick_mipmapped_filter() {
+    use moon_engine::gl::GL;
+
+    let options = TextureOptions::default();
+    assert_eq!(options.effective_min_filter(), GL::NEAREST);
+    assert!(!options.generate_mipmaps);
+
+    let mipmapped = TextureOptions {
+        generate_mipmaps: true,
+        ..options
+    };
+    assert_eq!(mipmapped.effective_min_filter(), GL::NEAREST_MIPMAP_LINEAR);
+
+    let explicit = TextureOptions {
+        min_filter: GL::LINEAR_MIPMAP_NEAREST,
+        ..mipmapped
+    };
+    assert_eq!(explicit.effective_min_filter(), GL::LINEAR_MIPMAP_NEAREST)
+}
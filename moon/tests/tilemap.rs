@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use moon_engine::collider::AABB;
+use moon_engine::texture::SubTexture;
+use moon_engine::tilemap::*;
+use moon_engine::Vec2;
+
+#[test]
+fn world_to_tile_inside() {
+    let tile = TileMap::world_to_tile(Vec2::new(3.5, 5.2), 2.0);
+    assert_eq!(tile, (1, 2))
+}
+
+#[test]
+fn world_to_tile_boundary() {
+    let tile = TileMap::world_to_tile(Vec2::new(4.0, 2.0), 2.0);
+    assert_eq!(tile, (2, 1))
+}
+
+#[test]
+fn world_to_tile_negative() {
+    let tile = TileMap::world_to_tile(Vec2::new(-0.5, -2.0), 2.0);
+    assert_eq!(tile, (-1, -1))
+}
+
+#[test]
+fn tile_to_world_origin() {
+    let world = TileMap::tile_to_world(2, -1, 2.0);
+    assert_eq!(world, Vec2::new(4.0, -2.0))
+}
+
+fn test_map() -> TileMap {
+    let sprite = Rc::new(SubTexture::default());
+    let mut tiles = BTreeMap::new();
+    // A solid tile filling its whole cell at (0, 0)
+    tiles.insert(
+        0,
+        Tile::new_with_collider(
+            1,
+            "Solid",
+            Rc::clone(&sprite),
+            AABB::new_position_and_size(1.0, 1.0, 2.0, 2.0),
+        ),
+    );
+    // A decorative tile with no collider at (1, 0)
+    tiles.insert(1, Tile::new(2, "Decoration", sprite));
+    TileMap::new_with_tiles(2, 2, tiles)
+}
+
+#[test]
+fn point_collides_solid() {
+    let map = test_map();
+    assert!(map.point_collides(Vec2::new(0.5, 1.5), 2.0))
+}
+
+#[test]
+fn point_collides_empty() {
+    let map = test_map();
+    assert!(!map.point_collides(Vec2::new(0.5, 2.5), 2.0))
+}
+
+#[test]
+fn point_collides_no_collider() {
+    let map = test_map();
+    assert!(!map.point_collides(Vec2::new(2.5, 0.5), 2.0))
+}
+
+#[test]
+fn point_collides_outside() {
+    let map = test_map();
+    assert!(!map.point_collides(Vec2::new(-0.5, 0.5), 2.0))
+}
+
+#[test]
+fn flood_fill_connected_region() {
+    let sprite = Rc::new(SubTexture::default());
+    let mut map = TileMap::new(5, 4);
+    // A wall splitting the map at x = 2
+    for y in 0..4 {
+        map.set_tile(2, y, Tile::new(9, "Wall", Rc::clone(&sprite)));
+    }
+
+    let filled = map.flood_fill(0, 0, Tile::new(1, "Grass", sprite));
+    assert_eq!(filled, 8);
+    assert_eq!(map.get_tile(1, 3).map(|tile| tile.id), Some(1));
+    assert_eq!(map.get_tile(2, 0).map(|tile| tile.id), Some(9));
+    assert!(map.get_tile(3, 0).is_none());
+    assert_eq!(map.tile_count(), 12)
+}
+
+#[test]
+fn flood_fill_same_id() {
+    let sprite = Rc::new(SubTexture::default());
+    let mut map = TileMap::new(2, 2);
+    map.flood_fill(0, 0, Tile::new(1, "Grass", Rc::clone(&sprite)));
+    assert_eq!(map.flood_fill(1, 1, Tile::new(1, "Grass", sprite)), 0)
+}
+
+#[test]
+fn spatial_grid_from_colliders() {
+    let map = test_map();
+    let grid = map.spatial_grid(2.0);
+    assert_eq!(grid.len(), 1);
+    assert_eq!(grid.query_region(AABB::new_position(1.0, 1.0)), vec![0]);
+    assert!(grid.query_region(AABB::new_position(3.0, 1.0)).is_empty())
+}
+
+#[test]
+fn quads_skip_empty_cells() {
+    let map = test_map();
+    let quads = map.quads(2.0);
+    assert_eq!(quads.len(), 2);
+
+    let positions: Vec<[f32; 2]> = quads[1]
+        .get_vertices()
+        .iter()
+        .map(|vertex| vertex.position)
+        .collect();
+    assert_eq!(
+        positions,
+        vec![[2.0, 2.0], [2.0, 0.0], [4.0, 0.0], [4.0, 2.0]]
+    )
+}
+
+fn palette() -> BTreeMap<u32, Tile> {
+    let sprite = Rc::new(SubTexture::default());
+    let mut palette = BTreeMap::new();
+    palette.insert(1, Tile::new(1, "Grass", Rc::clone(&sprite)));
+    palette.insert(2, Tile::new(2, "Stone", sprite));
+    palette
+}
+
+#[test]
+fn from_csv_infers_size_and_skips_holes() {
+    let map = TileMap::from_csv("1,2,-1\n,1\n", &palette()).unwrap();
+    assert_eq!((map.width, map.height), (3, 2));
+    assert_eq!(map.tile_count(), 3);
+    assert_eq!(map.get_tile(1, 0).map(|tile| tile.name), Some("Stone"));
+    assert!(map.get_tile(0, 1).is_none())
+}
+
+#[test]
+fn from_csv_rejects_unknown_ids() {
+    assert!(TileMap::from_csv("1,3", &palette()).is_err());
+    assert!(TileMap::from_csv("1,x", &palette()).is_err())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn from_json_matches_csv() {
+    let map = TileMap::from_json("[[1, 2, -1], [null, 1]]", &palette()).unwrap();
+    assert_eq!((map.width, map.height), (3, 2));
+    assert_eq!(map.tile_count(), 3);
+    assert_eq!(map.get_tile(1, 1).map(|tile| tile.id), Some(1));
+    assert!(TileMap::from_json("{}", &palette()).is_err())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn to_json_round_trips() {
+    let map = TileMap::from_csv("1,2,-1\n,1,\n-1,-1,-1", &palette()).unwrap();
+    let json = map.to_json();
+    assert_eq!(json, "[[1,2,null],[null,1,null],[null,null,null]]");
+
+    let loaded = TileMap::from_json(&json, &palette()).unwrap();
+    assert_eq!((loaded.width, loaded.height), (3, 3));
+    assert_eq!(loaded.tile_count(), map.tile_count());
+    assert_eq!(loaded.get_tile(1, 0).map(|tile| tile.name), Some("Stone"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_stores_palette_ids() {
+    let map = TileMap::from_csv("1,2\n,1", &palette()).unwrap();
+    let json = serde_json::to_string(&map).unwrap();
+    assert_eq!(json, r#"{"width":2,"height":2,"tiles":[[1,2],[null,1]]}"#);
+
+    let data: TileMapData = serde_json::from_str(&json).unwrap();
+    assert_eq!(data, map.to_data());
+    let loaded = TileMap::from_data(&data, &palette()).unwrap();
+    assert_eq!(loaded.get_tile(1, 0).map(|tile| tile.id), Some(2));
+    assert!(loaded.get_tile(0, 1).is_none());
+    assert!(TileMap::from_data(&data, &BTreeMap::new()).is_err());
+}
+
+#[test]
+fn tile_accessors_bounds_checked() {
+    let mut map = test_map();
+    let sprite = Rc::new(SubTexture::default());
+    map.set_tile(2, 0, Tile::new(3, "Outside", Rc::clone(&sprite)));
+    map.set_tile(-1, 0, Tile::new(3, "Outside", sprite));
+    assert_eq!(map.tile_count(), 2);
+    assert!(map.get_tile(0, 5).is_none());
+
+    assert_eq!(map.remove_tile(1, 0).map(|tile| tile.id), Some(2));
+    assert!(map.remove_tile(1, 0).is_none());
+    assert!(map.remove_tile(7, 7).is_none());
+    assert_eq!(map.tile_count(), 1)
+}
+
+#[test]
+fn neighbors_at_edge() {
+    let map = test_map();
+    let ids = map.neighbors(0, 0).map(|tile| tile.map(|tile| tile.id));
+    assert_eq!(ids, [None, Some(2), None, None]);
+    let ids = map.neighbors(1, 1).map(|tile| tile.map(|tile| tile.id));
+    assert_eq!(ids, [Some(2), None, None, None])
+}
+
+#[test]
+fn neighbors_8_include_diagonals() {
+    let map = test_map();
+    let ids = map.neighbors_8(0, 1).map(|tile| tile.map(|tile| tile.id));
+    assert_eq!(ids, [Some(1), Some(2), None, None, None, None, None, None]);
+    assert!(map.neighbors_8(5, 5).iter().all(Option::is_none))
+}
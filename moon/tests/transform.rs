@@ -0,0 +1,198 @@
+use std::f32::consts::FRAC_PI_2;
+
+use moon_engine::transform::*;
+use moon_engine::{Quat, Vec2, Vec3, Vec4};
+
+#[test]
+fn transform_2d_matrix_rotates_and_scales() {
+    let transform = Transform2D {
+        position: Vec2::zeros(),
+        rotation: FRAC_PI_2,
+        scale: Vec2::new(2.0, 1.0),
+    };
+    let x = transform.matrix() * Vec4::new(1.0, 0.0, 0.0, 0.0);
+    assert!((x - Vec4::new(0.0, 2.0, 0.0, 0.0)).norm() < 1e-6)
+}
+
+#[test]
+fn transform_2d_matrix_translates() {
+    let transform = Transform2D::new_with_position(3.0, -1.0);
+    let origin = transform.matrix() * Vec4::new(0.0, 0.0, 0.0, 1.0);
+    assert_eq!(origin, Vec4::new(3.0, -1.0, 0.0, 1.0))
+}
+
+#[test]
+fn transform_2d_lerp_is_clamped() {
+    let from = Transform2D::new_with_position(0.0, 0.0);
+    let to = Transform2D {
+        position: Vec2::new(4.0, -2.0),
+        rotation: 1.0,
+        scale: Vec2::new(3.0, 1.0),
+    };
+    let halfway = from.lerp(&to, 0.5);
+    assert_eq!(halfway.position, Vec2::new(2.0, -1.0));
+    assert_eq!(halfway.rotation, 0.5);
+    assert_eq!(halfway.scale, Vec2::new(2.0, 1.0));
+    assert_eq!(from.lerp(&to, 2.0).position, to.position)
+}
+
+#[test]
+fn euler_rotation_about_y() {
+    let mut transform = Transform::new();
+    transform.set_rotation_euler(0.0, FRAC_PI_2, 0.0);
+    let z = transform.matrix() * Vec4::new(0.0, 0.0, 1.0, 0.0);
+    assert!((z - Vec4::new(1.0, 0.0, 0.0, 0.0)).norm() < 1e-6)
+}
+
+#[test]
+fn z_rotation_matches_euler() {
+    let mut z_only = Transform::new();
+    z_only.set_rotation(0.5);
+    let mut euler = Transform::new();
+    euler.set_rotation_euler(0.0, 0.0, 0.5);
+    assert_eq!(z_only.matrix(), euler.matrix());
+    assert_eq!(z_only.get_rotation(), 0.5)
+}
+
+#[test]
+fn rotate_about_axis_accumulates() {
+    let mut transform = Transform::new();
+    transform.rotate(Vec3::y(), FRAC_PI_2 / 2.0);
+    transform.rotate(Vec3::y(), FRAC_PI_2 / 2.0);
+    let z = transform.matrix() * Vec4::new(0.0, 0.0, 1.0, 0.0);
+    assert!((z - Vec4::new(1.0, 0.0, 0.0, 0.0)).norm() < 1e-6)
+}
+
+#[test]
+fn set_rotation_quat_syncs_euler() {
+    let mut transform = Transform::new();
+    transform.set_rotation_quat(Quat::from_axis_angle(&Vec3::z_axis(), 0.5));
+    assert!((transform.rotation() - Vec3::new(0.0, 0.0, 0.5)).norm() < 1e-6)
+}
+
+#[test]
+fn slerp_halfway() {
+    let from = Transform::new();
+    let mut to = Transform::new();
+    to.set_rotation(FRAC_PI_2);
+    let halfway = from.slerp(&to, 0.5);
+    let expected = Quat::from_axis_angle(&Vec3::z_axis(), FRAC_PI_2 / 2.0);
+    assert!(halfway.angle_to(&expected) < 1e-5)
+}
+
+#[test]
+fn world_matrix_applies_parent() {
+    let parent = Transform::new_with_position(Vec3::new(1.0, 2.0, 0.0));
+    let child = Transform::new_with_position(Vec3::new(0.5, 0.0, 0.0));
+    let origin = child.world_matrix(&parent.matrix()) * Vec4::new(0.0, 0.0, 0.0, 1.0);
+    assert_eq!(origin, Vec4::new(1.5, 2.0, 0.0, 1.0));
+    assert_eq!(
+        child.matrix(),
+        Transform::new_with_position(Vec3::new(0.5, 0.0, 0.0)).matrix()
+    )
+}
+
+#[test]
+fn scene_moves_children_with_parent() {
+    let mut scene = Scene::new();
+    let root = scene.add(Transform::new(), None);
+    let child = scene.add(Transform::new_with_position(Vec3::x()), Some(root));
+    let grandchild = scene.add(Transform::new_with_position(Vec3::y()), Some(child));
+    assert_eq!(scene.parent(grandchild), Some(child));
+
+    scene
+        .get_mut(root)
+        .unwrap()
+        .set_position(Vec3::new(10.0, 0.0, 0.0));
+    let matrices = scene.world_matrices();
+    let origin = matrices[grandchild] * Vec4::new(0.0, 0.0, 0.0, 1.0);
+    assert_eq!(origin, Vec4::new(11.0, 1.0, 0.0, 1.0))
+}
+
+#[test]
+fn setters_rebuild_matrix_lazily() {
+    let mut transform = Transform::new();
+    transform.set_position(Vec3::new(1.0, 0.0, 0.0));
+    transform.set_scale(Vec3::new(2.0, 2.0, 2.0));
+    transform.position_mut().y = 3.0;
+    let point = transform.matrix() * Vec4::new(1.0, 0.0, 0.0, 1.0);
+    assert_eq!(point, Vec4::new(3.0, 3.0, 0.0, 1.0));
+
+    transform.rotation_mut().z = FRAC_PI_2;
+    let point = transform.matrix() * Vec4::new(1.0, 0.0, 0.0, 1.0);
+    assert!((point - Vec4::new(1.0, 5.0, 0.0, 1.0)).norm() < 1e-6)
+}
+
+#[test]
+fn look_at_points_forward_at_target() {
+    let mut transform = Transform::new_with_position(Vec3::new(1.0, 0.0, 0.0));
+    transform.look_at(Vec3::new(1.0, 0.0, 5.0), Vec3::y());
+    assert!((transform.forward() - Vec3::z()).norm() < 1e-6);
+
+    transform.look_at(Vec3::new(4.0, 0.0, 0.0), Vec3::y());
+    assert!((transform.forward() - Vec3::x()).norm() < 1e-6)
+}
+
+#[test]
+fn look_at_own_position_keeps_rotation() {
+    let mut transform = Transform::new();
+    transform.set_rotation(0.5);
+    transform.look_at(Vec3::zeros(), Vec3::y());
+    assert!((transform.rotation() - Vec3::new(0.0, 0.0, 0.5)).norm() < 1e-6)
+}
+
+#[test]
+fn translate_and_rotate_euler_accumulate() {
+    let mut transform = Transform::new();
+    transform.translate(Vec3::new(1.0, 0.0, 0.0));
+    transform.translate(Vec3::new(0.0, 2.0, 0.0));
+    transform.rotate_euler(Vec3::new(0.0, 0.0, 0.25));
+    transform.rotate_euler(Vec3::new(0.0, 0.0, 0.25));
+    assert_eq!(transform.position(), Vec3::new(1.0, 2.0, 0.0));
+    assert!((transform.rotation() - Vec3::new(0.0, 0.0, 0.5)).norm() < 1e-6);
+
+    let origin = transform.matrix() * Vec4::new(0.0, 0.0, 0.0, 1.0);
+    assert_eq!(origin, Vec4::new(1.0, 2.0, 0.0, 1.0))
+}
+
+#[test]
+fn basis_vectors_follow_rotation() {
+    let mut transform = Transform::new();
+    assert_eq!(transform.forward(), -Vec3::z());
+    assert_eq!(transform.right(), Vec3::x());
+    assert_eq!(transform.up(), Vec3::y());
+
+    transform.set_rotation_euler(0.0, FRAC_PI_2, 0.0);
+    transform.set_scale(Vec3::new(3.0, 3.0, 3.0));
+    assert!((transform.forward() + Vec3::x()).norm() < 1e-6);
+    assert!((transform.right() + Vec3::z()).norm() < 1e-6);
+    assert!((transform.up() - Vec3::y()).norm() < 1e-6);
+    assert!((transform.right().cross(&transform.up()) + transform.forward()).norm() < 1e-6)
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn transform_serde_round_trip() {
+    let mut transform = Transform::new_with_position(Vec3::new(1.0, 2.0, 3.0));
+    transform.set_rotation_euler(0.1, 0.2, 0.3);
+    transform.set_scale(Vec3::new(2.0, 2.0, 1.0));
+
+    let json = serde_json::to_string(&transform).unwrap();
+    assert!(!json.contains("matrix"));
+    let loaded: Transform = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.position(), transform.position());
+    assert_eq!(loaded.scale(), transform.scale());
+    assert!((loaded.matrix() - transform.matrix()).abs().max() < 1e-6);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn transform_2d_serde_round_trip() {
+    let mut transform = Transform2D::new_with_position(4.0, -1.0);
+    transform.rotation = 0.5;
+    let json = serde_json::to_string(&transform).unwrap();
+    let loaded: Transform2D = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.position, transform.position);
+    assert_eq!(loaded.rotation, transform.rotation);
+    assert_eq!(loaded.scale, transform.scale);
+}
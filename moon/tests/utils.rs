@@ -0,0 +1,69 @@
+use moon_engine::utils::*;
+
+fn variance(values: &[f32]) -> f32 {
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f32>()
+        / values.len() as f32
+}
+
+#[test]
+fn smoothed_delta_lower_variance() {
+    let noisy = [
+        0.016, 0.018, 0.014, 0.050, 0.016, 0.012, 0.020, 0.015, 0.017, 0.001, 0.016, 0.019, 0.013,
+        0.016, 0.040, 0.016,
+    ];
+    let mut smoother = DeltaSmoother::new();
+    let smoothed: Vec<f32> = noisy.iter().map(|delta| smoother.smooth(*delta)).collect();
+    assert!(variance(&smoothed) < variance(&noisy))
+}
+
+#[test]
+fn smoothed_delta_constant() {
+    let mut smoother = DeltaSmoother::new_with_frames(4);
+    for _ in 0..10 {
+        assert!((smoother.smooth(0.016) - 0.016).abs() < 1e-6)
+    }
+}
+
+#[test]
+fn fixed_timestep_accumulates_frames() {
+    let mut timestep = FixedTimestep::new_with_step(0.01);
+    assert_eq!(timestep.advance(0.004), 0);
+    assert_eq!(timestep.advance(0.004), 0);
+    assert_eq!(timestep.advance(0.004), 1);
+    assert!((timestep.alpha() - 0.2).abs() < 1e-4);
+
+    // The same total time gives the same steps, whatever the frame rate
+    let mut slow = FixedTimestep::new_with_step(1.0 / 60.0);
+    let mut fast = FixedTimestep::new_with_step(1.0 / 60.0);
+    let slow_steps: u32 = (0..30).map(|_| slow.advance(1.0 / 30.0)).sum();
+    let fast_steps: u32 = (0..144).map(|_| fast.advance(1.0 / 144.0)).sum();
+    assert_eq!(slow_steps, 60);
+    assert!((fast_steps as i32 - 60).abs() <= 1);
+}
+
+#[test]
+fn fixed_timestep_caps_slow_frames() {
+    let mut timestep = FixedTimestep::new_with_step(0.01);
+    assert_eq!(timestep.advance(1.0), MAX_STEPS_PER_FRAME);
+    assert_eq!(timestep.alpha(), 0.0);
+}
+
+#[test]
+fn frame_timer_averages_last_frames() {
+    let mut timer = FrameTimer::new_with_frames(4);
+    assert_eq!(timer.fps(), 0.0);
+    timer.record(1.0);
+    for _ in 0..4 {
+        timer.record(0.01);
+    }
+    // The slow first frame has left the ring buffer
+    assert!((timer.average_frame_ms() - 10.0).abs() < 1e-3);
+    assert!((timer.fps() - 100.0).abs() < 1e-2);
+
+    timer.reset();
+    assert_eq!(timer.average_frame_ms(), 0.0);
+}
@@ -21,3 +21,1054 @@ fn can_get_context() {
 
     let canvas = get_context();
 }
+
+#[wasm_bindgen_test]
+fn palette_uses_second_texture_unit() {
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{Renderer, PALETTE_SAMPLER, SPRITE_SAMPLER};
+    use moon_engine::texture::Texture;
+
+    let mut renderer = Renderer::default();
+    let palette = Texture::white(&renderer.gl);
+    renderer.set_palette(palette);
+
+    let unit = renderer.texture_units.get(PALETTE_SAMPLER).unwrap();
+    assert_eq!(renderer.get_palette().unwrap().slot, unit);
+    assert_eq!(renderer.texture_units.get(SPRITE_SAMPLER), Some(0));
+    assert_eq!(unit, 1);
+
+    renderer.gl.active_texture(GL::TEXTURE1);
+    assert!(!renderer
+        .gl
+        .get_parameter(GL::TEXTURE_BINDING_2D)
+        .unwrap()
+        .is_null());
+}
+
+#[wasm_bindgen_test]
+fn step_moves_smoke_under_held_input() {
+    use moon_engine::particle::{ParticleProps, ParticleSystem};
+    use moon_engine::Application;
+
+    let mut app = Application::new();
+    let smoke = ParticleSystem::new_from_emission_and_position(ParticleProps::smoke(), 5.0, 0.0);
+    app.scene_mut().add_component("SMOKE", Box::new(smoke));
+    app.scene_mut().init_components();
+
+    app.input(b'D', true);
+    for _ in 0..10 {
+        app.step(0.1);
+    }
+
+    let smoke = app
+        .scene()
+        .get_component::<ParticleSystem>("SMOKE")
+        .unwrap();
+    assert!((smoke.transform.position.x - 6.0).abs() < 1e-4);
+    assert_eq!(smoke.transform.position.y, 0.0);
+}
+
+#[wasm_bindgen_test]
+fn mesh_buffers_set_attribute_divisors() {
+    use moon_engine::gl::GL;
+    use moon_engine::mesh::{BufferLayout, Mesh};
+
+    let gl = get_context();
+    let mut mesh = Mesh::quad(&gl);
+    mesh.add_buffer(
+        &gl,
+        BufferLayout::new(16, 1).with_attribute(3, 4, 0),
+        vec![0; 16],
+    );
+    mesh.setup(&gl);
+
+    let divisor = |location: u32| {
+        gl.get_vertex_attrib(location, GL::VERTEX_ATTRIB_ARRAY_DIVISOR)
+            .unwrap()
+            .as_f64()
+            .unwrap()
+    };
+    assert_eq!(divisor(0), 0.0);
+    assert_eq!(divisor(3), 1.0);
+}
+
+#[wasm_bindgen_test]
+fn log_depth_sets_uniform() {
+    use moon_engine::renderer::Renderer;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.set_log_depth(true);
+
+    let gl = &renderer.gl;
+    let program = renderer.program.program().unwrap();
+    let location = renderer
+        .program
+        .get_uniform_location(gl, "uLogDepthFC")
+        .unwrap();
+    let value = gl.get_uniform(program, &location).as_f64().unwrap() as f32;
+
+    assert!(value > 0.0);
+    assert!((value - renderer.log_depth_coefficient()).abs() < 1e-6);
+}
+
+#[wasm_bindgen_test]
+fn tonemap_pass_runs_once() {
+    use moon_engine::renderer::Renderer;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.enable_tonemapping(2.0);
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.end_frame();
+    assert_eq!(renderer.post_passes(), 1);
+
+    let gl = &renderer.gl;
+    let shader = renderer.tonemap().unwrap().shader();
+    let location = shader.get_uniform_location(gl, "uExposure").unwrap();
+    let exposure = gl.get_uniform(shader.program().unwrap(), &location);
+    assert_eq!(exposure.as_f64(), Some(2.0));
+}
+
+#[wasm_bindgen_test]
+fn application_input_reaches_manager() {
+    use moon_engine::input::Key;
+    use moon_engine::Application;
+
+    let mut app = Application::new();
+    app.input(b'W', true);
+    app.input_key("a", true);
+    assert!(app.input_manager().get_key_state(Key::W));
+    assert!(app.input_manager().get_key_state(Key::A));
+
+    app.input(b'W', false);
+    assert!(!app.input_manager().get_key_state(Key::W));
+}
+
+#[wasm_bindgen_test]
+fn outlined_sprite_drawn_twice() {
+    use moon_engine::collider::AABB;
+    use moon_engine::renderer::Renderer;
+    use moon_engine::texture::{SubTexture, Texture};
+    use moon_engine::Color32;
+    use std::rc::Rc;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    let sprite = SubTexture::new(Rc::new(Texture::white(&renderer.gl)));
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.draw_sprite_outlined(&sprite, &AABB::default(), Color32::MAGENTA, 2.0);
+    assert_eq!(renderer.draw_calls(), 2);
+}
+
+#[wasm_bindgen_test]
+fn render_target_uses_render_resolution() {
+    use moon_engine::renderer::Renderer;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.set_render_resolution(320, 180);
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    let target = renderer.render_target().unwrap();
+    assert_eq!((target.width(), target.height()), (320, 180));
+
+    renderer.end_frame();
+    assert_eq!(renderer.post_passes(), 1);
+}
+
+#[wasm_bindgen_test]
+fn dynamic_mesh_streams_changed_range() {
+    use moon_engine::mesh::{DynamicMesh, Vertex};
+
+    let gl = get_context();
+    let mut mesh = DynamicMesh::new(&gl, vec![Vertex::default(); 100], vec![0, 1, 2]);
+    mesh.draw(&gl);
+    assert_eq!(mesh.last_upload(), Some(0..3200));
+
+    mesh.vertex_mut(10).unwrap().position = [1.0, 0.0];
+    mesh.vertex_mut(11).unwrap().position = [0.0, 1.0];
+    assert_eq!(mesh.dirty_range(), Some(320..384));
+
+    mesh.draw(&gl);
+    assert_eq!(mesh.last_upload(), Some(320..384));
+    assert_eq!(mesh.dirty_range(), None);
+}
+
+#[wasm_bindgen_test]
+fn light_pass_runs_with_lights() {
+    use moon_engine::light::Light;
+    use moon_engine::renderer::Renderer;
+    use moon_engine::{Color32, Vec2};
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.add_light(Light::new(Vec2::new(1.0, 1.0), 2.0, Color32::WHITE));
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.end_frame();
+    assert_eq!(renderer.post_passes(), 1);
+    assert_eq!(renderer.draw_calls(), 1);
+
+    renderer.clear_lights();
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.end_frame();
+    assert_eq!(renderer.post_passes(), 0);
+}
+
+#[wasm_bindgen_test]
+fn resize_updates_camera_aspect() {
+    use moon_engine::renderer::Renderer;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.resize(400.0, 100.0);
+    let projection = renderer.camera.projection();
+    assert!((projection[5].abs() / projection[0] - 4.0).abs() < 1e-4);
+}
+
+#[wasm_bindgen_test]
+fn camera_shake_offsets_view_only() {
+    use moon_engine::camera::{Camera, MAX_SHAKE_OFFSET};
+    use moon_engine::Vec3;
+
+    let mut camera = Camera::new();
+    camera.add_shake(1.0);
+    camera.update_shake(0.0);
+    assert!(camera.shake_offset().x.abs() <= MAX_SHAKE_OFFSET);
+    assert!(camera.shake_offset().y.abs() <= MAX_SHAKE_OFFSET);
+    assert_eq!(camera.transform.position(), Vec3::zeros());
+}
+
+#[wasm_bindgen_test]
+fn reload_shaders_keeps_program_on_failure() {
+    use moon_engine::Application;
+
+    let mut app = Application::new();
+    app.init();
+    let result = app.reload_shaders(
+        include_str!("../res/shader/default.vert.glsl"),
+        "#version 300 es\nvoid main() { broken }",
+    );
+    assert!(result.is_err());
+    assert_eq!(app.renderer().unwrap().program.name, "Default Shader");
+
+    let result = app.reload_shaders(
+        include_str!("../res/shader/default.vert.glsl"),
+        include_str!("../res/shader/default.frag.glsl"),
+    );
+    assert!(result.is_ok());
+    assert_eq!(app.renderer().unwrap().program.name, "Reloaded Shader");
+}
+
+#[wasm_bindgen_test]
+fn broken_shader_log_mentions_error() {
+    use moon_engine::shader::Shader;
+
+    let gl = get_context();
+    let error = Shader::create_fragment(
+        &gl,
+        "#version 300 es\nprecision highp float;\nvoid main() { broken; }",
+    )
+    .unwrap_err();
+    assert!(error.contains("ERROR"));
+    assert!(error.contains("   3 | void main() { broken; }"));
+}
+
+#[wasm_bindgen_test]
+fn mesh_index_bytes_use_smallest_type() {
+    use moon_engine::mesh::{IndexType, Mesh, Vertex};
+
+    let gl = get_context();
+    let small = Mesh::new(&gl, vec![Vertex::default(); 4], vec![0, 1, 2, 0, 2, 3]);
+    assert_eq!(small.index_type(), IndexType::U16);
+    assert_eq!(small.index_bytes().len(), 12);
+
+    let large = Mesh::new(&gl, vec![Vertex::default(); 70000], vec![0, 1, 69999]);
+    assert_eq!(large.index_type(), IndexType::U32);
+    assert_eq!(large.index_bytes()[8..], 69999u32.to_ne_bytes());
+}
+
+#[wasm_bindgen_test]
+fn mesh_draws_without_errors() {
+    use moon_engine::gl::check_gl_error;
+    use moon_engine::mesh::Mesh;
+    use moon_engine::shader::Shader;
+
+    let gl = get_context();
+    let shader = Shader::new(&gl);
+    gl.use_program(shader.program());
+    let mesh = Mesh::quad(&gl);
+    mesh.setup(&gl);
+    mesh.draw(&gl);
+    assert!(!check_gl_error(&gl));
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen_test]
+fn tiled_map_loads_first_tile_layer() {
+    use moon_engine::texture::Texture;
+    use moon_engine::tiled::load_tiled;
+    use std::rc::Rc;
+
+    let gl = get_context();
+    let atlas = Rc::new(Texture::white(&gl));
+    let json = r#"{
+        "width": 2, "height": 2, "tilewidth": 16, "tileheight": 16,
+        "layers": [{ "type": "tilelayer", "data": [1, 0, 2147483650, 1] }],
+        "tilesets": [{ "firstgid": 1, "columns": 2, "imagewidth": 32, "imageheight": 16 }]
+    }"#;
+    let tiled = load_tiled(json, atlas).unwrap();
+    assert_eq!((tiled.tile_width, tiled.tile_height), (16, 16));
+    assert_eq!(tiled.tilemap.tile_count(), 3);
+    assert!(tiled.tilemap.get_tile(1, 0).is_none());
+
+    let flipped = tiled.tilemap.get_tile(0, 1).unwrap();
+    assert_eq!(flipped.id, 2);
+    assert_eq!(flipped.sprite.get_uv_coords()[0], [0.5, 0.0]);
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen_test]
+fn tiled_map_requires_tile_layer() {
+    use moon_engine::texture::Texture;
+    use moon_engine::tiled::load_tiled;
+    use std::rc::Rc;
+
+    let atlas = Rc::new(Texture::white(&get_context()));
+    let json = r#"{ "width": 1, "height": 1, "tilewidth": 8, "tileheight": 8, "layers": [] }"#;
+    assert!(load_tiled(json, atlas).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[wasm_bindgen_test]
+fn tiled_map_rejects_invalid_size() {
+    use moon_engine::texture::Texture;
+    use moon_engine::tiled::load_tiled;
+    use std::rc::Rc;
+
+    let atlas = Rc::new(Texture::white(&get_context()));
+    let tileset =
+        r#""tilesets": [{ "firstgid": 1, "columns": 1, "imagewidth": 8, "imageheight": 8 }]"#;
+    let zero_width = format!(
+        r#"{{ "width": 0, "height": 1, "tilewidth": 8, "tileheight": 8,
+        "layers": [{{ "type": "tilelayer", "data": [1] }}], {} }}"#,
+        tileset
+    );
+    assert!(load_tiled(&zero_width, Rc::clone(&atlas)).is_err());
+
+    let too_much_data = format!(
+        r#"{{ "width": 1, "height": 1, "tilewidth": 8, "tileheight": 8,
+        "layers": [{{ "type": "tilelayer", "data": [1, 1] }}], {} }}"#,
+        tileset
+    );
+    assert!(load_tiled(&too_much_data, atlas).is_err());
+}
+
+#[wasm_bindgen_test]
+fn batches_break_on_texture_change() {
+    use moon_engine::renderer::{Quad, Renderer, MAX_BATCH_QUADS};
+    use moon_engine::texture::{SubTexture, Texture};
+    use std::rc::Rc;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    let grass = SubTexture::new(Rc::new(Texture::white(&renderer.gl)));
+    let stone = SubTexture::new(Rc::new(Texture::checkerboard(&renderer.gl)));
+    let quad = Quad::new_from_position_and_size(0.0, 0.0, 1.0, 1.0);
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.begin_draw();
+    renderer.add_sprite_quad(&quad, &grass);
+    renderer.add_sprite_quad(&quad, &grass);
+    renderer.add_sprite_quad(&quad, &stone);
+    for _ in 0..MAX_BATCH_QUADS {
+        renderer.add_sprite_quad(&quad, &stone);
+    }
+    assert_eq!(renderer.batch_count(), 3);
+    renderer.end_draw();
+    assert_eq!(renderer.draw_calls(), 3);
+}
+
+#[wasm_bindgen_test]
+fn plain_quads_after_sprite_use_selected_texture() {
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{Quad, Renderer};
+    use moon_engine::texture::{SubTexture, Texture};
+    use std::rc::Rc;
+    use wasm_bindgen::JsValue;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    let stone = SubTexture::new(Rc::new(Texture::checkerboard(&renderer.gl)));
+    let quad = Quad::new_from_position_and_size(0.0, 0.0, 1.0, 1.0);
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.begin_draw();
+    renderer.add_sprite_quad(&quad, &stone);
+    renderer.add_quad(&quad);
+    assert_eq!(renderer.batch_count(), 2);
+    renderer.end_draw();
+
+    // The plain batch is drawn last, with WHITE rather than the sprite texture
+    let white = renderer.get_texture("WHITE");
+    renderer.gl.active_texture(GL::TEXTURE0);
+    let bound = renderer.gl.get_parameter(GL::TEXTURE_BINDING_2D).unwrap();
+    assert_eq!(bound, JsValue::from(white.texture().unwrap().clone()));
+
+    renderer.begin_draw();
+    renderer.add_quad(&quad);
+    renderer.use_texture("CHECKERBOARD");
+    renderer.add_quad(&quad);
+    assert_eq!(renderer.batch_count(), 2);
+}
+
+#[wasm_bindgen_test]
+fn render_stats_reset_each_frame() {
+    use moon_engine::renderer::{Quad, RenderStats, Renderer};
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    let quad = Quad::new_from_position_and_size(0.0, 0.0, 1.0, 1.0);
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.begin_draw();
+    renderer.add_quad(&quad);
+    renderer.add_quad(&quad);
+    renderer.end_draw();
+    renderer.draw_quads(&[quad]);
+    let stats = renderer.stats();
+    assert_eq!(stats.quad_count, 3);
+    assert_eq!(stats.batch_count, 2);
+    assert_eq!(stats.draw_calls, 2);
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    assert_eq!(renderer.stats(), RenderStats::default());
+}
+
+#[wasm_bindgen_test]
+fn blend_mode_sets_gl_state() {
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{BlendMode, Renderer};
+
+    let mut renderer = Renderer::default();
+    assert_eq!(renderer.blend_mode(), BlendMode::Alpha);
+
+    renderer.set_blend_mode(BlendMode::Additive);
+    assert!(renderer.gl.is_enabled(GL::BLEND));
+    let destination = renderer.gl.get_parameter(GL::BLEND_DST_RGB).unwrap();
+    assert_eq!(destination.as_f64(), Some(GL::ONE as f64));
+
+    renderer.set_blend_mode(BlendMode::None);
+    assert!(!renderer.gl.is_enabled(GL::BLEND));
+}
+
+#[wasm_bindgen_test]
+fn lines_drawn_in_one_call() {
+    use moon_engine::collider::AABB;
+    use moon_engine::renderer::Renderer;
+    use moon_engine::{Color32, Vec2};
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    for width in [None, Some(0.1)] {
+        renderer.set_line_width(width);
+        renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+        renderer.begin_draw();
+        renderer.draw_line(Vec2::zeros(), Vec2::new(1.0, 1.0), Color32::WHITE);
+        renderer.draw_aabb_outline(&AABB::default(), Color32::MAGENTA);
+        renderer.end_draw();
+        assert_eq!(renderer.draw_calls(), 1);
+        assert_eq!(get_context().get_error(), 0);
+    }
+}
+
+#[wasm_bindgen_test]
+fn texture_options_generate_mipmaps() {
+    use moon_engine::gl::GL;
+    use moon_engine::texture::{Texture, TextureOptions};
+
+    let gl = get_context();
+    let options = TextureOptions {
+        min_filter: GL::LINEAR,
+        mag_filter: GL::LINEAR,
+        wrap_s: GL::CLAMP_TO_EDGE,
+        generate_mipmaps: true,
+        ..Default::default()
+    };
+    let _texture = Texture::new_from_pixels_with_options(&gl, 2, 2, &[255; 16], &options);
+    let filter = gl
+        .get_tex_parameter(GL::TEXTURE_2D, GL::TEXTURE_MIN_FILTER)
+        .as_f64();
+    assert_eq!(filter, Some(GL::LINEAR_MIPMAP_LINEAR as f64));
+    assert_eq!(gl.get_error(), GL::NO_ERROR);
+}
+
+#[wasm_bindgen_test]
+fn texture_grid_is_row_major_and_truncated() {
+    use moon_engine::texture::Texture;
+    use std::rc::Rc;
+
+    let gl = get_context();
+    // 10x4 pixels split into 3x2 cells of 3x2 pixels, leaving a 1 pixel column
+    let texture = Rc::new(Texture::new_from_pixels(&gl, 10, 4, &[255; 160]));
+    let cells = texture.grid(3, 2);
+    assert_eq!(cells.len(), 6);
+
+    let uv = cells[4].get_uv_coords();
+    assert_eq!(uv[0], [0.3, 0.5]);
+    assert_eq!(uv[2], [0.6, 1.0]);
+    assert_eq!(texture.sub_texture(3, 2, 1, 1).get_uv_coords(), uv);
+}
+
+#[wasm_bindgen_test]
+fn particles_fall_age_out_and_render() {
+    use moon_engine::component::Component;
+    use moon_engine::particle::{ParticleProps, ParticleSystem};
+    use moon_engine::renderer::Renderer;
+    use moon_engine::Vec2;
+
+    let props = ParticleProps {
+        lifetime: 1.0,
+        burst_count: 0,
+        gravity: Vec2::new(0.0, 10.0),
+        ..Default::default()
+    };
+    let mut system = ParticleSystem::new_from_emission(props);
+    system.init();
+    system.emit_many(4);
+    assert_eq!(system.alive_count(), 4);
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.begin_draw();
+    system.render(&mut renderer);
+    renderer.end_draw();
+    assert_eq!(renderer.stats().quad_count, 4);
+
+    system.update(2.0);
+    assert_eq!(system.alive_count(), 0);
+}
+
+#[wasm_bindgen_test]
+fn application_tracks_injected_time() {
+    use moon_engine::Application;
+
+    let mut app = Application::new();
+    app.set_fixed_timestep(0.01);
+    app.render_with_delta(0.025);
+    app.render_with_delta(0.015);
+    assert_eq!(app.delta_time(), 0.015);
+    assert!((app.total_time() - 0.04).abs() < 1e-6);
+
+    // The first measured frame has no previous timestamp
+    app.render();
+    assert_eq!(app.delta_time(), 0.0);
+}
+
+#[wasm_bindgen_test]
+fn resize_scales_backing_size_by_pixel_ratio() {
+    use moon_engine::renderer::Renderer;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.resize_with_pixel_ratio(200.0, 100.0, 2.0);
+    assert_eq!(renderer.gl.drawing_buffer_width(), 400);
+    assert_eq!(renderer.gl.drawing_buffer_height(), 200);
+
+    // Screen coordinates stay in CSS pixels
+    let (x, _) = renderer.camera.screen_to_world_coordinates(100.0, 50.0);
+    assert!(x.abs() < 1e-4);
+}
+
+#[wasm_bindgen_test]
+fn pause_freezes_time_and_components() {
+    use moon_engine::particle::{ParticleProps, ParticleSystem};
+    use moon_engine::Application;
+
+    let mut app = Application::new();
+    let props = ParticleProps {
+        burst_count: 1,
+        ..Default::default()
+    };
+    let mut smoke = ParticleSystem::new_from_emission(props);
+    smoke.alive = true;
+    app.scene_mut().add_component("SMOKE", Box::new(smoke));
+
+    app.pause();
+    assert!(app.is_paused());
+    app.render_with_delta(0.1);
+    assert_eq!(app.total_time(), 0.0);
+    let smoke = app
+        .scene()
+        .get_component::<ParticleSystem>("SMOKE")
+        .unwrap();
+    assert_eq!(smoke.alive_count(), 0);
+
+    app.resume();
+    app.scene_mut().pause_component("SMOKE");
+    app.render_with_delta(0.1);
+    assert!((app.total_time() - 0.1).abs() < 1e-6);
+    assert!(app.scene().is_component_paused("SMOKE"));
+    let smoke = app
+        .scene()
+        .get_component::<ParticleSystem>("SMOKE")
+        .unwrap();
+    assert_eq!(smoke.alive_count(), 0);
+
+    app.scene_mut().resume_component("SMOKE");
+    app.render_with_delta(0.1);
+    let smoke = app
+        .scene()
+        .get_component::<ParticleSystem>("SMOKE")
+        .unwrap();
+    assert!(smoke.alive_count() > 0);
+}
+
+#[wasm_bindgen_test]
+fn clear_color_is_configurable() {
+    use moon_engine::gl::GL;
+    use moon_engine::{Application, Color32, DEFAULT_CLEAR_COLOR};
+
+    let mut app = Application::new();
+    assert_eq!(app.clear_color().to_array(), DEFAULT_CLEAR_COLOR.to_array());
+
+    app.set_clear_color(Color32::BLUE);
+    assert_eq!(app.clear_color().to_array(), Color32::BLUE.to_array());
+    assert!(app.set_clear_color_hex("not a color").is_err());
+    assert!(app.set_clear_color_hex("#ff0000").is_ok());
+    assert_eq!(app.clear_color().to_array(), Color32::RED.to_array());
+
+    app.render_with_delta(0.0);
+    let gl = get_context();
+    let clear = gl.get_parameter(GL::COLOR_CLEAR_VALUE).unwrap();
+    let clear = js_sys::Float32Array::from(clear).to_vec();
+    assert_eq!(clear, vec![1.0, 0.0, 0.0, 1.0]);
+}
+
+#[wasm_bindgen_test]
+fn depth_test_and_culling_toggle_mid_frame() {
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{CullMode, Quad, Renderer};
+
+    let gl = get_context();
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    assert!(!gl.is_enabled(GL::DEPTH_TEST));
+    assert!(!gl.is_enabled(GL::CULL_FACE));
+    renderer.draw_quads(&[Quad::default()]);
+
+    renderer.set_depth_test(true);
+    renderer.set_cull_face(Some(CullMode::Front));
+    assert!(gl.is_enabled(GL::DEPTH_TEST));
+    assert!(gl.is_enabled(GL::CULL_FACE));
+    assert_eq!(
+        gl.get_parameter(GL::CULL_FACE_MODE).unwrap().as_f64(),
+        Some(GL::FRONT as f64)
+    );
+    renderer.draw_quads(&[Quad::default()]);
+    renderer.end_frame();
+    assert!(gl.is_enabled(GL::DEPTH_TEST));
+
+    renderer.set_depth_test(false);
+    renderer.set_cull_face(None);
+    assert!(!gl.is_enabled(GL::DEPTH_TEST));
+    assert!(!gl.is_enabled(GL::CULL_FACE));
+    assert_eq!(gl.get_error(), GL::NO_ERROR);
+}
+
+#[wasm_bindgen_test]
+fn depth_is_cleared_after_toggling_mid_frame() {
+    use moon_engine::camera::Camera;
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{Quad, Renderer};
+    use moon_engine::{Color32, Vec3};
+
+    let draw_at_distance = |renderer: &mut Renderer, distance: f32, color: Color32| {
+        renderer
+            .camera
+            .transform
+            .set_position(Vec3::new(0.0, 0.0, distance));
+        renderer.set_depth_test(true);
+        renderer.begin_draw();
+        renderer.add_quad(&Quad::new_from_position_and_size_and_color(
+            0.0, 0.0, 100.0, 100.0, color,
+        ));
+        renderer.end_draw();
+        renderer.set_depth_test(false);
+    };
+
+    let mut renderer = Renderer::new_with_camera(Camera::new_perspective(1.0, 1.0, 0.1, 1000.0));
+    renderer.init_shader();
+    renderer.use_texture("WHITE");
+
+    // Depth testing is only enabled while drawing, and off when each frame is cleared
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    draw_at_distance(&mut renderer, 5.0, Color32(1.0, 0.0, 0.0, 1.0));
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    draw_at_distance(&mut renderer, 50.0, Color32(0.0, 1.0, 0.0, 1.0));
+
+    let gl = &renderer.gl;
+    let mut pixel = [0u8; 4];
+    gl.read_pixels_with_opt_u8_array(
+        gl.drawing_buffer_width() / 2,
+        gl.drawing_buffer_height() / 2,
+        1,
+        1,
+        GL::RGBA,
+        GL::UNSIGNED_BYTE,
+        Some(&mut pixel),
+    )
+    .unwrap();
+    assert_eq!(pixel, [0, 255, 0, 255]);
+}
+
+#[wasm_bindgen_test]
+fn framebuffer_has_depth_attachment() {
+    use moon_engine::framebuffer::Framebuffer;
+    use moon_engine::gl::{Bind, GL};
+
+    let gl = get_context();
+    let framebuffer = Framebuffer::new(&gl, 16, 8);
+    framebuffer.bind(&gl);
+    assert_eq!(
+        gl.check_framebuffer_status(GL::FRAMEBUFFER),
+        GL::FRAMEBUFFER_COMPLETE
+    );
+    let attachment = gl
+        .get_framebuffer_attachment_parameter(
+            GL::FRAMEBUFFER,
+            GL::DEPTH_ATTACHMENT,
+            GL::FRAMEBUFFER_ATTACHMENT_OBJECT_TYPE,
+        )
+        .unwrap();
+    assert_eq!(attachment.as_f64(), Some(GL::RENDERBUFFER as f64));
+    framebuffer.unbind(&gl);
+}
+
+#[wasm_bindgen_test]
+async fn restore_context_is_repeatable() {
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{Quad, Renderer};
+    use moon_engine::web::{handle_context_loss, Canvas};
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::WebglLoseContext;
+
+    // Resolve once the canvas receives an event
+    let next_event = |canvas: &Canvas, event: &str| {
+        let canvas = canvas.clone();
+        let event = event.to_string();
+        JsFuture::from(js_sys::Promise::new(&mut |resolve, _| {
+            canvas
+                .add_event_listener_with_callback(&event, &resolve)
+                .unwrap();
+        }))
+    };
+
+    let gl = get_context();
+    let canvas: Canvas = gl.canvas().unwrap().dyn_into().unwrap();
+    handle_context_loss(&canvas).unwrap();
+    let lose_context: WebglLoseContext = gl
+        .get_extension("WEBGL_lose_context")
+        .unwrap()
+        .unwrap()
+        .unchecked_into();
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.enable_tonemapping(1.5);
+    assert!(!renderer.is_context_lost());
+
+    for _ in 0..2 {
+        let lost = next_event(&canvas, "webglcontextlost");
+        lose_context.lose_context();
+        lost.await.unwrap();
+        assert!(renderer.is_context_lost());
+
+        let restored = next_event(&canvas, "webglcontextrestored");
+        lose_context.restore_context();
+        restored.await.unwrap();
+        assert!(!renderer.is_context_lost());
+        renderer.restore_context();
+    }
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.draw_quads(&[Quad::default()]);
+    renderer.end_frame();
+    assert_eq!(renderer.stats().post_passes, 1);
+    assert_eq!(gl.get_error(), GL::NO_ERROR);
+}
+
+#[wasm_bindgen_test]
+fn gl_check_consumes_errors_in_debug() {
+    use moon_engine::gl::{gl_check, GL};
+
+    let gl = get_context();
+    gl.enable(0xFFFF);
+    gl_check(&gl, "enabling an invalid capability");
+    if cfg!(debug_assertions) {
+        assert_eq!(gl.get_error(), GL::NO_ERROR);
+    } else {
+        assert_eq!(gl.get_error(), GL::INVALID_ENUM);
+    }
+}
+
+#[wasm_bindgen_test]
+fn instanced_sprites_drawn_in_one_call() {
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{InstanceData, Renderer};
+    use moon_engine::texture::SubTexture;
+
+    let gl = get_context();
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    let instances: Vec<InstanceData> = (0..1000)
+        .map(|index| InstanceData::new(index as f32 * 0.01, 0.0, 0.1, 0.1))
+        .collect();
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.draw_instanced(&SubTexture::default(), &instances);
+    let stats = renderer.stats();
+    assert_eq!(stats.draw_calls, 1);
+    assert_eq!(stats.quad_count, 1000);
+    assert_eq!(gl.get_error(), GL::NO_ERROR);
+}
+
+#[wasm_bindgen_test]
+fn instanced_sprites_drawn_over_batched_quads() {
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{InstanceData, Quad, Renderer};
+    use moon_engine::texture::SubTexture;
+    use moon_engine::Color32;
+
+    let gl = get_context();
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    renderer.use_texture("WHITE");
+    let green = InstanceData {
+        color: [0.0, 1.0, 0.0, 1.0],
+        ..InstanceData::new(0.0, 0.0, 100.0, 100.0)
+    };
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.begin_draw();
+    renderer.add_quad(&Quad::new_from_position_and_size_and_color(
+        0.0,
+        0.0,
+        100.0,
+        100.0,
+        Color32(1.0, 0.0, 0.0, 1.0),
+    ));
+    // The instance buffer grows, and is then reused for fewer instances
+    renderer.draw_instanced(&SubTexture::default(), &[green; 4]);
+    renderer.draw_instanced(&SubTexture::default(), &[green]);
+    renderer.end_draw();
+
+    let mut pixel = [0u8; 4];
+    gl.read_pixels_with_opt_u8_array(
+        gl.drawing_buffer_width() / 2,
+        gl.drawing_buffer_height() / 2,
+        1,
+        1,
+        GL::RGBA,
+        GL::UNSIGNED_BYTE,
+        Some(&mut pixel),
+    )
+    .unwrap();
+    assert_eq!(pixel, [0, 255, 0, 255]);
+    assert_eq!(gl.get_error(), GL::NO_ERROR);
+}
+
+#[wasm_bindgen_test]
+fn bind_material_flushes_batch_and_switches_program() {
+    use std::rc::Rc;
+
+    use moon_engine::gl::GL;
+    use moon_engine::material::Material;
+    use moon_engine::renderer::{Quad, Renderer};
+    use moon_engine::shader::Shader;
+
+    let gl = get_context();
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    let material = Rc::new(Material::new(Rc::new(Shader::new(&gl))).with_uniform("uTime", 1.0));
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    renderer.begin_draw();
+    renderer.add_quad(&Quad::default());
+    renderer.bind_material(Rc::clone(&material));
+    assert_eq!(renderer.stats().draw_calls, 1);
+    assert!(renderer.material().is_some());
+
+    renderer.add_quad(&Quad::default());
+    renderer.clear_material();
+    assert_eq!(renderer.stats().draw_calls, 2);
+    assert!(renderer.material().is_none());
+    assert_eq!(gl.get_error(), GL::NO_ERROR);
+}
+
+#[wasm_bindgen_test]
+fn resize_and_log_depth_update_material_shader() {
+    use std::rc::Rc;
+
+    use moon_engine::camera::Camera;
+    use moon_engine::gl::GL;
+    use moon_engine::material::Material;
+    use moon_engine::renderer::Renderer;
+    use moon_engine::shader::Shader;
+
+    let gl = get_context();
+    let mut renderer = Renderer::new_with_camera(Camera::new_perspective(1.0, 1.0, 0.1, 1000.0));
+    renderer.init_shader();
+    let shader = Rc::new(Shader::new(&gl));
+    renderer.bind_material(Rc::new(Material::new(Rc::clone(&shader))));
+    let program = shader.program().unwrap();
+    let uniform = |name: &str| {
+        let location = gl.get_uniform_location(program, name).unwrap();
+        gl.get_uniform(program, &location)
+    };
+
+    renderer.resize_with_pixel_ratio(200.0, 100.0, 2.0);
+    let projection = js_sys::Float32Array::from(uniform("uProj")).to_vec();
+    assert_eq!(
+        projection,
+        renderer.camera.projection_kind().as_matrix().as_slice()
+    );
+
+    renderer.set_log_depth(true);
+    assert_eq!(
+        uniform("uLogDepthFC").as_f64(),
+        Some(renderer.log_depth_coefficient() as f64)
+    );
+    assert_eq!(gl.get_parameter(GL::CURRENT_PROGRAM).unwrap(), ***program);
+    assert_eq!(gl.get_error(), GL::NO_ERROR);
+}
+
+#[wasm_bindgen_test]
+fn mouse_move_stores_pixels_for_ndc() {
+    use moon_engine::{Application, Vec2};
+
+    let mut app = Application::new();
+    app.resize(800, 600);
+    app.mouse_move(400, 150);
+    assert_eq!(app.input_manager().mouse_position, Vec2::new(400.0, 150.0));
+    assert_eq!(
+        app.input_manager().mouse_ndc(800.0, 600.0),
+        Vec2::new(0.0, 0.5)
+    );
+    let (x, y) = app
+        .renderer()
+        .unwrap()
+        .camera
+        .screen_to_world_coordinates(400.0, 150.0);
+    assert_eq!(app.mouse_world_position(), Some(Vec2::new(x, y)));
+}
+
+#[wasm_bindgen_test]
+fn log_depth_keeps_nearer_sprite() {
+    use moon_engine::camera::Camera;
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::{Quad, Renderer};
+    use moon_engine::{Color32, Vec3};
+
+    let draw_at_distance = |renderer: &mut Renderer, distance: f32, color: Color32| {
+        renderer
+            .camera
+            .transform
+            .set_position(Vec3::new(0.0, 0.0, distance));
+        renderer.begin_draw();
+        renderer.add_quad(&Quad::new_from_position_and_size_and_color(
+            0.0, 0.0, 100.0, 100.0, color,
+        ));
+        renderer.end_draw();
+    };
+    let center_pixel = |gl: &GL| {
+        let mut pixel = [0u8; 4];
+        gl.read_pixels_with_opt_u8_array(
+            gl.drawing_buffer_width() / 2,
+            gl.drawing_buffer_height() / 2,
+            1,
+            1,
+            GL::RGBA,
+            GL::UNSIGNED_BYTE,
+            Some(&mut pixel),
+        )
+        .unwrap();
+        pixel
+    };
+
+    let mut renderer = Renderer::new_with_camera(Camera::new_perspective(1.0, 1.0, 0.1, 1000.0));
+    renderer.init_shader();
+    renderer.set_log_depth(true);
+    renderer.set_depth_test(true);
+    renderer.use_texture("WHITE");
+    let red = Color32(1.0, 0.0, 0.0, 1.0);
+    let green = Color32(0.0, 1.0, 0.0, 1.0);
+
+    // The farther sprite is drawn last, and is hidden by the nearer one
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    draw_at_distance(&mut renderer, 5.0, red);
+    draw_at_distance(&mut renderer, 50.0, green);
+    assert_eq!(center_pixel(&renderer.gl), [255, 0, 0, 255]);
+
+    // The nearer sprite is drawn last, and passes the depth test
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    draw_at_distance(&mut renderer, 50.0, green);
+    draw_at_distance(&mut renderer, 5.0, red);
+    assert_eq!(center_pixel(&renderer.gl), [255, 0, 0, 255]);
+    assert_eq!(renderer.gl.get_error(), GL::NO_ERROR);
+}
+
+#[wasm_bindgen_test]
+fn outline_extends_beyond_sprite() {
+    use moon_engine::collider::AABB;
+    use moon_engine::gl::GL;
+    use moon_engine::renderer::Renderer;
+    use moon_engine::texture::{SubTexture, Texture};
+    use moon_engine::{Color32, Vec2, Vec3};
+    use std::rc::Rc;
+
+    let mut renderer = Renderer::default();
+    renderer.init_shader();
+    let gl = renderer.gl.clone();
+    let sprite = SubTexture::new(Rc::new(Texture::new_from_pixels(&gl, 4, 4, &[255; 64])));
+    let pixel_at = |renderer: &Renderer, world_x: f32| {
+        let viewport = Vec2::new(
+            gl.drawing_buffer_width() as f32,
+            gl.drawing_buffer_height() as f32,
+        );
+        let screen = renderer
+            .camera
+            .world_to_screen(Vec3::new(world_x, 0.0, 0.0), viewport)
+            .unwrap();
+        let mut pixel = [0u8; 4];
+        gl.read_pixels_with_opt_u8_array(
+            screen.x as i32,
+            (viewport.y - screen.y) as i32,
+            1,
+            1,
+            GL::RGBA,
+            GL::UNSIGNED_BYTE,
+            Some(&mut pixel),
+        )
+        .unwrap();
+        pixel
+    };
+
+    renderer.begin_frame([0.0, 0.0, 0.0, 1.0]);
+    // One texel of a 4 texel wide sprite that is 4 units wide pads it by 1 unit
+    renderer.draw_sprite_outlined(
+        &sprite,
+        &AABB::new_size(4.0, 4.0),
+        Color32(1.0, 0.0, 0.0, 1.0),
+        1.0,
+    );
+    assert_eq!(pixel_at(&renderer, 0.0), [255, 255, 255, 255]);
+    assert_eq!(pixel_at(&renderer, 2.5), [255, 0, 0, 255]);
+    assert_eq!(pixel_at(&renderer, 3.5), [0, 0, 0, 255]);
+    assert_eq!(gl.get_error(), GL::NO_ERROR);
+}
@@ -6,6 +6,10 @@ const app = new wasm.Application();
 
 const FPS_LIMIT = 1000.0 / 30.0;
 let lastDrawTime = -1;
+// The CSS size of the canvas and the pixel ratio it was last resized with.
+let canvasWidth = 0;
+let canvasHeight = 0;
+let pixelRatio = 0;
 
 function init() {
     if (!gl) {
@@ -14,17 +18,29 @@ function init() {
     }
     app.init();
 
+    // `Application::init` prevents the default of `webglcontextlost`, so the context can be restored.
+    canvas.addEventListener("webglcontextrestored", () => {
+        app.on_context_restored();
+    }, false);
     canvas.addEventListener("keydown", event => {
-        app.input(event.which, true);
+        app.input_code(event.code, true);
     });
     canvas.addEventListener("keyup", event => {
-        app.input(event.which, false);
+        app.input_code(event.code, false);
     });
     canvas.addEventListener("mousemove", event => {
         app.mouse_move(event.clientX, event.clientY);
     }, false);
+    canvas.addEventListener("wheel", event => {
+        app.mouse_scroll(event.deltaY);
+    }, { passive: true });
+    canvas.addEventListener("mousedown", event => {
+        app.mouse_button(event.button, true);
+    }, false);
+    canvas.addEventListener("mouseup", event => {
+        app.mouse_button(event.button, false);
+    }, false);
 
-    app.resize(window.innerWidth, window.innerHeight);
     function render() {
         window.requestAnimationFrame(render);
         let currentTime = performance.now();
@@ -32,15 +48,14 @@ function init() {
         if (deltaTime >= 0) { // Ignoring FPS_LIMIT for now
             lastDrawTime = currentTime;
 
-            if (canvas.height != window.innerHeight || canvas.width != window.innerWidth) {
-                canvas.height = window.innerHeight;
-                canvas.style.height = window.innerHeight;
-                
-                canvas.width = window.innerWidth;
-                canvas.style.width = window.innerWidth;
-                app.resize(window.innerWidth, window.innerHeight);
+            // The canvas is sized by CSS, and `app.resize` sizes its backing store by the pixel ratio.
+            if (canvas.clientWidth != canvasWidth || canvas.clientHeight != canvasHeight || window.devicePixelRatio != pixelRatio) {
+                canvasWidth = canvas.clientWidth;
+                canvasHeight = canvas.clientHeight;
+                pixelRatio = window.devicePixelRatio;
+                app.resize(canvasWidth, canvasHeight);
             }
-            app.render(deltaTime);
+            app.render();
             counter.innerText = Math.round(1000/deltaTime);
         }
     }